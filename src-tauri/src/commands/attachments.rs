@@ -0,0 +1,322 @@
+// Attachment commands (local files attached to todos, tasks, plans and targets)
+
+use crate::log_command;
+use crate::models::Attachment;
+use crate::AppState;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri_plugin_opener::OpenerExt;
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentGcResult {
+    pub removed_records: usize,
+    pub removed_files: usize,
+    pub freed_bytes: u64,
+}
+
+/// A file copied into the attachments dir.
+pub struct StoredFile {
+    pub stored_name: String,
+    pub content_hash: String,
+    pub size: i64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Directory where attachment files are stored.
+pub fn attachments_dir() -> PathBuf {
+    crate::db::data_dir().join("attachments")
+}
+
+/// Checks that a stored name is `<sha256 hex>[.<ext>]` as written by
+/// `store_file`, so joining it to the attachments dir stays inside it.
+pub fn is_valid_stored_name(name: &str) -> bool {
+    let (hash, ext) = match name.split_once('.') {
+        Some((hash, ext)) => (hash, Some(ext)),
+        None => (name, None),
+    };
+    let hash_ok = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+    let ext_ok =
+        ext.is_none_or(|ext| !ext.is_empty() && !ext.contains(['.', '/', '\\', ':', '\0']));
+    hash_ok && ext_ok
+}
+
+/// Path of a stored file in `dir`; rejects names that could escape it.
+pub fn stored_path(dir: &Path, stored_name: &str) -> Result<PathBuf, String> {
+    if !is_valid_stored_name(stored_name) {
+        return Err(format!("Invalid attachment file name: {}", stored_name));
+    }
+    Ok(dir.join(stored_name))
+}
+
+fn entity_table(entity_type: &str) -> Result<&'static str, String> {
+    match entity_type {
+        "todo" => Ok("todos"),
+        "task" => Ok("tasks"),
+        "plan" => Ok("plans"),
+        "target" => Ok("targets"),
+        _ => Err("Invalid entity_type. Use 'todo', 'task', 'plan' or 'target'".to_string()),
+    }
+}
+
+/// Copies a file into `dir`, named by its SHA-256 so identical content is
+/// only stored once. The extension is kept so the OS can pick an app to open it.
+pub fn store_file(dir: &Path, source: &Path) -> Result<StoredFile, String> {
+    let bytes = std::fs::read(source).map_err(|e| format!("Failed to read file: {}", e))?;
+    let content_hash = format!("{:x}", Sha256::digest(&bytes));
+
+    let stored_name = match source.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}.{}", content_hash, ext.to_lowercase()),
+        None => content_hash.clone(),
+    };
+
+    let dest = dir.join(&stored_name);
+    if !dest.exists() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        std::fs::write(&dest, &bytes).map_err(|e| format!("Failed to store file: {}", e))?;
+    }
+
+    Ok(StoredFile {
+        stored_name,
+        content_hash,
+        size: bytes.len() as i64,
+    })
+}
+
+fn get_attachment_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Attachment, String> {
+    conn.query_row(
+        "SELECT id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at FROM attachments WHERE id = ?",
+        [id],
+        |row| {
+            Ok(Attachment {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                file_name: row.get(3)?,
+                stored_name: row.get(4)?,
+                content_hash: row.get(5)?,
+                size: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Deletes the stored file if no attachment row references it any more.
+fn remove_file_if_unreferenced(
+    conn: &rusqlite::Connection,
+    dir: &Path,
+    stored_name: &str,
+) -> Result<bool, String> {
+    let referenced: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM attachments WHERE stored_name = ?)",
+            [stored_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    if referenced {
+        return Ok(false);
+    }
+
+    match std::fs::remove_file(stored_path(dir, stored_name)?) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Removes attachment rows whose entity no longer exists, then files in `dir`
+/// that no row references.
+pub fn collect_garbage(
+    conn: &rusqlite::Connection,
+    dir: &Path,
+) -> Result<AttachmentGcResult, String> {
+    let removed_records = conn
+        .execute(
+            "DELETE FROM attachments WHERE
+                (entity_type = 'todo' AND entity_id NOT IN (SELECT id FROM todos))
+                OR (entity_type = 'task' AND entity_id NOT IN (SELECT id FROM tasks))
+                OR (entity_type = 'plan' AND entity_id NOT IN (SELECT id FROM plans))
+                OR (entity_type = 'target' AND entity_id NOT IN (SELECT id FROM targets))",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT stored_name FROM attachments")
+        .map_err(|e| e.to_string())?;
+    let referenced: HashSet<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut removed_files = 0usize;
+    let mut freed_bytes = 0u64;
+
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if referenced.contains(&name) || !entry.path().is_file() {
+                continue;
+            }
+
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            std::fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+            removed_files += 1;
+            freed_bytes += size;
+        }
+    }
+
+    Ok(AttachmentGcResult {
+        removed_records,
+        removed_files,
+        freed_bytes,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn add_attachment(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+    file_path: String,
+) -> Result<Attachment, String> {
+    log_command!("add_attachment", {
+        let table = entity_table(&entity_type)?;
+        let source = Path::new(&file_path);
+        if !source.is_file() {
+            return Err(format!("File not found: {}", file_path));
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let exists: bool = conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+                [&entity_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        let stored = store_file(&attachments_dir(), source)?;
+        let file_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| stored.stored_name.clone());
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO attachments (id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, entity_type, entity_id, file_name, stored.stored_name, stored.content_hash, stored.size, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Attachment {
+            id,
+            entity_type,
+            entity_id,
+            file_name,
+            stored_name: stored.stored_name,
+            content_hash: stored.content_hash,
+            size: stored.size,
+            created_at: now,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn get_attachments(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<Attachment>, String> {
+    log_command!("get_attachments", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at
+                 FROM attachments WHERE entity_type = ? AND entity_id = ?
+                 ORDER BY created_at",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let attachments = stmt
+            .query_map(rusqlite::params![entity_type, entity_id], |row| {
+                Ok(Attachment {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    entity_id: row.get(2)?,
+                    file_name: row.get(3)?,
+                    stored_name: row.get(4)?,
+                    content_hash: row.get(5)?,
+                    size: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(attachments.filter_map(|a| a.ok()).collect())
+    })
+}
+
+#[tauri::command]
+pub fn open_attachment(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    id: String,
+) -> Result<(), String> {
+    log_command!("open_attachment", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let attachment = get_attachment_by_id(&conn, &id)?;
+
+        let path = stored_path(&attachments_dir(), &attachment.stored_name)?;
+        if !path.is_file() {
+            return Err(format!("Attachment file missing: {}", attachment.file_name));
+        }
+
+        app.opener()
+            .open_path(path.to_string_lossy().to_string(), None::<&str>)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub fn remove_attachment(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("remove_attachment", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let attachment = get_attachment_by_id(&conn, &id)?;
+
+        conn.execute("DELETE FROM attachments WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+
+        remove_file_if_unreferenced(&conn, &attachments_dir(), &attachment.stored_name)?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn gc_attachments(state: tauri::State<AppState>) -> Result<AttachmentGcResult, String> {
+    log_command!("gc_attachments", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        collect_garbage(&conn, &attachments_dir())
+    })
+}
@@ -1,5 +1,14 @@
 // Batch operations commands
 
+use super::burndown::{record_status_change, step_category};
+use super::comments::delete_entity_comments;
+use super::dependencies::{check_can_start, delete_entity_dependencies, notify_unblocked};
+use super::plans::refresh_task_plan_progress;
+use super::pomodoro::delete_entity_pomodoro_sessions;
+use super::step_links::{delete_entity_step_links, sync_linked_steps};
+use super::subtasks::{delete_task_subtree, rollup_ancestors};
+use super::time_tracking::delete_entity_time_entries;
+use super::workflows::{default_status, status_category, validate_transition};
 use crate::log_command;
 use crate::AppState;
 use serde::Serialize;
@@ -18,25 +27,49 @@ pub struct BatchFailedItem {
 
 #[tauri::command]
 pub fn bulk_update_todo_status(
+    app: tauri::AppHandle,
     state: tauri::State<AppState>,
     ids: Vec<String>,
     status: String,
+    force: Option<bool>,
 ) -> Result<BatchUpdateResult, String> {
     log_command!("bulk_update_todo_status", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
+        let force = force.unwrap_or(false);
 
         let mut updated = 0;
         let mut failed: Vec<BatchFailedItem> = Vec::new();
 
         for id in ids {
+            let old_status: Option<String> = conn
+                .query_row("SELECT status FROM todos WHERE id = ?", [&id], |row| {
+                    row.get(0)
+                })
+                .ok();
+
+            if old_status.as_deref() != Some(status.as_str()) {
+                if let Err(e) = check_can_start(&conn, "todo", &id, &status, force) {
+                    failed.push(BatchFailedItem { id, error: e });
+                    continue;
+                }
+            }
+
             let result = conn.execute(
                 "UPDATE todos SET status = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![status, now, id],
             );
 
             match result {
-                Ok(rows) if rows > 0 => updated += 1,
+                Ok(rows) if rows > 0 => {
+                    updated += 1;
+                    if status == "done" && old_status.as_deref() != Some("done") {
+                        notify_unblocked(&app, &conn, "todo", &id)?;
+                    }
+                    if old_status.as_deref() != Some(status.as_str()) {
+                        sync_linked_steps(&conn, "todo", &id)?;
+                    }
+                }
                 Ok(_) => failed.push(BatchFailedItem {
                     id,
                     error: "Not found".to_string(),
@@ -54,25 +87,71 @@ pub fn bulk_update_todo_status(
 
 #[tauri::command]
 pub fn bulk_update_task_status(
+    app: tauri::AppHandle,
     state: tauri::State<AppState>,
     ids: Vec<String>,
     status: String,
+    force: Option<bool>,
 ) -> Result<BatchUpdateResult, String> {
     log_command!("bulk_update_task_status", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
+        let force = force.unwrap_or(false);
 
         let mut updated = 0;
         let mut failed: Vec<BatchFailedItem> = Vec::new();
 
         for id in ids {
+            let found: Option<(String, String)> = conn
+                .query_row(
+                    "SELECT plan_id, status FROM tasks WHERE id = ?",
+                    [&id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .ok();
+            let Some((plan_id, old_status)) = found else {
+                failed.push(BatchFailedItem {
+                    id,
+                    error: "Not found".to_string(),
+                });
+                continue;
+            };
+            let old_category = status_category(&conn, &plan_id, &old_status)?;
+            let new_category = status_category(&conn, &plan_id, &status)?;
+
+            if old_status != status {
+                let allowed =
+                    validate_transition(&conn, &plan_id, &old_status, &status).and_then(|_| {
+                        check_can_start(&conn, "task", &id, default_status(&new_category), force)
+                    });
+                if let Err(e) = allowed {
+                    failed.push(BatchFailedItem { id, error: e });
+                    continue;
+                }
+            }
+
             let result = conn.execute(
                 "UPDATE tasks SET status = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![status, now, id],
             );
 
             match result {
-                Ok(rows) if rows > 0 => updated += 1,
+                Ok(rows) if rows > 0 => {
+                    updated += 1;
+                    record_status_change(
+                        &conn,
+                        "task",
+                        &id,
+                        Some(&old_status),
+                        &status,
+                        &new_category,
+                    )?;
+                    if new_category == "done" && old_category != "done" {
+                        notify_unblocked(&app, &conn, "task", &id)?;
+                    }
+                    rollup_ancestors(&conn, &id)?;
+                    refresh_task_plan_progress(&conn, &id)?;
+                }
                 Ok(_) => failed.push(BatchFailedItem {
                     id,
                     error: "Not found".to_string(),
@@ -102,13 +181,28 @@ pub fn bulk_update_step_status(
         let mut failed: Vec<BatchFailedItem> = Vec::new();
 
         for id in ids {
+            let old_status: Option<String> = conn
+                .query_row("SELECT status FROM steps WHERE id = ?", [&id], |row| {
+                    row.get(0)
+                })
+                .ok();
             let result = conn.execute(
                 "UPDATE steps SET status = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![status, now, id],
             );
 
             match result {
-                Ok(rows) if rows > 0 => updated += 1,
+                Ok(rows) if rows > 0 => {
+                    updated += 1;
+                    record_status_change(
+                        &conn,
+                        "step",
+                        &id,
+                        old_status.as_deref(),
+                        &status,
+                        step_category(&status),
+                    )?;
+                }
                 Ok(_) => failed.push(BatchFailedItem {
                     id,
                     error: "Not found".to_string(),
@@ -139,7 +233,14 @@ pub fn bulk_delete_todos(
             let result = conn.execute("DELETE FROM todos WHERE id = ?", [&id]);
 
             match result {
-                Ok(rows) if rows > 0 => deleted += 1,
+                Ok(rows) if rows > 0 => {
+                    deleted += 1;
+                    delete_entity_dependencies(&conn, "todo", &id)?;
+                    delete_entity_comments(&conn, "todo", &id)?;
+                    delete_entity_step_links(&conn, "todo", &id)?;
+                    delete_entity_time_entries(&conn, "todo", &id)?;
+                    delete_entity_pomodoro_sessions(&conn, "todo", &id)?;
+                }
                 Ok(_) => failed.push(BatchFailedItem {
                     id,
                     error: "Not found".to_string(),
@@ -170,11 +271,10 @@ pub fn bulk_delete_tasks(
         let mut failed: Vec<BatchFailedItem> = Vec::new();
 
         for id in ids {
-            let result = conn.execute("DELETE FROM tasks WHERE id = ?", [&id]);
-
-            match result {
-                Ok(rows) if rows > 0 => deleted += 1,
-                Ok(_) => failed.push(BatchFailedItem {
+            // Subtasks go with their parent
+            match delete_task_subtree(&conn, &id) {
+                Ok(true) => deleted += 1,
+                Ok(false) => failed.push(BatchFailedItem {
                     id,
                     error: "Not found".to_string(),
                 }),
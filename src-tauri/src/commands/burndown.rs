@@ -0,0 +1,387 @@
+// Burndown commands (daily remaining / completed work of plans and targets)
+//
+// Task and step status changes are recorded in status_history with the
+// category they moved to, so a series knows when work became done. Items
+// without recorded changes count as done since their last update.
+
+use super::plans::priority_weight;
+use super::schedule::parse_day;
+use super::workflows::{status_category, task_category_sql};
+use crate::log_command;
+use crate::AppState;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Longest series a burndown or burnup covers, in days.
+const MAX_SERIES_DAYS: i64 = 3660;
+
+#[derive(Debug, Serialize)]
+pub struct BurndownPoint {
+    pub date: String,
+    pub total: Option<i64>, // None for days after today
+    pub completed: Option<i64>,
+    pub remaining: Option<i64>,
+    pub ideal: f64, // Remaining work (burndown) or completed work (burnup) on schedule
+}
+
+#[derive(Debug, Serialize)]
+pub struct Burndown {
+    pub entity_id: String,
+    pub unit: String, // tasks | priority (weighted plans) | weight (target steps)
+    pub start_date: String,
+    pub end_date: String,
+    pub points: Vec<BurndownPoint>,
+}
+
+/// A unit of work: a leaf task or a step.
+struct WorkItem {
+    created: NaiveDate,
+    weight: i64,
+    done_before: bool, // State before the first recorded change
+    changes: Vec<(NaiveDate, bool)>,
+}
+
+impl WorkItem {
+    fn done_on(&self, day: NaiveDate) -> bool {
+        self.changes
+            .iter()
+            .rev()
+            .find(|(changed, _)| *changed <= day)
+            .map(|(_, done)| *done)
+            .unwrap_or(self.done_before)
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Records a task or step status change. `category` is the category of
+/// `to_status` (steps: done when completed).
+pub fn record_status_change(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+    from_status: Option<&str>,
+    to_status: &str,
+    category: &str,
+) -> Result<(), String> {
+    if from_status == Some(to_status) {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO status_history (id, entity_type, entity_id, from_status, to_status, category, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            entity_type,
+            entity_id,
+            from_status,
+            to_status,
+            category,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn delete_status_history(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM status_history WHERE entity_type = ? AND entity_id = ?",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Category of a step status.
+pub fn step_category(status: &str) -> &'static str {
+    if status == "completed" {
+        "done"
+    } else {
+        "todo"
+    }
+}
+
+/// Recorded changes of an entity as (day, done) and the first from_status.
+fn load_changes(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(Vec<(NaiveDate, bool)>, Option<String>), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT changed_at, category, from_status FROM status_history
+             WHERE entity_type = ? AND entity_id = ? ORDER BY changed_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, String, Option<String>)> = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let first_from = rows.first().and_then(|(_, _, from)| from.clone());
+    let changes = rows
+        .into_iter()
+        .filter_map(|(at, category, _)| parse_day(&at).map(|day| (day, category == "done")))
+        .collect();
+    Ok((changes, first_from))
+}
+
+/// Builds a work item from its current state and recorded history.
+fn work_item(
+    created_at: &str,
+    updated_at: &str,
+    weight: i64,
+    done_now: bool,
+    changes: Vec<(NaiveDate, bool)>,
+    done_before: bool,
+    today: NaiveDate,
+) -> WorkItem {
+    let created = parse_day(created_at).unwrap_or(today);
+    if changes.is_empty() && done_now {
+        let done_day = parse_day(updated_at).unwrap_or(created);
+        return WorkItem {
+            created,
+            weight,
+            done_before: false,
+            changes: vec![(done_day, true)],
+        };
+    }
+    WorkItem {
+        created,
+        weight,
+        done_before,
+        changes,
+    }
+}
+
+/// Daily series from `start` until `end`, or later when work changed after
+/// the end (at most until today). `burnup` flips the ideal line to count up.
+fn build_series(
+    items: &[WorkItem],
+    start: NaiveDate,
+    end: NaiveDate,
+    today: NaiveDate,
+    burnup: bool,
+) -> Result<Vec<BurndownPoint>, String> {
+    let end = end.max(start);
+    let last_activity = items
+        .iter()
+        .flat_map(|item| {
+            std::iter::once(item.created).chain(item.changes.iter().map(|(day, _)| *day))
+        })
+        .max()
+        .unwrap_or(start);
+    let last = end.max(last_activity.min(today));
+    if (last - start).num_days() >= MAX_SERIES_DAYS {
+        return Err(format!("Burndown is limited to {} days", MAX_SERIES_DAYS));
+    }
+    let span = (end - start).num_days().max(1) as f64;
+    let scope: i64 = items.iter().map(|item| item.weight).sum();
+
+    let mut points = Vec::new();
+    let mut day = start;
+    while day <= last {
+        let elapsed = ((day - start).num_days() as f64 / span).min(1.0);
+        let ideal = if burnup {
+            scope as f64 * elapsed
+        } else {
+            scope as f64 * (1.0 - elapsed)
+        };
+        let (total, completed) = if day > today {
+            (None, None)
+        } else {
+            let existing = items.iter().filter(|item| item.created <= day);
+            let total: i64 = existing.clone().map(|item| item.weight).sum();
+            let completed: i64 = existing
+                .filter(|item| item.done_on(day))
+                .map(|item| item.weight)
+                .sum();
+            (Some(total), Some(completed))
+        };
+        points.push(BurndownPoint {
+            date: day.format("%Y-%m-%d").to_string(),
+            total,
+            completed,
+            remaining: total.zip(completed).map(|(t, c)| t - c),
+            ideal: (ideal * 100.0).round() / 100.0,
+        });
+        day += chrono::Duration::days(1);
+    }
+    Ok(points)
+}
+
+/// Burndown of a plan's leaf tasks, weighted by priority when the plan uses
+/// weighted progress.
+pub fn plan_burndown(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    today: NaiveDate,
+) -> Result<Burndown, String> {
+    let (start_date, end_date, weighted, created_at): (
+        Option<String>,
+        Option<String>,
+        bool,
+        String,
+    ) = conn
+        .query_row(
+            "SELECT start_date, end_date, weighted_progress, created_at FROM plans WHERE id = ?",
+            [plan_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|_| "Plan not found".to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, priority, {} = 'done', created_at, updated_at FROM tasks t WHERE plan_id = ?
+             AND NOT EXISTS(SELECT 1 FROM tasks c WHERE c.parent_task_id = t.id)",
+            task_category_sql("t")
+        ))
+        .map_err(|e| e.to_string())?;
+    let tasks: Vec<(String, String, bool, String, String)> = stmt
+        .query_map([plan_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut items = Vec::new();
+    for (id, priority, done_now, task_created, task_updated) in tasks {
+        let (changes, first_from) = load_changes(conn, "task", &id)?;
+        let done_before = match first_from {
+            Some(status) => status_category(conn, plan_id, &status)? == "done",
+            None => false,
+        };
+        let weight = if weighted {
+            priority_weight(&priority)
+        } else {
+            1
+        };
+        items.push(work_item(
+            &task_created,
+            &task_updated,
+            weight,
+            done_now,
+            changes,
+            done_before,
+            today,
+        ));
+    }
+
+    let start = start_date
+        .as_deref()
+        .and_then(parse_day)
+        .or_else(|| items.iter().map(|item| item.created).min())
+        .or_else(|| parse_day(&created_at))
+        .unwrap_or(today);
+    let end = end_date.as_deref().and_then(parse_day).unwrap_or(today);
+
+    Ok(Burndown {
+        entity_id: plan_id.to_string(),
+        unit: if weighted { "priority" } else { "tasks" }.to_string(),
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.max(start).format("%Y-%m-%d").to_string(),
+        points: build_series(&items, start, end, today, false)?,
+    })
+}
+
+/// Burnup of a target's step weights from its creation to its due date.
+pub fn target_burnup(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+    today: NaiveDate,
+) -> Result<Burndown, String> {
+    let (due_date, created_at): (Option<String>, String) = conn
+        .query_row(
+            "SELECT due_date, created_at FROM targets WHERE id = ?",
+            [target_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Target not found".to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, weight, status, created_at, updated_at FROM steps WHERE target_id = ?")
+        .map_err(|e| e.to_string())?;
+    let steps: Vec<(String, i64, String, String, String)> = stmt
+        .query_map([target_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut items = Vec::new();
+    for (id, weight, status, step_created, step_updated) in steps {
+        let (changes, first_from) = load_changes(conn, "step", &id)?;
+        let done_before = first_from.is_some_and(|s| step_category(&s) == "done");
+        items.push(work_item(
+            &step_created,
+            &step_updated,
+            weight,
+            step_category(&status) == "done",
+            changes,
+            done_before,
+            today,
+        ));
+    }
+
+    let start = items
+        .iter()
+        .map(|item| item.created)
+        .chain(parse_day(&created_at))
+        .min()
+        .unwrap_or(today);
+    let end = due_date.as_deref().and_then(parse_day).unwrap_or(today);
+
+    Ok(Burndown {
+        entity_id: target_id.to_string(),
+        unit: "weight".to_string(),
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.max(start).format("%Y-%m-%d").to_string(),
+        points: build_series(&items, start, end, today, true)?,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_burndown(state: tauri::State<AppState>, plan_id: String) -> Result<Burndown, String> {
+    log_command!("get_burndown", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        plan_burndown(&conn, &plan_id, chrono::Local::now().date_naive())
+    })
+}
+
+#[tauri::command]
+pub fn get_target_burnup(
+    state: tauri::State<AppState>,
+    target_id: String,
+) -> Result<Burndown, String> {
+    log_command!("get_target_burnup", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        target_burnup(&conn, &target_id, chrono::Local::now().date_naive())
+    })
+}
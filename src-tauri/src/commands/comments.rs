@@ -0,0 +1,181 @@
+// Comment commands (append-only notes timeline on todos, tasks, plans and targets)
+
+use crate::log_command;
+use crate::models::Comment;
+use crate::AppState;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+pub fn entity_table(entity_type: &str) -> Result<&'static str, String> {
+    match entity_type {
+        "todo" => Ok("todos"),
+        "task" => Ok("tasks"),
+        "plan" => Ok("plans"),
+        "target" => Ok("targets"),
+        _ => Err("Invalid entity_type. Use 'todo', 'task', 'plan' or 'target'".to_string()),
+    }
+}
+
+fn get_comment_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Comment, String> {
+    conn.query_row(
+        "SELECT id, entity_type, entity_id, body, created_at, edited_at FROM comments WHERE id = ?",
+        [id],
+        |row| {
+            Ok(Comment {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                body: row.get(3)?,
+                created_at: row.get(4)?,
+                edited_at: row.get(5)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the comments of an entity, oldest first.
+pub fn list_comments(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Vec<Comment>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, entity_type, entity_id, body, created_at, edited_at
+             FROM comments WHERE entity_type = ? AND entity_id = ?
+             ORDER BY created_at",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let comments = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| {
+            Ok(Comment {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                body: row.get(3)?,
+                created_at: row.get(4)?,
+                edited_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(comments.filter_map(|c| c.ok()).collect())
+}
+
+/// Removes all comments of the entity.
+pub fn delete_entity_comments(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM comments WHERE entity_type = ? AND entity_id = ?",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn add_comment(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+    body: String,
+) -> Result<Comment, String> {
+    log_command!("add_comment", {
+        let table = entity_table(&entity_type)?;
+        if body.trim().is_empty() {
+            return Err("Comment cannot be empty".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let exists: bool = conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+                [&entity_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO comments (id, entity_type, entity_id, body, created_at, edited_at) VALUES (?, ?, ?, ?, ?, NULL)",
+            rusqlite::params![id, entity_type, entity_id, body, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Comment {
+            id,
+            entity_type,
+            entity_id,
+            body,
+            created_at: now,
+            edited_at: None,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn get_comments(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<Comment>, String> {
+    log_command!("get_comments", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        list_comments(&conn, &entity_type, &entity_id)
+    })
+}
+
+#[tauri::command]
+pub fn update_comment(
+    state: tauri::State<AppState>,
+    id: String,
+    body: String,
+) -> Result<Comment, String> {
+    log_command!("update_comment", {
+        if body.trim().is_empty() {
+            return Err("Comment cannot be empty".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let rows = conn
+            .execute(
+                "UPDATE comments SET body = ?, edited_at = ? WHERE id = ?",
+                rusqlite::params![body, now, id],
+            )
+            .map_err(|e| e.to_string())?;
+        if rows == 0 {
+            return Err(format!("Comment {} not found", id));
+        }
+
+        get_comment_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_comment(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_comment", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM comments WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
@@ -1,5 +1,7 @@
 // Dashboard API for today overview
 
+use super::forecast::forecast;
+use super::workflows::task_category_sql;
 use crate::log_command;
 use crate::AppState;
 use chrono::Datelike;
@@ -85,6 +87,8 @@ pub struct TargetWithProgress {
     pub title: String,
     pub progress: i32,
     pub due_date: Option<String>,
+    pub forecast_status: String, // on_track | at_risk | off_track | unknown
+    pub estimated_completion: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -169,6 +173,7 @@ fn get_today_todos(conn: &rusqlite::Connection, today: &str) -> Result<Vec<TodoS
             FROM todos 
             WHERE (status = 'pending' OR status = 'in-progress')
             AND date(due_date) <= date(?)
+            AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))
             ORDER BY due_date",
         )
         .map_err(|e| e.to_string())?;
@@ -195,8 +200,9 @@ fn get_overdue_todos(conn: &rusqlite::Connection, today: &str) -> Result<Vec<Tod
         .prepare(
             "SELECT id, title, due_date, status, COALESCE(priority, 'P2') as priority
             FROM todos 
-            WHERE status != 'done'
+            WHERE status NOT IN ('done', 'archived')
             AND date(due_date) < date(?)
+            AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))
             ORDER BY due_date DESC",
         )
         .map_err(|e| e.to_string())?;
@@ -229,6 +235,7 @@ fn get_upcoming_todos(
             WHERE status = 'pending'
             AND date(due_date) > date(?)
             AND date(due_date) <= date(?, '+3 days')
+            AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))
             ORDER BY due_date",
         )
         .map_err(|e| e.to_string())?;
@@ -258,7 +265,7 @@ fn get_completed_today(
         .prepare(
             "SELECT id, title, due_date, status, COALESCE(priority, 'P2') as priority
             FROM todos 
-            WHERE status = 'done'
+            WHERE status IN ('done', 'archived')
             AND date(updated_at) = date(?)
             ORDER BY updated_at DESC",
         )
@@ -285,7 +292,7 @@ fn get_week_completed(conn: &rusqlite::Connection) -> Result<i32, String> {
     let count: i32 = conn
         .query_row(
             "SELECT COUNT(*) FROM todos 
-            WHERE status = 'done' 
+            WHERE status IN ('done', 'archived') 
             AND date(updated_at) >= date('now', '-7 days', 'localtime')",
             [],
             |row| row.get(0),
@@ -327,33 +334,29 @@ fn get_entity_counts(conn: &rusqlite::Connection) -> Result<EntityCounts, String
 
 fn get_active_plans(conn: &rusqlite::Connection) -> Result<Vec<PlanWithProgress>, String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT p.id, p.title, 
+        .prepare(&format!(
+            "SELECT p.id, p.title, p.progress,
                 COUNT(t.id) as total_tasks,
-                SUM(CASE WHEN t.status = 'done' THEN 1 ELSE 0 END) as completed_tasks
+                SUM(CASE WHEN {} = 'done' THEN 1 ELSE 0 END) as completed_tasks
             FROM plans p
             LEFT JOIN tasks t ON p.id = t.plan_id
             WHERE p.status = 'active'
             GROUP BY p.id, p.title
             ORDER BY p.created_at DESC
             LIMIT 5",
-        )
+            task_category_sql("t")
+        ))
         .map_err(|e| e.to_string())?;
 
     let plans: Vec<PlanWithProgress> = stmt
         .query_map([], |row| {
-            let total: i32 = row.get(2)?;
-            let completed: i32 = row.get(3).unwrap_or(0);
-            let progress = if total > 0 {
-                (completed * 100) / total
-            } else {
-                0
-            };
+            let total: i32 = row.get(3)?;
+            let completed: i32 = row.get(4).unwrap_or(0);
 
             Ok(PlanWithProgress {
                 id: row.get(0)?,
                 title: row.get(1)?,
-                progress,
+                progress: row.get(2)?,
                 task_count: total,
                 completed_count: completed,
             })
@@ -365,7 +368,7 @@ fn get_active_plans(conn: &rusqlite::Connection) -> Result<Vec<PlanWithProgress>
     Ok(plans)
 }
 
-fn get_active_targets(conn: &rusqlite::Connection) -> Result<Vec<TargetWithProgress>, String> {
+pub fn get_active_targets(conn: &rusqlite::Connection) -> Result<Vec<TargetWithProgress>, String> {
     let mut stmt = conn
         .prepare(
             "SELECT id, title, due_date, progress
@@ -376,20 +379,41 @@ fn get_active_targets(conn: &rusqlite::Connection) -> Result<Vec<TargetWithProgr
         )
         .map_err(|e| e.to_string())?;
 
-    let targets: Vec<TargetWithProgress> = stmt
+    let targets: Vec<(String, String, Option<String>, i32)> = stmt
         .query_map([], |row| {
-            Ok(TargetWithProgress {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                progress: row.get(3).unwrap_or(0),
-                due_date: row.get(2)?,
-            })
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(targets)
+    // Progress comes with the forecast; a target that cannot be forecast
+    // still shows its stored progress
+    let today = chrono::Local::now().date_naive();
+    let mut result = Vec::new();
+    for (id, title, due_date, progress) in targets {
+        let (progress, forecast_status, estimated_completion) = match forecast(conn, &id, today) {
+            Ok(forecast) => (
+                forecast.progress,
+                forecast.status,
+                forecast.estimated_completion,
+            ),
+            Err(e) => {
+                log::warn!("Forecast for target {} failed: {}", id, e);
+                (progress, "unknown".to_string(), None)
+            }
+        };
+        result.push(TargetWithProgress {
+            id,
+            title,
+            progress,
+            due_date,
+            forecast_status,
+            estimated_completion,
+        });
+    }
+
+    Ok(result)
 }
 
 fn get_active_milestones(
@@ -427,7 +451,7 @@ fn calculate_efficiency(conn: &rusqlite::Connection) -> Result<(i32, i32), Strin
         .prepare(
             "SELECT DISTINCT date(updated_at) as completion_date
             FROM todos
-            WHERE status = 'done'
+            WHERE status IN ('done', 'archived')
                 AND date(updated_at) >= date('now', '-30 days', 'localtime')
             ORDER BY completion_date DESC",
         )
@@ -476,11 +500,11 @@ fn calculate_efficiency(conn: &rusqlite::Connection) -> Result<(i32, i32), Strin
     let (completed, total): (i32, i32) = conn
         .query_row(
             "SELECT 
-                SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status IN ('done', 'archived') THEN 1 ELSE 0 END),
                 COUNT(*)
             FROM todos
             WHERE date(updated_at) >= date('now', '-7 days', 'localtime')
-                OR status != 'done'",
+                OR status NOT IN ('done', 'archived')",
             [],
             |row| {
                 Ok((
@@ -0,0 +1,339 @@
+// Dependency commands (blocking between todos and tasks)
+
+use super::workflows::task_category_sql;
+use crate::log_command;
+use crate::models::Dependency;
+use crate::AppState;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use tauri::Emitter;
+
+#[derive(Debug, Serialize)]
+pub struct DependencyItem {
+    pub dependency_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UnblockedEvent {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub unblocked_by_type: String,
+    pub unblocked_by_id: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn validate_entity_type(entity_type: &str) -> Result<(), String> {
+    match entity_type {
+        "todo" | "task" => Ok(()),
+        _ => Err("Invalid entity_type. Use 'todo' or 'task'".to_string()),
+    }
+}
+
+fn entity_table(entity_type: &str) -> &'static str {
+    if entity_type == "task" {
+        "tasks"
+    } else {
+        "todos"
+    }
+}
+
+fn entity_exists(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<bool, String> {
+    conn.query_row(
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)",
+            entity_table(entity_type)
+        ),
+        [entity_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns true if the entity has at least one blocker that is not done yet.
+pub fn is_blocked(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<bool, String> {
+    conn.query_row(
+        &format!(
+            "SELECT EXISTS(
+                SELECT 1 FROM dependencies d
+                WHERE d.blocked_type = ?1 AND d.blocked_id = ?2
+                AND (
+                    (d.blocker_type = 'todo' AND EXISTS(SELECT 1 FROM todos WHERE id = d.blocker_id AND status NOT IN ('done', 'archived')))
+                    OR (d.blocker_type = 'task' AND EXISTS(SELECT 1 FROM tasks WHERE id = d.blocker_id AND {} != 'done'))
+                )
+            )",
+            task_category_sql("tasks")
+        ),
+        rusqlite::params![entity_type, entity_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Rejects moving a blocked entity to in-progress unless `force` is set.
+pub fn check_can_start(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+    status: &str,
+    force: bool,
+) -> Result<(), String> {
+    if status == "in-progress" && !force && is_blocked(conn, entity_type, entity_id)? {
+        return Err(format!(
+            "Cannot start {} {}: blocked by unfinished dependencies",
+            entity_type, entity_id
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if adding blocker -> blocked would close a cycle, i.e. the
+/// blocker is already (transitively) blocked by the blocked entity.
+pub fn would_create_cycle(
+    conn: &rusqlite::Connection,
+    blocker: (&str, &str),
+    blocked: (&str, &str),
+) -> Result<bool, String> {
+    if blocker == blocked {
+        return Ok(true);
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT blocked_type, blocked_id FROM dependencies WHERE blocker_type = ? AND blocker_id = ?")
+        .map_err(|e| e.to_string())?;
+
+    let target = (blocker.0.to_string(), blocker.1.to_string());
+    let mut visited: HashSet<(String, String)> = HashSet::new();
+    let mut queue: VecDeque<(String, String)> = VecDeque::new();
+    queue.push_back((blocked.0.to_string(), blocked.1.to_string()));
+
+    while let Some(current) = queue.pop_front() {
+        if current == target {
+            return Ok(true);
+        }
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+
+        let next: Vec<(String, String)> = stmt
+            .query_map(rusqlite::params![current.0, current.1], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        queue.extend(next);
+    }
+
+    Ok(false)
+}
+
+/// Returns dependents of a (just completed) entity that have no unfinished
+/// blockers left.
+pub fn find_unblocked(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Vec<UnblockedEvent>, String> {
+    let mut stmt = conn
+        .prepare("SELECT blocked_type, blocked_id FROM dependencies WHERE blocker_type = ? AND blocker_id = ?")
+        .map_err(|e| e.to_string())?;
+
+    let dependents: Vec<(String, String)> = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut unblocked = Vec::new();
+    for (blocked_type, blocked_id) in dependents {
+        if !is_blocked(conn, &blocked_type, &blocked_id)? {
+            unblocked.push(UnblockedEvent {
+                entity_type: blocked_type,
+                entity_id: blocked_id,
+                unblocked_by_type: entity_type.to_string(),
+                unblocked_by_id: entity_id.to_string(),
+            });
+        }
+    }
+
+    Ok(unblocked)
+}
+
+/// Emits an "unblocked" event for every dependent freed by completing the entity.
+pub fn notify_unblocked(
+    app: &tauri::AppHandle,
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    for event in find_unblocked(conn, entity_type, entity_id)? {
+        app.emit("unblocked", event).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Removes all dependency rows that reference the entity.
+pub fn delete_entity_dependencies(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM dependencies WHERE (blocker_type = ?1 AND blocker_id = ?2) OR (blocked_type = ?1 AND blocked_id = ?2)",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn get_items(
+    conn: &rusqlite::Connection,
+    query: &str,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Vec<DependencyItem>, String> {
+    let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
+
+    let items = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| {
+            Ok(DependencyItem {
+                dependency_id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                title: row.get(3)?,
+                status: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(items.filter_map(|i| i.ok()).collect())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn add_dependency(
+    state: tauri::State<AppState>,
+    blocker_type: String,
+    blocker_id: String,
+    blocked_type: String,
+    blocked_id: String,
+) -> Result<Dependency, String> {
+    log_command!("add_dependency", {
+        validate_entity_type(&blocker_type)?;
+        validate_entity_type(&blocked_type)?;
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        if !entity_exists(&conn, &blocker_type, &blocker_id)? {
+            return Err(format!("Blocker {} {} not found", blocker_type, blocker_id));
+        }
+        if !entity_exists(&conn, &blocked_type, &blocked_id)? {
+            return Err(format!("Blocked {} {} not found", blocked_type, blocked_id));
+        }
+
+        if would_create_cycle(
+            &conn,
+            (&blocker_type, &blocker_id),
+            (&blocked_type, &blocked_id),
+        )? {
+            return Err("Dependency would create a cycle".to_string());
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, blocker_type, blocker_id, blocked_type, blocked_id, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Dependency {
+            id,
+            blocker_type,
+            blocker_id,
+            blocked_type,
+            blocked_id,
+            created_at: now,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn remove_dependency(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("remove_dependency", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM dependencies WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_blockers(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<DependencyItem>, String> {
+    log_command!("get_blockers", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        get_items(
+            &conn,
+            "SELECT d.id, d.blocker_type, d.blocker_id, COALESCE(t.title, k.title), COALESCE(t.status, k.status)
+             FROM dependencies d
+             LEFT JOIN todos t ON d.blocker_type = 'todo' AND t.id = d.blocker_id
+             LEFT JOIN tasks k ON d.blocker_type = 'task' AND k.id = d.blocker_id
+             WHERE d.blocked_type = ? AND d.blocked_id = ?
+             AND (t.id IS NOT NULL OR k.id IS NOT NULL)
+             ORDER BY d.created_at",
+            &entity_type,
+            &entity_id,
+        )
+    })
+}
+
+#[tauri::command]
+pub fn get_dependents(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<DependencyItem>, String> {
+    log_command!("get_dependents", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        get_items(
+            &conn,
+            "SELECT d.id, d.blocked_type, d.blocked_id, COALESCE(t.title, k.title), COALESCE(t.status, k.status)
+             FROM dependencies d
+             LEFT JOIN todos t ON d.blocked_type = 'todo' AND t.id = d.blocked_id
+             LEFT JOIN tasks k ON d.blocked_type = 'task' AND k.id = d.blocked_id
+             WHERE d.blocker_type = ? AND d.blocker_id = ?
+             AND (t.id IS NOT NULL OR k.id IS NOT NULL)
+             ORDER BY d.created_at",
+            &entity_type,
+            &entity_id,
+        )
+    })
+}
@@ -0,0 +1,225 @@
+// Duplicate commands (deep copies of plans and targets)
+//
+// Copies get new ids and start over: statuses are reset and links between the
+// copied rows (subtasks, dependencies, milestones) point at the new ids.
+
+use super::ordering::next_position;
+use super::plan_templates::{capture_plan, instantiate};
+use super::plans::get_plan_by_id;
+use super::schedule::parse_day;
+use super::targets::get_target_by_id;
+use super::workflows::{
+    load_workflow, save_workflow, WorkflowStatusInput, WorkflowTransitionInput,
+};
+use crate::log_command;
+use crate::models::{Plan, Target};
+use crate::AppState;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Copies the reminder of an entity, marked as not yet sent.
+fn copy_notification(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT OR IGNORE INTO notification_settings (id, entity_type, entity_id, reminder_minutes, reminder_sent, created_at, updated_at)
+         SELECT ?1, entity_type, ?2, reminder_minutes, 0, ?3, ?3 FROM notification_settings
+         WHERE entity_type = ?4 AND entity_id = ?5",
+        rusqlite::params![
+            format!("notif-{}-{}", entity_type, to_id),
+            to_id,
+            now,
+            entity_type,
+            from_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn copy_tags(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO entity_tags (entity_type, entity_id, tag_id)
+         SELECT entity_type, ?1, tag_id FROM entity_tags WHERE entity_type = ?2 AND entity_id = ?3",
+        rusqlite::params![to_id, entity_type, from_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deep-copies a plan with its tasks, subtasks, dependencies, milestones,
+/// tags, workflow and reminders. Dates keep their offsets from `start`
+/// (default: the original start). Returns the new plan id.
+pub fn duplicate_plan_tree(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    title: Option<String>,
+    start: Option<chrono::NaiveDate>,
+) -> Result<String, String> {
+    let (content, base) = capture_plan(conn, plan_id)?;
+    let title = title.unwrap_or_else(|| format!("{} (copy)", content.plan_title));
+    let (new_plan_id, ids) = instantiate(conn, &content, start.unwrap_or(base), Some(title))?;
+
+    // Tasks are created as 'pending'; saving the workflow moves them to its
+    // first todo status.
+    if let Some(workflow) = load_workflow(conn, plan_id)? {
+        let statuses: Vec<WorkflowStatusInput> = workflow
+            .statuses
+            .into_iter()
+            .map(|s| WorkflowStatusInput {
+                name: s.name,
+                category: s.category,
+            })
+            .collect();
+        let transitions: Vec<WorkflowTransitionInput> = workflow
+            .transitions
+            .into_iter()
+            .map(|t| WorkflowTransitionInput {
+                from_status: t.from_status,
+                to_status: t.to_status,
+            })
+            .collect();
+        save_workflow(conn, &new_plan_id, &statuses, &transitions)?;
+    }
+
+    copy_notification(conn, "plan", plan_id, &new_plan_id)?;
+    for task in &content.tasks {
+        copy_notification(conn, "task", &task.key, &ids[&task.key])?;
+    }
+    for key in content.milestones.iter().filter_map(|m| m.key.as_ref()) {
+        copy_notification(conn, "milestone", key, &ids[key])?;
+    }
+
+    Ok(new_plan_id)
+}
+
+/// Deep-copies a target with its steps, metric settings, tags and reminders.
+/// Recorded metric values are not copied. Returns the new target id.
+pub fn duplicate_target_tree(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+    title: Option<String>,
+) -> Result<String, String> {
+    let original_title: String = conn
+        .query_row(
+            "SELECT title FROM targets WHERE id = ?",
+            [target_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "Target not found".to_string())?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let new_target_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO targets (id, title, description, due_date, status, progress, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode)
+         SELECT ?, ?, description, due_date, 'active', 0, ?, ?, metric_unit, metric_start, metric_goal, metric_direction, weight_mode FROM targets WHERE id = ?",
+        rusqlite::params![
+            new_target_id,
+            title.unwrap_or_else(|| format!("{} (copy)", original_title)),
+            now,
+            now,
+            target_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    copy_tags(conn, "target", target_id, &new_target_id)?;
+    copy_notification(conn, "target", target_id, &new_target_id)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, weight, priority, estimate, estimate_unit FROM steps
+             WHERE target_id = ? ORDER BY position, created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let steps: Vec<(String, String, i32, String, Option<f64>, Option<String>)> = stmt
+        .query_map([target_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (step_id, step_title, weight, priority, estimate, estimate_unit) in steps {
+        let new_step_id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO steps (id, target_id, title, weight, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                new_step_id,
+                new_target_id,
+                step_title,
+                weight,
+                priority,
+                estimate,
+                estimate_unit,
+                next_position(conn, "step", Some(&new_target_id))?,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        copy_tags(conn, "step", &step_id, &new_step_id)?;
+    }
+
+    Ok(new_target_id)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn duplicate_plan(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    title: Option<String>,
+    start_date: Option<String>,
+) -> Result<Plan, String> {
+    log_command!("duplicate_plan", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let start = match start_date.as_deref() {
+            Some(value) => Some(parse_day(value).ok_or("Invalid start date")?),
+            None => None,
+        };
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let new_plan_id = duplicate_plan_tree(&tx, &plan_id, title, start)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_plan_by_id(&conn, &new_plan_id)
+    })
+}
+
+#[tauri::command]
+pub fn duplicate_target(
+    state: tauri::State<AppState>,
+    target_id: String,
+    title: Option<String>,
+) -> Result<Target, String> {
+    log_command!("duplicate_target", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let new_target_id = duplicate_target_tree(&tx, &target_id, title)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_target_by_id(&conn, &new_target_id)
+    })
+}
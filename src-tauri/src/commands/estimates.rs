@@ -0,0 +1,180 @@
+// Effort estimates (validation and estimate-vs-actual report)
+
+use super::time_tracking::ENTRY_SECONDS_SQL;
+use super::workflows::task_category_sql;
+use crate::log_command;
+use crate::AppState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+pub struct EstimateReport {
+    pub by_plan: Vec<EstimateAccuracy>,     // Tasks only
+    pub by_priority: Vec<EstimateAccuracy>, // Tasks, todos and steps
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct EstimateAccuracy {
+    pub key: String,   // Plan id / priority
+    pub label: String, // Plan title / priority
+    pub items: i32,    // Done items with an estimate
+    pub estimated_minutes: f64,
+    pub actual_minutes: f64,         // Start to done, minute estimates only
+    pub tracked_minutes: f64,        // From time entries, minute estimates only
+    pub accuracy_ratio: Option<f64>, // actual / estimated (> 1 means underestimated)
+    pub story_points: f64,
+    pub point_actual_minutes: f64, // Start to done, point estimates only
+    pub minutes_per_point: Option<f64>,
+}
+
+/// A finished todo, task or step that had an estimate.
+struct EstimatedItem {
+    plan_id: Option<String>,
+    plan_title: Option<String>,
+    priority: String,
+    estimate: f64,
+    unit: String,
+    actual_minutes: f64,
+    tracked_minutes: f64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Checks an estimate and returns the unit to store (minutes if not given).
+pub fn normalize_estimate(
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
+) -> Result<Option<String>, String> {
+    if let Some(value) = estimate {
+        if !value.is_finite() || value < 0.0 {
+            return Err("Estimate must be a non-negative number".to_string());
+        }
+    }
+
+    match estimate_unit.as_deref() {
+        None if estimate.is_some() => Ok(Some("minutes".to_string())),
+        None => Ok(None),
+        Some("minutes") | Some("points") => Ok(estimate_unit),
+        Some(other) => Err(format!(
+            "Invalid estimate_unit '{}'. Use 'minutes' or 'points'",
+            other
+        )),
+    }
+}
+
+fn load_estimated_items(conn: &rusqlite::Connection) -> Result<Vec<EstimatedItem>, String> {
+    // Completion time is updated_at, as in the statistics
+    let query = format!(
+        "SELECT t.plan_id, p.title, t.priority, t.estimate, COALESCE(t.estimate_unit, 'minutes'),
+            (julianday(t.updated_at) - julianday(COALESCE(t.start_date, t.created_at))) * 1440,
+            (SELECT COALESCE(SUM({secs}), 0) FROM time_entries WHERE entity_type = 'task' AND entity_id = t.id) / 60.0
+        FROM tasks t LEFT JOIN plans p ON p.id = t.plan_id
+        WHERE {done_task} = 'done' AND t.estimate IS NOT NULL
+        UNION ALL
+        SELECT NULL, NULL, d.priority, d.estimate, COALESCE(d.estimate_unit, 'minutes'),
+            (julianday(d.updated_at) - julianday(d.created_at)) * 1440,
+            (SELECT COALESCE(SUM({secs}), 0) FROM time_entries WHERE entity_type = 'todo' AND entity_id = d.id) / 60.0
+        FROM todos d
+        WHERE d.status IN ('done', 'archived') AND d.estimate IS NOT NULL
+        UNION ALL
+        SELECT NULL, NULL, s.priority, s.estimate, COALESCE(s.estimate_unit, 'minutes'),
+            (julianday(s.updated_at) - julianday(s.created_at)) * 1440,
+            0.0
+        FROM steps s
+        WHERE s.status = 'completed' AND s.estimate IS NOT NULL",
+        secs = ENTRY_SECONDS_SQL,
+        done_task = task_category_sql("t")
+    );
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let items = stmt
+        .query_map([], |row| {
+            let actual: Option<f64> = row.get(5)?;
+            Ok(EstimatedItem {
+                plan_id: row.get(0)?,
+                plan_title: row.get(1)?,
+                priority: row.get(2)?,
+                estimate: row.get(3)?,
+                unit: row.get(4)?,
+                // Planned start dates after completion count as zero
+                actual_minutes: actual.unwrap_or(0.0).max(0.0),
+                tracked_minutes: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(items.filter_map(|i| i.ok()).collect())
+}
+
+fn summarize<'a>(
+    items: impl Iterator<Item = (String, String, &'a EstimatedItem)>,
+) -> Vec<EstimateAccuracy> {
+    let mut groups: BTreeMap<String, EstimateAccuracy> = BTreeMap::new();
+
+    for (key, label, item) in items {
+        let group = groups
+            .entry(key.clone())
+            .or_insert_with(|| EstimateAccuracy {
+                key,
+                label,
+                ..Default::default()
+            });
+        group.items += 1;
+        if item.unit == "points" {
+            group.story_points += item.estimate;
+            group.point_actual_minutes += item.actual_minutes;
+        } else {
+            group.estimated_minutes += item.estimate;
+            group.actual_minutes += item.actual_minutes;
+            group.tracked_minutes += item.tracked_minutes;
+        }
+    }
+
+    groups
+        .into_values()
+        .map(|mut group| {
+            if group.estimated_minutes > 0.0 {
+                group.accuracy_ratio = Some(group.actual_minutes / group.estimated_minutes);
+            }
+            if group.story_points > 0.0 {
+                group.minutes_per_point = Some(group.point_actual_minutes / group.story_points);
+            }
+            group
+        })
+        .collect()
+}
+
+pub fn build_estimate_report(conn: &rusqlite::Connection) -> Result<EstimateReport, String> {
+    let items = load_estimated_items(conn)?;
+
+    let by_plan = summarize(items.iter().filter_map(|item| {
+        let plan_id = item.plan_id.clone()?;
+        let label = item.plan_title.clone().unwrap_or_else(|| plan_id.clone());
+        Some((plan_id, label, item))
+    }));
+
+    let by_priority = summarize(
+        items
+            .iter()
+            .map(|item| (item.priority.clone(), item.priority.clone(), item)),
+    );
+
+    Ok(EstimateReport {
+        by_plan,
+        by_priority,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_estimate_report(state: tauri::State<AppState>) -> Result<EstimateReport, String> {
+    log_command!("get_estimate_report", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        build_estimate_report(&conn)
+    })
+}
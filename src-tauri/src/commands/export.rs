@@ -1,8 +1,14 @@
 // Export functionality
 
-use crate::models::{Circulation, CirculationLog, Milestone, Plan, Step, Target, Task, Todo};
+use super::attachments::{attachments_dir, stored_path};
+use crate::models::{
+    Attachment, Circulation, CirculationLog, Comment, Dependency, KeyResult, Milestone, Objective,
+    Plan, PlanTemplate, PomodoroSession, Sprint, StatusChange, Step, StepLink, Target, TargetValue,
+    Task, TimeEntry, Todo, WorkflowStatus, WorkflowTransition,
+};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 
 // ============================================================================
 // Export Data Structures
@@ -28,6 +34,34 @@ pub struct ExportDataContent {
     pub circulations: Vec<Circulation>,
     pub circulation_logs: Vec<CirculationLog>,
     pub settings: SettingsData,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub pomodoro_sessions: Vec<PomodoroSession>,
+    #[serde(default)]
+    pub plan_templates: Vec<PlanTemplate>,
+    #[serde(default)]
+    pub workflow_statuses: Vec<WorkflowStatus>,
+    #[serde(default)]
+    pub workflow_transitions: Vec<WorkflowTransition>,
+    #[serde(default)]
+    pub status_history: Vec<StatusChange>,
+    #[serde(default)]
+    pub sprints: Vec<Sprint>,
+    #[serde(default)]
+    pub target_values: Vec<TargetValue>,
+    #[serde(default)]
+    pub step_links: Vec<StepLink>,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub key_results: Vec<KeyResult>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -85,19 +119,82 @@ pub struct NotificationPluginData {
 #[tauri::command]
 pub fn export_data(state: tauri::State<AppState>) -> Result<ExportData, String> {
     let conn = state.db.lock().map_err(|e| e.to_string())?;
+    build_export(&conn)
+}
+
+/// Writes a zip archive with the export JSON (`data.json`) and all attachment
+/// files (`attachments/<stored_name>`) to `path`.
+#[tauri::command]
+pub fn export_bundle(state: tauri::State<AppState>, path: String) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let export = build_export(&conn)?;
+
+    let file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    let json = serde_json::to_vec_pretty(&export).map_err(|e| e.to_string())?;
+    zip.start_file("data.json", options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+
+    let dir = attachments_dir();
+    let mut written = std::collections::HashSet::new();
+    for attachment in &export.data.attachments {
+        if !written.insert(attachment.stored_name.clone()) {
+            continue;
+        }
+        let Ok(stored) = stored_path(&dir, &attachment.stored_name) else {
+            log::warn!("Skipping invalid attachment {}", attachment.stored_name);
+            continue;
+        };
+        let bytes = match std::fs::read(stored) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::warn!(
+                    "Skipping missing attachment {}: {}",
+                    attachment.stored_name,
+                    e
+                );
+                continue;
+            }
+        };
+        zip.start_file(format!("attachments/{}", attachment.stored_name), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
 
+pub(crate) fn build_export(conn: &rusqlite::Connection) -> Result<ExportData, String> {
     // Export all tables
-    let todos = export_todos(&conn)?;
-    let tasks = export_tasks(&conn)?;
-    let plans = export_plans(&conn)?;
-    let targets = export_targets(&conn)?;
-    let steps = export_steps(&conn)?;
-    let milestones = export_milestones(&conn)?;
-    let tags = export_tags(&conn)?;
-    let entity_tags = export_entity_tags(&conn)?;
-    let circulations = export_circulations(&conn)?;
-    let circulation_logs = export_circulation_logs(&conn)?;
-    let settings = export_settings(&conn)?;
+    let todos = export_todos(conn)?;
+    let tasks = export_tasks(conn)?;
+    let plans = export_plans(conn)?;
+    let targets = export_targets(conn)?;
+    let steps = export_steps(conn)?;
+    let milestones = export_milestones(conn)?;
+    let tags = export_tags(conn)?;
+    let entity_tags = export_entity_tags(conn)?;
+    let circulations = export_circulations(conn)?;
+    let circulation_logs = export_circulation_logs(conn)?;
+    let settings = export_settings(conn)?;
+    let dependencies = export_dependencies(conn)?;
+    let attachments = export_attachments(conn)?;
+    let comments = export_comments(conn)?;
+    let time_entries = export_time_entries(conn)?;
+    let pomodoro_sessions = export_pomodoro_sessions(conn)?;
+    let plan_templates = export_plan_templates(conn)?;
+    let workflow_statuses = export_workflow_statuses(conn)?;
+    let workflow_transitions = export_workflow_transitions(conn)?;
+    let status_history = export_status_history(conn)?;
+    let sprints = export_sprints(conn)?;
+    let target_values = export_target_values(conn)?;
+    let step_links = export_step_links(conn)?;
+    let objectives = export_objectives(conn)?;
+    let key_results = export_key_results(conn)?;
 
     let exported_at = chrono::Utc::now().to_rfc3339();
 
@@ -116,13 +213,27 @@ pub fn export_data(state: tauri::State<AppState>) -> Result<ExportData, String>
             circulations,
             circulation_logs,
             settings,
+            dependencies,
+            attachments,
+            comments,
+            time_entries,
+            pomodoro_sessions,
+            plan_templates,
+            workflow_statuses,
+            workflow_transitions,
+            status_history,
+            sprints,
+            target_values,
+            step_links,
+            objectives,
+            key_results,
         },
     })
 }
 
 fn export_todos(conn: &rusqlite::Connection) -> Result<Vec<Todo>, String> {
     let mut stmt = conn
-        .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at FROM todos")
+        .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at, defer_until, rollover_count, estimate, estimate_unit, position FROM todos")
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -132,10 +243,16 @@ fn export_todos(conn: &rusqlite::Connection) -> Result<Vec<Todo>, String> {
                 title: row.get(1)?,
                 content: row.get(2)?,
                 due_date: row.get(3)?,
+                defer_until: row.get(8)?,
                 status: row.get(4)?,
                 priority: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                rollover_count: row.get(9)?,
+                estimate: row.get(10)?,
+                estimate_unit: row.get(11)?,
+                position: row.get(12)?,
+                blocked: false,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -145,7 +262,7 @@ fn export_todos(conn: &rusqlite::Connection) -> Result<Vec<Todo>, String> {
 
 fn export_tasks(conn: &rusqlite::Connection) -> Result<Vec<Task>, String> {
     let mut stmt = conn
-        .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at FROM tasks")
+        .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks")
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -157,10 +274,17 @@ fn export_tasks(conn: &rusqlite::Connection) -> Result<Vec<Task>, String> {
                 description: row.get(3)?,
                 start_date: row.get(4)?,
                 end_date: row.get(5)?,
+                defer_until: row.get(10)?,
                 status: row.get(6)?,
                 priority: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                estimate: row.get(11)?,
+                estimate_unit: row.get(12)?,
+                position: row.get(14)?,
+                sprint_id: row.get(15)?,
+                parent_task_id: row.get(13)?,
+                blocked: false,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -170,7 +294,7 @@ fn export_tasks(conn: &rusqlite::Connection) -> Result<Vec<Task>, String> {
 
 fn export_plans(conn: &rusqlite::Connection) -> Result<Vec<Plan>, String> {
     let mut stmt = conn
-        .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at FROM plans")
+        .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete FROM plans")
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -184,6 +308,9 @@ fn export_plans(conn: &rusqlite::Connection) -> Result<Vec<Plan>, String> {
                 status: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                progress: row.get(8)?,
+                weighted_progress: row.get(9)?,
+                auto_complete: row.get(10)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -193,7 +320,7 @@ fn export_plans(conn: &rusqlite::Connection) -> Result<Vec<Plan>, String> {
 
 fn export_targets(conn: &rusqlite::Connection) -> Result<Vec<Target>, String> {
     let mut stmt = conn
-        .prepare("SELECT id, title, description, due_date, status, progress, created_at, updated_at FROM targets")
+        .prepare("SELECT id, title, description, due_date, status, progress, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode FROM targets")
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -207,6 +334,12 @@ fn export_targets(conn: &rusqlite::Connection) -> Result<Vec<Target>, String> {
                 progress: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                metric_unit: row.get(8)?,
+                metric_start: row.get(9)?,
+                metric_goal: row.get(10)?,
+                metric_direction: row.get(11)?,
+                weight_mode: row.get(12)?,
+                metric_value: None,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -216,7 +349,7 @@ fn export_targets(conn: &rusqlite::Connection) -> Result<Vec<Target>, String> {
 
 fn export_steps(conn: &rusqlite::Connection) -> Result<Vec<Step>, String> {
     let mut stmt = conn
-        .prepare("SELECT id, target_id, title, weight, status, priority, created_at, updated_at FROM steps")
+        .prepare("SELECT id, target_id, title, weight, status, priority, created_at, updated_at, estimate, estimate_unit, position FROM steps")
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -230,6 +363,9 @@ fn export_steps(conn: &rusqlite::Connection) -> Result<Vec<Step>, String> {
                 priority: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                estimate: row.get(8)?,
+                estimate_unit: row.get(9)?,
+                position: row.get(10)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -353,6 +489,328 @@ fn export_circulation_logs(conn: &rusqlite::Connection) -> Result<Vec<Circulatio
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+fn export_dependencies(conn: &rusqlite::Connection) -> Result<Vec<Dependency>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, blocker_type, blocker_id, blocked_type, blocked_id, created_at FROM dependencies")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Dependency {
+                id: row.get(0)?,
+                blocker_type: row.get(1)?,
+                blocker_id: row.get(2)?,
+                blocked_type: row.get(3)?,
+                blocked_id: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_attachments(conn: &rusqlite::Connection) -> Result<Vec<Attachment>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at FROM attachments")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Attachment {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                file_name: row.get(3)?,
+                stored_name: row.get(4)?,
+                content_hash: row.get(5)?,
+                size: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_comments(conn: &rusqlite::Connection) -> Result<Vec<Comment>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, entity_type, entity_id, body, created_at, edited_at FROM comments")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Comment {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                body: row.get(3)?,
+                created_at: row.get(4)?,
+                edited_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_time_entries(conn: &rusqlite::Connection) -> Result<Vec<TimeEntry>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at FROM time_entries")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(TimeEntry {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                started_at: row.get(3)?,
+                ended_at: row.get(4)?,
+                running_since: row.get(5)?,
+                duration_seconds: row.get(6)?,
+                status: row.get(7)?,
+                note: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_pomodoro_sessions(conn: &rusqlite::Connection) -> Result<Vec<PomodoroSession>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at FROM pomodoro_sessions")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(PomodoroSession {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                started_at: row.get(3)?,
+                ended_at: row.get(4)?,
+                planned_minutes: row.get(5)?,
+                focus_seconds: row.get(6)?,
+                status: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_plan_templates(conn: &rusqlite::Connection) -> Result<Vec<PlanTemplate>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, description, content, created_at, updated_at FROM plan_templates",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(PlanTemplate {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                content: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_workflow_statuses(conn: &rusqlite::Connection) -> Result<Vec<WorkflowStatus>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, plan_id, name, category, position, created_at FROM workflow_statuses")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(WorkflowStatus {
+                id: row.get(0)?,
+                plan_id: row.get(1)?,
+                name: row.get(2)?,
+                category: row.get(3)?,
+                position: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_workflow_transitions(
+    conn: &rusqlite::Connection,
+) -> Result<Vec<WorkflowTransition>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, plan_id, from_status, to_status, created_at FROM workflow_transitions")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(WorkflowTransition {
+                id: row.get(0)?,
+                plan_id: row.get(1)?,
+                from_status: row.get(2)?,
+                to_status: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_status_history(conn: &rusqlite::Connection) -> Result<Vec<StatusChange>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, entity_type, entity_id, from_status, to_status, category, changed_at FROM status_history")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(StatusChange {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                from_status: row.get(3)?,
+                to_status: row.get(4)?,
+                category: row.get(5)?,
+                changed_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_sprints(conn: &rusqlite::Connection) -> Result<Vec<Sprint>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at FROM sprints")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Sprint {
+                id: row.get(0)?,
+                plan_id: row.get(1)?,
+                name: row.get(2)?,
+                goal: row.get(3)?,
+                start_date: row.get(4)?,
+                end_date: row.get(5)?,
+                status: row.get(6)?,
+                created_at: row.get(7)?,
+                updated_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_target_values(conn: &rusqlite::Connection) -> Result<Vec<TargetValue>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, target_id, value, note, recorded_at, created_at FROM target_values")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(TargetValue {
+                id: row.get(0)?,
+                target_id: row.get(1)?,
+                value: row.get(2)?,
+                note: row.get(3)?,
+                recorded_at: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_step_links(conn: &rusqlite::Connection) -> Result<Vec<StepLink>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, step_id, entity_type, entity_id, created_at FROM step_links")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(StepLink {
+                id: row.get(0)?,
+                step_id: row.get(1)?,
+                entity_type: row.get(2)?,
+                entity_id: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_objectives(conn: &rusqlite::Connection) -> Result<Vec<Objective>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, title, description, period, parent_id, status, final_score, grade_note, graded_at, created_at, updated_at FROM objectives")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Objective {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                period: row.get(3)?,
+                parent_id: row.get(4)?,
+                status: row.get(5)?,
+                final_score: row.get(6)?,
+                grade_note: row.get(7)?,
+                graded_at: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn export_key_results(conn: &rusqlite::Connection) -> Result<Vec<KeyResult>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, final_score, created_at, updated_at FROM key_results")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(KeyResult {
+                id: row.get(0)?,
+                objective_id: row.get(1)?,
+                title: row.get(2)?,
+                kind: row.get(3)?,
+                unit: row.get(4)?,
+                start_value: row.get(5)?,
+                target_value: row.get(6)?,
+                current_value: row.get(7)?,
+                score: row.get(8)?,
+                confidence: row.get(9)?,
+                final_score: row.get(10)?,
+                created_at: row.get(11)?,
+                updated_at: row.get(12)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 fn export_settings(conn: &rusqlite::Connection) -> Result<SettingsData, String> {
     // Export daily summary settings
     let daily_summary_settings = conn
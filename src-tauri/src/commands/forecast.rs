@@ -0,0 +1,192 @@
+// Target forecast commands (estimated completion date from progress history)
+//
+// Progress history is the daily completed share of step weight, derived from
+// recorded step status changes; metric targets use their recorded values. A
+// least-squares line through the history estimates the day progress reaches
+// 100%, which is compared with the target's due date.
+
+use super::burndown::target_burnup;
+use super::schedule::parse_day;
+use super::target_metrics::get_value_series;
+use super::targets::get_target_by_id;
+use crate::log_command;
+use crate::AppState;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A forecast at most this many days past the due date is at risk; later is off track.
+const AT_RISK_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize)]
+pub struct TargetForecast {
+    pub target_id: String,
+    pub progress: i32,
+    pub due_date: Option<String>,
+    pub estimated_completion: Option<String>, // None without an upward trend
+    pub daily_rate: f64,                      // Progress points per day
+    pub status: String,                       // on_track | at_risk | off_track | unknown
+    pub history: Vec<ForecastPoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForecastPoint {
+    pub date: String,
+    pub progress: f64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Least-squares line through `points`, as (slope, intercept). None with
+/// fewer than two distinct x values.
+pub fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let slope = cov / var_x;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+/// Daily progress (0-100) of a target up to today.
+fn progress_history(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+    metric: bool,
+    created_at: &str,
+    today: NaiveDate,
+) -> Result<Vec<(NaiveDate, f64)>, String> {
+    if !metric {
+        let burnup = target_burnup(conn, target_id, today)?;
+        let history = burnup
+            .points
+            .iter()
+            .filter_map(|point| {
+                let (total, completed) = (point.total?, point.completed?);
+                if total <= 0 {
+                    return None;
+                }
+                let day = parse_day(&point.date)?;
+                Some((day, completed as f64 * 100.0 / total as f64))
+            })
+            .collect();
+        return Ok(history);
+    }
+
+    // The start value counts from the target's creation; the last value of a
+    // day wins
+    let mut by_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    if let Some(created) = parse_day(created_at) {
+        by_day.insert(created, 0.0);
+    }
+    for point in get_value_series(conn, target_id)?.points {
+        if let Some(day) = parse_day(&point.recorded_at).filter(|d| *d <= today) {
+            by_day.insert(day, point.progress as f64);
+        }
+    }
+    Ok(by_day.into_iter().collect())
+}
+
+/// Forecast status of an estimated completion against the due date.
+fn forecast_status(
+    due: Option<NaiveDate>,
+    estimated: Option<NaiveDate>,
+    history_days: usize,
+    today: NaiveDate,
+) -> &'static str {
+    let Some(due) = due else {
+        return "unknown";
+    };
+    match estimated {
+        Some(day) if day <= due => "on_track",
+        Some(day) if (day - due).num_days() <= AT_RISK_DAYS => "at_risk",
+        Some(_) => "off_track",
+        // Too little history for a trend
+        None if history_days < 2 && due >= today => "unknown",
+        None => "off_track",
+    }
+}
+
+pub fn forecast(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+    today: NaiveDate,
+) -> Result<TargetForecast, String> {
+    let target = get_target_by_id(conn, target_id)?;
+    let history = progress_history(
+        conn,
+        target_id,
+        target.metric_goal.is_some(),
+        &target.created_at,
+        today,
+    )?;
+    let due = target.due_date.as_deref().and_then(parse_day);
+
+    let first = history.first().map(|(day, _)| *day).unwrap_or(today);
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|(day, progress)| ((*day - first).num_days() as f64, *progress))
+        .collect();
+    let fit = linear_fit(&points);
+    let daily_rate = fit.map(|(slope, _)| slope).unwrap_or(0.0);
+
+    let estimated = if target.progress >= 100 {
+        // Done: the first day the history reached 100%
+        history
+            .iter()
+            .find(|(_, progress)| *progress >= 100.0)
+            .map(|(day, _)| *day)
+            .or(Some(today))
+    } else {
+        match fit {
+            Some((slope, intercept)) if slope > 0.0 => {
+                let days = ((100.0 - intercept) / slope).ceil().min(36_500.0) as i64;
+                Some((first + chrono::Duration::days(days)).max(today))
+            }
+            _ => None,
+        }
+    };
+
+    Ok(TargetForecast {
+        target_id: target.id,
+        progress: target.progress,
+        due_date: target.due_date,
+        estimated_completion: estimated.map(|d| d.format("%Y-%m-%d").to_string()),
+        daily_rate: (daily_rate * 100.0).round() / 100.0,
+        status: forecast_status(due, estimated, history.len(), today).to_string(),
+        history: history
+            .into_iter()
+            .map(|(day, progress)| ForecastPoint {
+                date: day.format("%Y-%m-%d").to_string(),
+                progress: (progress * 100.0).round() / 100.0,
+            })
+            .collect(),
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn forecast_target(
+    state: tauri::State<AppState>,
+    target_id: String,
+) -> Result<TargetForecast, String> {
+    log_command!("forecast_target", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        forecast(&conn, &target_id, chrono::Local::now().date_naive())
+    })
+}
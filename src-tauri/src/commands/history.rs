@@ -0,0 +1,85 @@
+// Entity history (timeline of creation, edits, comments and attachments)
+
+use super::comments::{entity_table, list_comments};
+use crate::log_command;
+use crate::AppState;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct HistoryEvent {
+    pub event_type: String, // 'created' | 'updated' | 'comment' | 'attachment'
+    pub timestamp: String,
+    pub ref_id: Option<String>,    // Comment / attachment id
+    pub body: Option<String>,      // Comment body / attachment file name
+    pub edited_at: Option<String>, // Comments only
+}
+
+#[tauri::command]
+pub fn get_entity_history(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<HistoryEvent>, String> {
+    log_command!("get_entity_history", {
+        let table = entity_table(&entity_type)?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let (created_at, updated_at): (String, String) = conn
+            .query_row(
+                &format!("SELECT created_at, updated_at FROM {} WHERE id = ?", table),
+                [&entity_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut events = vec![HistoryEvent {
+            event_type: "created".to_string(),
+            timestamp: created_at.clone(),
+            ref_id: None,
+            body: None,
+            edited_at: None,
+        }];
+
+        if updated_at != created_at {
+            events.push(HistoryEvent {
+                event_type: "updated".to_string(),
+                timestamp: updated_at,
+                ref_id: None,
+                body: None,
+                edited_at: None,
+            });
+        }
+
+        for comment in list_comments(&conn, &entity_type, &entity_id)? {
+            events.push(HistoryEvent {
+                event_type: "comment".to_string(),
+                timestamp: comment.created_at,
+                ref_id: Some(comment.id),
+                body: Some(comment.body),
+                edited_at: comment.edited_at,
+            });
+        }
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, file_name, created_at FROM attachments WHERE entity_type = ? AND entity_id = ?",
+            )
+            .map_err(|e| e.to_string())?;
+        let attachments = stmt
+            .query_map(rusqlite::params![entity_type, entity_id], |row| {
+                Ok(HistoryEvent {
+                    event_type: "attachment".to_string(),
+                    timestamp: row.get(2)?,
+                    ref_id: Some(row.get(0)?),
+                    body: Some(row.get(1)?),
+                    edited_at: None,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        events.extend(attachments.filter_map(|a| a.ok()));
+
+        events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        Ok(events)
+    })
+}
@@ -1,8 +1,15 @@
 // Import functionality
 
+use super::attachments::{attachments_dir, is_valid_stored_name, stored_path};
 use super::export::ExportDataContent;
+use super::okrs::detach_invalid_alignments;
+use super::ordering::{backfill_positions, clear_invalid_positions};
+use super::plans::refresh_all_plan_progress;
+use super::step_links::check_step_link;
+use super::subtasks::detach_invalid_parents;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
+use std::io::Read;
 
 // ============================================================================
 // Import Data Structures
@@ -33,13 +40,94 @@ pub fn import_data(
     mode: String,
 ) -> Result<ImportResult, String> {
     let conn = state.db.lock().map_err(|e| e.to_string())?;
+    import_content(&conn, &data.data, &mode)
+}
 
-    match mode.as_str() {
-        "merge" => import_merge(&conn, &data.data),
-        "replace" => import_replace(&conn, &data.data),
-        "update" => import_update(&conn, &data.data),
-        _ => Err("Invalid mode. Use 'merge', 'replace', or 'update'".to_string()),
+/// Imports a zip archive written by `export_bundle`: attachment files are
+/// restored into the attachments dir, then `data.json` is imported with `mode`.
+#[tauri::command]
+pub fn import_bundle(
+    state: tauri::State<AppState>,
+    path: String,
+    mode: String,
+) -> Result<ImportResult, String> {
+    let file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    let data: ImportData = {
+        let mut entry = archive
+            .by_name("data.json")
+            .map_err(|_| "Invalid bundle: data.json not found".to_string())?;
+        let mut json = String::new();
+        entry.read_to_string(&mut json).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())?
+    };
+
+    let dir = attachments_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        // Only stored file names under attachments/ are accepted
+        let Some(name) = entry
+            .name()
+            .strip_prefix("attachments/")
+            .map(|n| n.to_string())
+        else {
+            continue;
+        };
+        let Ok(dest) = stored_path(&dir, &name) else {
+            continue;
+        };
+        if dest.exists() {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        std::fs::write(&dest, bytes).map_err(|e| e.to_string())?;
     }
+
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    import_content(&conn, &data.data, &mode)
+}
+
+pub(crate) fn import_content(
+    conn: &rusqlite::Connection,
+    data: &ExportDataContent,
+    mode: &str,
+) -> Result<ImportResult, String> {
+    let mut result = match mode {
+        "merge" => import_merge(conn, data),
+        "replace" => import_replace(conn, data),
+        "update" => import_update(conn, data),
+        _ => Err("Invalid mode. Use 'merge', 'replace', or 'update'".to_string()),
+    }?;
+
+    // Parents are checked once all rows are in; an invalid one is dropped
+    let task_ids: Vec<String> = data
+        .tasks
+        .iter()
+        .filter(|t| t.parent_task_id.is_some())
+        .map(|t| t.id.clone())
+        .collect();
+    result
+        .errors
+        .extend(detach_invalid_parents(conn, &task_ids)?);
+    let objective_ids: Vec<String> = data
+        .objectives
+        .iter()
+        .filter(|o| o.parent_id.is_some())
+        .map(|o| o.id.clone())
+        .collect();
+    result
+        .errors
+        .extend(detach_invalid_alignments(conn, &objective_ids)?);
+
+    // Imported tasks may belong to any plan; older exports have no positions,
+    // and invalid or clashing ones are given fresh keys
+    refresh_all_plan_progress(conn)?;
+    clear_invalid_positions(conn)?;
+    backfill_positions(conn)?;
+    Ok(result)
 }
 
 // ============================================================================
@@ -91,8 +179,8 @@ fn import_merge(
             skipped += 1;
         } else {
             match conn.execute(
-                "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at],
+                "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at, plan.progress, plan.weighted_progress, plan.auto_complete],
             ) {
                 Ok(_) => imported += 1,
                 Err(e) => errors.push(format!("Plan {}: {}", plan.id, e)),
@@ -114,8 +202,8 @@ fn import_merge(
             skipped += 1;
         } else {
             match conn.execute(
-                "INSERT INTO targets (id, title, description, due_date, status, progress, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at],
+                "INSERT INTO targets (id, title, description, due_date, status, progress, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at, target.metric_unit, target.metric_start, target.metric_goal, target.metric_direction, target.weight_mode],
             ) {
                 Ok(_) => imported += 1,
                 Err(e) => errors.push(format!("Target {}: {}", target.id, e)),
@@ -137,8 +225,8 @@ fn import_merge(
             skipped += 1;
         } else {
             match conn.execute(
-                "INSERT INTO todos (id, title, content, due_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.status, todo.priority, todo.created_at, todo.updated_at],
+                "INSERT INTO todos (id, title, content, due_date, defer_until, status, priority, rollover_count, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.defer_until, todo.status, todo.priority, todo.rollover_count, todo.estimate, todo.estimate_unit, todo.position, todo.created_at, todo.updated_at],
             ) {
                 Ok(_) => imported += 1,
                 Err(e) => errors.push(format!("Todo {}: {}", todo.id, e)),
@@ -160,8 +248,8 @@ fn import_merge(
             skipped += 1;
         } else {
             match conn.execute(
-                "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.status, task.priority, task.created_at, task.updated_at],
+                "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, defer_until, status, priority, estimate, estimate_unit, parent_task_id, position, sprint_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.defer_until, task.status, task.priority, task.estimate, task.estimate_unit, task.parent_task_id, task.position, task.sprint_id, task.created_at, task.updated_at],
             ) {
                 Ok(_) => imported += 1,
                 Err(e) => errors.push(format!("Task {}: {}", task.id, e)),
@@ -183,8 +271,8 @@ fn import_merge(
             skipped += 1;
         } else {
             match conn.execute(
-                "INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.created_at, step.updated_at],
+                "INSERT INTO steps (id, target_id, title, weight, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.estimate, step.estimate_unit, step.position, step.created_at, step.updated_at],
             ) {
                 Ok(_) => imported += 1,
                 Err(e) => errors.push(format!("Step {}: {}", step.id, e)),
@@ -238,6 +326,336 @@ fn import_merge(
         }
     }
 
+    // Import dependencies
+    for dep in &data.dependencies {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM dependencies WHERE id = ?)",
+                [&dep.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![dep.id, dep.blocker_type, dep.blocker_id, dep.blocked_type, dep.blocked_id, dep.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Dependency {}: {}", dep.id, e)),
+            }
+        }
+    }
+
+    // Import attachments
+    for attachment in &data.attachments {
+        if !is_valid_stored_name(&attachment.stored_name) {
+            errors.push(format!("Attachment {}: invalid file name", attachment.id));
+            continue;
+        }
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM attachments WHERE id = ?)",
+                [&attachment.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO attachments (id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![attachment.id, attachment.entity_type, attachment.entity_id, attachment.file_name, attachment.stored_name, attachment.content_hash, attachment.size, attachment.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Attachment {}: {}", attachment.id, e)),
+            }
+        }
+    }
+
+    // Import comments
+    for comment in &data.comments {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM comments WHERE id = ?)",
+                [&comment.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO comments (id, entity_type, entity_id, body, created_at, edited_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![comment.id, comment.entity_type, comment.entity_id, comment.body, comment.created_at, comment.edited_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Comment {}: {}", comment.id, e)),
+            }
+        }
+    }
+
+    // Import time entries
+    for entry in &data.time_entries {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM time_entries WHERE id = ?)",
+                [&entry.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![entry.id, entry.entity_type, entry.entity_id, entry.started_at, entry.ended_at, entry.running_since, entry.duration_seconds, entry.status, entry.note, entry.created_at, entry.updated_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Time entry {}: {}", entry.id, e)),
+            }
+        }
+    }
+
+    // Import pomodoro sessions
+    for pomodoro_session in &data.pomodoro_sessions {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM pomodoro_sessions WHERE id = ?)",
+                [&pomodoro_session.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO pomodoro_sessions (id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![pomodoro_session.id, pomodoro_session.entity_type, pomodoro_session.entity_id, pomodoro_session.started_at, pomodoro_session.ended_at, pomodoro_session.planned_minutes, pomodoro_session.focus_seconds, pomodoro_session.status, pomodoro_session.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Pomodoro session {}: {}", pomodoro_session.id, e)),
+            }
+        }
+    }
+
+    // Import plan templates
+    for template in &data.plan_templates {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM plan_templates WHERE id = ?)",
+                [&template.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO plan_templates (id, name, description, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![template.id, template.name, template.description, template.content, template.created_at, template.updated_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Plan template {}: {}", template.id, e)),
+            }
+        }
+    }
+
+    // Import workflow statuses
+    for status in &data.workflow_statuses {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM workflow_statuses WHERE id = ?)",
+                [&status.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO workflow_statuses (id, plan_id, name, category, position, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![status.id, status.plan_id, status.name, status.category, status.position, status.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Workflow status {}: {}", status.id, e)),
+            }
+        }
+    }
+
+    // Import workflow transitions
+    for transition in &data.workflow_transitions {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM workflow_transitions WHERE id = ?)",
+                [&transition.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO workflow_transitions (id, plan_id, from_status, to_status, created_at) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![transition.id, transition.plan_id, transition.from_status, transition.to_status, transition.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Workflow transition {}: {}", transition.id, e)),
+            }
+        }
+    }
+
+    // Import status history
+    for change in &data.status_history {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM status_history WHERE id = ?)",
+                [&change.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO status_history (id, entity_type, entity_id, from_status, to_status, category, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![change.id, change.entity_type, change.entity_id, change.from_status, change.to_status, change.category, change.changed_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Status change {}: {}", change.id, e)),
+            }
+        }
+    }
+
+    // Import sprints
+    for sprint in &data.sprints {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sprints WHERE id = ?)",
+                [&sprint.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO sprints (id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![sprint.id, sprint.plan_id, sprint.name, sprint.goal, sprint.start_date, sprint.end_date, sprint.status, sprint.created_at, sprint.updated_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Sprint {}: {}", sprint.id, e)),
+            }
+        }
+    }
+
+    // Import target values
+    for value in &data.target_values {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM target_values WHERE id = ?)",
+                [&value.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO target_values (id, target_id, value, note, recorded_at, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![value.id, value.target_id, value.value, value.note, value.recorded_at, value.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Target value {}: {}", value.id, e)),
+            }
+        }
+    }
+
+    // Import step links
+    for step_link in &data.step_links {
+        if let Err(e) = check_step_link(conn, &step_link.step_id, &step_link.entity_type) {
+            errors.push(format!("Step link {}: {}", step_link.id, e));
+            continue;
+        }
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM step_links WHERE id = ?)",
+                [&step_link.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![step_link.id, step_link.step_id, step_link.entity_type, step_link.entity_id, step_link.created_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Step link {}: {}", step_link.id, e)),
+            }
+        }
+    }
+
+    // Import objectives
+    for objective in &data.objectives {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM objectives WHERE id = ?)",
+                [&objective.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO objectives (id, title, description, period, parent_id, status, final_score, grade_note, graded_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![objective.id, objective.title, objective.description, objective.period, objective.parent_id, objective.status, objective.final_score, objective.grade_note, objective.graded_at, objective.created_at, objective.updated_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Objective {}: {}", objective.id, e)),
+            }
+        }
+    }
+
+    // Import key results
+    for key_result in &data.key_results {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM key_results WHERE id = ?)",
+                [&key_result.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if exists {
+            skipped += 1;
+        } else {
+            match conn.execute(
+                "INSERT INTO key_results (id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, final_score, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![key_result.id, key_result.objective_id, key_result.title, key_result.kind, key_result.unit, key_result.start_value, key_result.target_value, key_result.current_value, key_result.score, key_result.confidence, key_result.final_score, key_result.created_at, key_result.updated_at],
+            ) {
+                Ok(_) => imported += 1,
+                Err(e) => errors.push(format!("Key result {}: {}", key_result.id, e)),
+            }
+        }
+    }
+
     // Import settings
     if let Some(ref settings) = data.settings.daily_summary_settings {
         conn.execute("DELETE FROM daily_summary_settings", []).ok();
@@ -289,6 +707,20 @@ fn import_replace(
     let mut errors = Vec::new();
 
     // Clear all tables (in reverse dependency order)
+    conn.execute("DELETE FROM key_results", []).ok();
+    conn.execute("DELETE FROM objectives", []).ok();
+    conn.execute("DELETE FROM step_links", []).ok();
+    conn.execute("DELETE FROM target_values", []).ok();
+    conn.execute("DELETE FROM sprints", []).ok();
+    conn.execute("DELETE FROM status_history", []).ok();
+    conn.execute("DELETE FROM workflow_transitions", []).ok();
+    conn.execute("DELETE FROM workflow_statuses", []).ok();
+    conn.execute("DELETE FROM plan_templates", []).ok();
+    conn.execute("DELETE FROM pomodoro_sessions", []).ok();
+    conn.execute("DELETE FROM time_entries", []).ok();
+    conn.execute("DELETE FROM comments", []).ok();
+    conn.execute("DELETE FROM attachments", []).ok();
+    conn.execute("DELETE FROM dependencies", []).ok();
     conn.execute("DELETE FROM entity_tags", []).ok();
     conn.execute("DELETE FROM milestones", []).ok();
     conn.execute("DELETE FROM steps", []).ok();
@@ -314,8 +746,8 @@ fn import_replace(
     // Import plans
     for plan in &data.plans {
         match conn.execute(
-            "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at],
+            "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at, plan.progress, plan.weighted_progress, plan.auto_complete],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Plan {}: {}", plan.id, e)),
@@ -325,8 +757,8 @@ fn import_replace(
     // Import targets
     for target in &data.targets {
         match conn.execute(
-            "INSERT INTO targets (id, title, description, due_date, status, progress, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at],
+            "INSERT INTO targets (id, title, description, due_date, status, progress, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at, target.metric_unit, target.metric_start, target.metric_goal, target.metric_direction, target.weight_mode],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Target {}: {}", target.id, e)),
@@ -336,8 +768,8 @@ fn import_replace(
     // Import todos
     for todo in &data.todos {
         match conn.execute(
-            "INSERT INTO todos (id, title, content, due_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.status, todo.priority, todo.created_at, todo.updated_at],
+            "INSERT INTO todos (id, title, content, due_date, defer_until, status, priority, rollover_count, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.defer_until, todo.status, todo.priority, todo.rollover_count, todo.estimate, todo.estimate_unit, todo.position, todo.created_at, todo.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Todo {}: {}", todo.id, e)),
@@ -347,8 +779,8 @@ fn import_replace(
     // Import tasks
     for task in &data.tasks {
         match conn.execute(
-            "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.status, task.priority, task.created_at, task.updated_at],
+            "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, defer_until, status, priority, estimate, estimate_unit, parent_task_id, position, sprint_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.defer_until, task.status, task.priority, task.estimate, task.estimate_unit, task.parent_task_id, task.position, task.sprint_id, task.created_at, task.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Task {}: {}", task.id, e)),
@@ -358,8 +790,8 @@ fn import_replace(
     // Import steps
     for step in &data.steps {
         match conn.execute(
-            "INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.created_at, step.updated_at],
+            "INSERT INTO steps (id, target_id, title, weight, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.estimate, step.estimate_unit, step.position, step.created_at, step.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Step {}: {}", step.id, e)),
@@ -388,6 +820,168 @@ fn import_replace(
         }
     }
 
+    // Import dependencies
+    for dep in &data.dependencies {
+        match conn.execute(
+            "INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![dep.id, dep.blocker_type, dep.blocker_id, dep.blocked_type, dep.blocked_id, dep.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Dependency {}: {}", dep.id, e)),
+        }
+    }
+
+    // Import attachments
+    for attachment in &data.attachments {
+        if !is_valid_stored_name(&attachment.stored_name) {
+            errors.push(format!("Attachment {}: invalid file name", attachment.id));
+            continue;
+        }
+        match conn.execute(
+            "INSERT INTO attachments (id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![attachment.id, attachment.entity_type, attachment.entity_id, attachment.file_name, attachment.stored_name, attachment.content_hash, attachment.size, attachment.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Attachment {}: {}", attachment.id, e)),
+        }
+    }
+
+    // Import comments
+    for comment in &data.comments {
+        match conn.execute(
+            "INSERT INTO comments (id, entity_type, entity_id, body, created_at, edited_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![comment.id, comment.entity_type, comment.entity_id, comment.body, comment.created_at, comment.edited_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Comment {}: {}", comment.id, e)),
+        }
+    }
+
+    // Import time entries
+    for entry in &data.time_entries {
+        match conn.execute(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![entry.id, entry.entity_type, entry.entity_id, entry.started_at, entry.ended_at, entry.running_since, entry.duration_seconds, entry.status, entry.note, entry.created_at, entry.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Time entry {}: {}", entry.id, e)),
+        }
+    }
+
+    // Import pomodoro sessions
+    for pomodoro_session in &data.pomodoro_sessions {
+        match conn.execute(
+            "INSERT INTO pomodoro_sessions (id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![pomodoro_session.id, pomodoro_session.entity_type, pomodoro_session.entity_id, pomodoro_session.started_at, pomodoro_session.ended_at, pomodoro_session.planned_minutes, pomodoro_session.focus_seconds, pomodoro_session.status, pomodoro_session.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Pomodoro session {}: {}", pomodoro_session.id, e)),
+        }
+    }
+
+    // Import plan templates
+    for template in &data.plan_templates {
+        match conn.execute(
+            "INSERT INTO plan_templates (id, name, description, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![template.id, template.name, template.description, template.content, template.created_at, template.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Plan template {}: {}", template.id, e)),
+        }
+    }
+
+    // Import workflow statuses
+    for status in &data.workflow_statuses {
+        match conn.execute(
+            "INSERT INTO workflow_statuses (id, plan_id, name, category, position, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![status.id, status.plan_id, status.name, status.category, status.position, status.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Workflow status {}: {}", status.id, e)),
+        }
+    }
+
+    // Import workflow transitions
+    for transition in &data.workflow_transitions {
+        match conn.execute(
+            "INSERT INTO workflow_transitions (id, plan_id, from_status, to_status, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![transition.id, transition.plan_id, transition.from_status, transition.to_status, transition.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Workflow transition {}: {}", transition.id, e)),
+        }
+    }
+
+    // Import status history
+    for change in &data.status_history {
+        match conn.execute(
+            "INSERT INTO status_history (id, entity_type, entity_id, from_status, to_status, category, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![change.id, change.entity_type, change.entity_id, change.from_status, change.to_status, change.category, change.changed_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Status change {}: {}", change.id, e)),
+        }
+    }
+
+    // Import sprints
+    for sprint in &data.sprints {
+        match conn.execute(
+            "INSERT INTO sprints (id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![sprint.id, sprint.plan_id, sprint.name, sprint.goal, sprint.start_date, sprint.end_date, sprint.status, sprint.created_at, sprint.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Sprint {}: {}", sprint.id, e)),
+        }
+    }
+
+    // Import target values
+    for value in &data.target_values {
+        match conn.execute(
+            "INSERT INTO target_values (id, target_id, value, note, recorded_at, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![value.id, value.target_id, value.value, value.note, value.recorded_at, value.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Target value {}: {}", value.id, e)),
+        }
+    }
+
+    // Import step links
+    for step_link in &data.step_links {
+        if let Err(e) = check_step_link(conn, &step_link.step_id, &step_link.entity_type) {
+            errors.push(format!("Step link {}: {}", step_link.id, e));
+            continue;
+        }
+        match conn.execute(
+            "INSERT INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![step_link.id, step_link.step_id, step_link.entity_type, step_link.entity_id, step_link.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Step link {}: {}", step_link.id, e)),
+        }
+    }
+
+    // Import objectives
+    for objective in &data.objectives {
+        match conn.execute(
+            "INSERT INTO objectives (id, title, description, period, parent_id, status, final_score, grade_note, graded_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![objective.id, objective.title, objective.description, objective.period, objective.parent_id, objective.status, objective.final_score, objective.grade_note, objective.graded_at, objective.created_at, objective.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Objective {}: {}", objective.id, e)),
+        }
+    }
+
+    // Import key results
+    for key_result in &data.key_results {
+        match conn.execute(
+            "INSERT INTO key_results (id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, final_score, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![key_result.id, key_result.objective_id, key_result.title, key_result.kind, key_result.unit, key_result.start_value, key_result.target_value, key_result.current_value, key_result.score, key_result.confidence, key_result.final_score, key_result.created_at, key_result.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Key result {}: {}", key_result.id, e)),
+        }
+    }
+
     // Import settings
     if let Some(ref settings) = data.settings.daily_summary_settings {
         conn.execute(
@@ -439,8 +1033,8 @@ fn import_update(
     // Import plans (upsert)
     for plan in &data.plans {
         match conn.execute(
-            "INSERT OR REPLACE INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at],
+            "INSERT OR REPLACE INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![plan.id, plan.title, plan.description, plan.start_date, plan.end_date, plan.status, plan.created_at, plan.updated_at, plan.progress, plan.weighted_progress, plan.auto_complete],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Plan {}: {}", plan.id, e)),
@@ -450,8 +1044,8 @@ fn import_update(
     // Import targets (upsert)
     for target in &data.targets {
         match conn.execute(
-            "INSERT OR REPLACE INTO targets (id, title, description, due_date, status, progress, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at],
+            "INSERT OR REPLACE INTO targets (id, title, description, due_date, status, progress, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![target.id, target.title, target.description, target.due_date, target.status, target.progress, target.created_at, target.updated_at, target.metric_unit, target.metric_start, target.metric_goal, target.metric_direction, target.weight_mode],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Target {}: {}", target.id, e)),
@@ -461,8 +1055,8 @@ fn import_update(
     // Import todos (upsert)
     for todo in &data.todos {
         match conn.execute(
-            "INSERT OR REPLACE INTO todos (id, title, content, due_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.status, todo.priority, todo.created_at, todo.updated_at],
+            "INSERT OR REPLACE INTO todos (id, title, content, due_date, defer_until, status, priority, rollover_count, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![todo.id, todo.title, todo.content, todo.due_date, todo.defer_until, todo.status, todo.priority, todo.rollover_count, todo.estimate, todo.estimate_unit, todo.position, todo.created_at, todo.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Todo {}: {}", todo.id, e)),
@@ -472,8 +1066,8 @@ fn import_update(
     // Import tasks (upsert)
     for task in &data.tasks {
         match conn.execute(
-            "INSERT OR REPLACE INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.status, task.priority, task.created_at, task.updated_at],
+            "INSERT OR REPLACE INTO tasks (id, plan_id, title, description, start_date, end_date, defer_until, status, priority, estimate, estimate_unit, parent_task_id, position, sprint_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![task.id, task.plan_id, task.title, task.description, task.start_date, task.end_date, task.defer_until, task.status, task.priority, task.estimate, task.estimate_unit, task.parent_task_id, task.position, task.sprint_id, task.created_at, task.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Task {}: {}", task.id, e)),
@@ -483,8 +1077,8 @@ fn import_update(
     // Import steps (upsert)
     for step in &data.steps {
         match conn.execute(
-            "INSERT OR REPLACE INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.created_at, step.updated_at],
+            "INSERT OR REPLACE INTO steps (id, target_id, title, weight, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![step.id, step.target_id, step.title, step.weight, step.status, step.priority, step.estimate, step.estimate_unit, step.position, step.created_at, step.updated_at],
         ) {
             Ok(_) => imported += 1,
             Err(e) => errors.push(format!("Step {}: {}", step.id, e)),
@@ -513,6 +1107,168 @@ fn import_update(
         }
     }
 
+    // Import dependencies (upsert)
+    for dep in &data.dependencies {
+        match conn.execute(
+            "INSERT OR REPLACE INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![dep.id, dep.blocker_type, dep.blocker_id, dep.blocked_type, dep.blocked_id, dep.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Dependency {}: {}", dep.id, e)),
+        }
+    }
+
+    // Import attachments (upsert)
+    for attachment in &data.attachments {
+        if !is_valid_stored_name(&attachment.stored_name) {
+            errors.push(format!("Attachment {}: invalid file name", attachment.id));
+            continue;
+        }
+        match conn.execute(
+            "INSERT OR REPLACE INTO attachments (id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![attachment.id, attachment.entity_type, attachment.entity_id, attachment.file_name, attachment.stored_name, attachment.content_hash, attachment.size, attachment.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Attachment {}: {}", attachment.id, e)),
+        }
+    }
+
+    // Import comments (upsert)
+    for comment in &data.comments {
+        match conn.execute(
+            "INSERT OR REPLACE INTO comments (id, entity_type, entity_id, body, created_at, edited_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![comment.id, comment.entity_type, comment.entity_id, comment.body, comment.created_at, comment.edited_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Comment {}: {}", comment.id, e)),
+        }
+    }
+
+    // Import time entries (upsert)
+    for entry in &data.time_entries {
+        match conn.execute(
+            "INSERT OR REPLACE INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![entry.id, entry.entity_type, entry.entity_id, entry.started_at, entry.ended_at, entry.running_since, entry.duration_seconds, entry.status, entry.note, entry.created_at, entry.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Time entry {}: {}", entry.id, e)),
+        }
+    }
+
+    // Import pomodoro sessions (upsert)
+    for pomodoro_session in &data.pomodoro_sessions {
+        match conn.execute(
+            "INSERT OR REPLACE INTO pomodoro_sessions (id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![pomodoro_session.id, pomodoro_session.entity_type, pomodoro_session.entity_id, pomodoro_session.started_at, pomodoro_session.ended_at, pomodoro_session.planned_minutes, pomodoro_session.focus_seconds, pomodoro_session.status, pomodoro_session.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Pomodoro session {}: {}", pomodoro_session.id, e)),
+        }
+    }
+
+    // Import plan templates (upsert)
+    for template in &data.plan_templates {
+        match conn.execute(
+            "INSERT OR REPLACE INTO plan_templates (id, name, description, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![template.id, template.name, template.description, template.content, template.created_at, template.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Plan template {}: {}", template.id, e)),
+        }
+    }
+
+    // Import workflow statuses (upsert)
+    for status in &data.workflow_statuses {
+        match conn.execute(
+            "INSERT OR REPLACE INTO workflow_statuses (id, plan_id, name, category, position, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![status.id, status.plan_id, status.name, status.category, status.position, status.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Workflow status {}: {}", status.id, e)),
+        }
+    }
+
+    // Import workflow transitions (upsert)
+    for transition in &data.workflow_transitions {
+        match conn.execute(
+            "INSERT OR REPLACE INTO workflow_transitions (id, plan_id, from_status, to_status, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![transition.id, transition.plan_id, transition.from_status, transition.to_status, transition.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Workflow transition {}: {}", transition.id, e)),
+        }
+    }
+
+    // Import status history (upsert)
+    for change in &data.status_history {
+        match conn.execute(
+            "INSERT OR REPLACE INTO status_history (id, entity_type, entity_id, from_status, to_status, category, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![change.id, change.entity_type, change.entity_id, change.from_status, change.to_status, change.category, change.changed_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Status change {}: {}", change.id, e)),
+        }
+    }
+
+    // Import sprints (upsert)
+    for sprint in &data.sprints {
+        match conn.execute(
+            "INSERT OR REPLACE INTO sprints (id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![sprint.id, sprint.plan_id, sprint.name, sprint.goal, sprint.start_date, sprint.end_date, sprint.status, sprint.created_at, sprint.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Sprint {}: {}", sprint.id, e)),
+        }
+    }
+
+    // Import target values (upsert)
+    for value in &data.target_values {
+        match conn.execute(
+            "INSERT OR REPLACE INTO target_values (id, target_id, value, note, recorded_at, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![value.id, value.target_id, value.value, value.note, value.recorded_at, value.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Target value {}: {}", value.id, e)),
+        }
+    }
+
+    // Import step links (upsert)
+    for step_link in &data.step_links {
+        if let Err(e) = check_step_link(conn, &step_link.step_id, &step_link.entity_type) {
+            errors.push(format!("Step link {}: {}", step_link.id, e));
+            continue;
+        }
+        match conn.execute(
+            "INSERT OR REPLACE INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![step_link.id, step_link.step_id, step_link.entity_type, step_link.entity_id, step_link.created_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Step link {}: {}", step_link.id, e)),
+        }
+    }
+
+    // Import objectives (upsert)
+    for objective in &data.objectives {
+        match conn.execute(
+            "INSERT OR REPLACE INTO objectives (id, title, description, period, parent_id, status, final_score, grade_note, graded_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![objective.id, objective.title, objective.description, objective.period, objective.parent_id, objective.status, objective.final_score, objective.grade_note, objective.graded_at, objective.created_at, objective.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Objective {}: {}", objective.id, e)),
+        }
+    }
+
+    // Import key results (upsert)
+    for key_result in &data.key_results {
+        match conn.execute(
+            "INSERT OR REPLACE INTO key_results (id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, final_score, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![key_result.id, key_result.objective_id, key_result.title, key_result.kind, key_result.unit, key_result.start_value, key_result.target_value, key_result.current_value, key_result.score, key_result.confidence, key_result.final_score, key_result.created_at, key_result.updated_at],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Key result {}: {}", key_result.id, e)),
+        }
+    }
+
     // Import settings (replace)
     conn.execute("DELETE FROM daily_summary_settings", []).ok();
     if let Some(ref settings) = data.settings.daily_summary_settings {
@@ -1,5 +1,6 @@
 // Milestone CRUD commands
 
+use super::workflows::task_category_sql;
 use crate::log_command;
 use crate::models::Milestone;
 use crate::AppState;
@@ -14,32 +15,32 @@ pub fn calculate_milestone_progress(
     };
 
     match biz_type.as_str() {
-        // If linked to Plan, calculate from Tasks
+        // If linked to Plan, use the stored plan progress
         "plan" => {
             let mut stmt = conn
-                .prepare("SELECT COUNT(*), SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END) FROM tasks WHERE plan_id = ?")
+                .prepare("SELECT progress FROM plans WHERE id = ?")
                 .map_err(|e| e.to_string())?;
 
-            let (total, done): (i32, i32) = stmt
-                .query_row([biz_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            let progress: i32 = stmt
+                .query_row([biz_id], |row| row.get(0))
                 .map_err(|e| e.to_string())?;
 
-            if total == 0 {
-                return Ok(0);
-            }
-            Ok((done * 100) / total)
+            Ok(progress)
         }
         // If linked to Task, return status as progress (0 or 100)
         "task" => {
             let mut stmt = conn
-                .prepare("SELECT status FROM tasks WHERE id = ?")
+                .prepare(&format!(
+                    "SELECT {} FROM tasks WHERE id = ?",
+                    task_category_sql("tasks")
+                ))
                 .map_err(|e| e.to_string())?;
 
-            let status: String = stmt
+            let category: String = stmt
                 .query_row([biz_id], |row| row.get(0))
                 .map_err(|e| e.to_string())?;
 
-            Ok(if status == "done" { 100 } else { 0 })
+            Ok(if category == "done" { 100 } else { 0 })
         }
         // If linked to Target, use target progress
         "target" => {
@@ -1,19 +1,43 @@
 // Commands module - all Tauri commands
 
+pub mod attachments;
 pub mod batch;
+pub mod burndown;
 pub mod circulations;
 pub mod command_log;
+pub mod comments;
 pub mod dashboard;
+pub mod dependencies;
+pub mod duplicate;
+pub mod estimates;
 pub mod export;
+pub mod forecast;
+pub mod history;
 pub mod import;
 pub mod milestones;
 pub mod notification_plugins;
 pub mod notifications;
+pub mod okrs;
+pub mod ordering;
+pub mod plan_templates;
 pub mod plans;
+pub mod pomodoro;
+pub mod quick_add;
+pub mod schedule;
 pub mod search;
+pub mod snooze;
+pub mod sprints;
 pub mod statistics;
+pub mod step_links;
+pub mod step_weights;
 pub mod steps;
+pub mod subtasks;
 pub mod tags;
+pub mod target_metrics;
 pub mod targets;
 pub mod tasks;
+pub mod time_tracking;
+pub mod todo_policies;
 pub mod todos;
+pub mod workflows;
+pub mod workload;
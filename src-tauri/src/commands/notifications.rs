@@ -1,5 +1,6 @@
 // Notification system module
 
+use super::workflows::task_category_sql;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
@@ -230,7 +231,8 @@ pub fn get_due_reminders(state: tauri::State<AppState>) -> Result<Vec<DueReminde
     // Check todos
     let mut stmt = conn
         .prepare(
-            "SELECT id, title, due_date FROM todos WHERE status != 'done' AND due_date IS NOT NULL",
+            "SELECT id, title, due_date FROM todos WHERE status NOT IN ('done', 'archived') AND due_date IS NOT NULL
+             AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))",
         )
         .map_err(|e| e.to_string())?;
 
@@ -271,9 +273,11 @@ pub fn get_due_reminders(state: tauri::State<AppState>) -> Result<Vec<DueReminde
 
     // Check tasks
     let mut stmt = conn
-        .prepare(
-            "SELECT id, title, end_date FROM tasks WHERE status != 'done' AND end_date IS NOT NULL",
-        )
+        .prepare(&format!(
+            "SELECT id, title, end_date FROM tasks WHERE {} != 'done' AND end_date IS NOT NULL
+             AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))",
+            task_category_sql("tasks")
+        ))
         .map_err(|e| e.to_string())?;
 
     let task_iter = stmt
@@ -380,28 +384,31 @@ pub fn get_daily_summary(state: tauri::State<AppState>) -> Result<DailySummary,
 
     let pending_count: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM todos WHERE status = 'pending' OR status = 'in-progress'",
+            "SELECT COUNT(*) FROM todos WHERE (status = 'pending' OR status = 'in-progress')
+             AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))",
             [],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let overdue_count: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM todos WHERE status != 'done' AND due_date IS NOT NULL AND due_date < ?",
+        "SELECT COUNT(*) FROM todos WHERE status NOT IN ('done', 'archived') AND due_date IS NOT NULL AND due_date < ?
+         AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))",
         [&today],
         |row| row.get(0),
     ).unwrap_or(0);
 
     let completed_count: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM todos WHERE status = 'done' AND updated_at LIKE ?",
+            "SELECT COUNT(*) FROM todos WHERE status IN ('done', 'archived') AND updated_at LIKE ?",
             [&format!("{}%", today)],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let upcoming_count: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM todos WHERE status != 'done' AND due_date IS NOT NULL AND due_date > ?",
+        "SELECT COUNT(*) FROM todos WHERE status NOT IN ('done', 'archived') AND due_date IS NOT NULL AND due_date > ?
+         AND (defer_until IS NULL OR datetime(defer_until) <= datetime('now'))",
         [&today],
         |row| row.get(0),
     ).unwrap_or(0);
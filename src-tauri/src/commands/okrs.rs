@@ -0,0 +1,719 @@
+// OKR commands (objectives with scored key results per period)
+//
+// A key result scores 0.0-1.0: metric key results by how far the current value
+// has moved from the start value towards the target, manual ones as entered.
+// An objective scores the average of its key results. Grading at the end of
+// the period locks the scores; a graded objective is read-only until reopened.
+// Grades follow the usual OKR colours: green >= 0.7, yellow >= 0.4, else red.
+
+use crate::log_command;
+use crate::models::{KeyResult, Objective};
+use crate::AppState;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Key results with a confidence below this are reported at risk.
+const LOW_CONFIDENCE: f64 = 0.5;
+
+#[derive(Debug, Serialize)]
+pub struct OkrReport {
+    pub period: String,
+    pub average_score: Option<f64>, // Of the objectives with key results
+    pub graded_count: i32,
+    pub grades: HashMap<String, i32>, // green | yellow | red -> objectives
+    pub at_risk_key_results: Vec<String>,
+    pub objectives: Vec<ObjectiveReport>, // Aligned objectives nested under their parent
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObjectiveReport {
+    pub objective: Objective,
+    pub score: Option<f64>, // None without key results
+    pub grade: Option<String>,
+    pub confidence: Option<f64>,
+    pub key_results: Vec<KeyResultReport>,
+    pub children: Vec<ObjectiveReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyResultReport {
+    pub key_result: KeyResult,
+    pub score: f64,
+    pub grade: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const OBJECTIVE_COLUMNS: &str = "id, title, description, period, parent_id, status, final_score, grade_note, graded_at, created_at, updated_at";
+
+const KEY_RESULT_COLUMNS: &str = "id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, final_score, created_at, updated_at";
+
+fn row_to_objective(row: &rusqlite::Row) -> rusqlite::Result<Objective> {
+    Ok(Objective {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        period: row.get(3)?,
+        parent_id: row.get(4)?,
+        status: row.get(5)?,
+        final_score: row.get(6)?,
+        grade_note: row.get(7)?,
+        graded_at: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn row_to_key_result(row: &rusqlite::Row) -> rusqlite::Result<KeyResult> {
+    Ok(KeyResult {
+        id: row.get(0)?,
+        objective_id: row.get(1)?,
+        title: row.get(2)?,
+        kind: row.get(3)?,
+        unit: row.get(4)?,
+        start_value: row.get(5)?,
+        target_value: row.get(6)?,
+        current_value: row.get(7)?,
+        score: row.get(8)?,
+        confidence: row.get(9)?,
+        final_score: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+pub fn get_objective_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Objective, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM objectives WHERE id = ?", OBJECTIVE_COLUMNS),
+        [id],
+        row_to_objective,
+    )
+    .map_err(|_| format!("Objective {} not found", id))
+}
+
+fn get_key_result_by_id(conn: &rusqlite::Connection, id: &str) -> Result<KeyResult, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM key_results WHERE id = ?",
+            KEY_RESULT_COLUMNS
+        ),
+        [id],
+        row_to_key_result,
+    )
+    .map_err(|_| format!("Key result {} not found", id))
+}
+
+pub fn list_objectives(
+    conn: &rusqlite::Connection,
+    period: Option<&str>,
+) -> Result<Vec<Objective>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM objectives WHERE ?1 IS NULL OR period = ?1 ORDER BY period, created_at",
+            OBJECTIVE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let objectives = stmt
+        .query_map([period], row_to_objective)
+        .map_err(|e| e.to_string())?
+        .filter_map(|o| o.ok())
+        .collect();
+    Ok(objectives)
+}
+
+pub fn list_key_results(
+    conn: &rusqlite::Connection,
+    objective_id: &str,
+) -> Result<Vec<KeyResult>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM key_results WHERE objective_id = ? ORDER BY created_at",
+            KEY_RESULT_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let key_results = stmt
+        .query_map([objective_id], row_to_key_result)
+        .map_err(|e| e.to_string())?
+        .filter_map(|k| k.ok())
+        .collect();
+    Ok(key_results)
+}
+
+/// Checks a period key: a year, optionally with a quarter or half (2026-Q3, 2026-H1).
+fn validate_period(period: &str) -> Result<(), String> {
+    let (year, part) = match period.split_once('-') {
+        Some((year, part)) => (year, Some(part)),
+        None => (period, None),
+    };
+    let year_ok = year.len() == 4 && year.chars().all(|c| c.is_ascii_digit());
+    let part_ok = match part {
+        None => true,
+        Some(part) => matches!(part, "Q1" | "Q2" | "Q3" | "Q4" | "H1" | "H2"),
+    };
+    if year_ok && part_ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid period: {} (expected YYYY, YYYY-Qn or YYYY-Hn)",
+            period
+        ))
+    }
+}
+
+fn validate_unit_interval(name: &str, value: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("{} must be between 0.0 and 1.0", name))
+    }
+}
+
+/// Checks that `parent_id` exists and is not `id` or one of its descendants.
+fn check_alignment(
+    conn: &rusqlite::Connection,
+    id: Option<&str>,
+    parent_id: &str,
+) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    let mut current = Some(get_objective_by_id(conn, parent_id)?);
+    while let Some(objective) = current {
+        if Some(objective.id.as_str()) == id {
+            return Err("Objective cannot be aligned to itself or a child".to_string());
+        }
+        if !seen.insert(objective.id.clone()) {
+            return Err("Objective alignment contains a cycle".to_string());
+        }
+        current = match objective.parent_id {
+            Some(parent) => Some(get_objective_by_id(conn, &parent)?),
+            None => None,
+        };
+    }
+    Ok(())
+}
+
+/// Checks the alignment of imported objectives. Invalid parents are cleared;
+/// returns the errors.
+pub fn detach_invalid_alignments(
+    conn: &rusqlite::Connection,
+    objective_ids: &[String],
+) -> Result<Vec<String>, String> {
+    let mut errors = Vec::new();
+    for id in objective_ids {
+        let parent_id: Option<String> = conn
+            .query_row(
+                "SELECT parent_id FROM objectives WHERE id = ?",
+                [id],
+                |row| row.get(0),
+            )
+            .ok()
+            .flatten();
+        let Some(parent_id) = parent_id else {
+            continue;
+        };
+        if let Err(e) = check_alignment(conn, Some(id), &parent_id) {
+            conn.execute("UPDATE objectives SET parent_id = NULL WHERE id = ?", [id])
+                .map_err(|e| e.to_string())?;
+            errors.push(format!("Objective {}: {}", id, e));
+        }
+    }
+    Ok(errors)
+}
+
+fn check_not_graded(objective: &Objective) -> Result<(), String> {
+    if objective.status == "graded" {
+        return Err("Objective is graded; reopen it to make changes".to_string());
+    }
+    Ok(())
+}
+
+fn round_score(score: f64) -> f64 {
+    (score * 100.0).round() / 100.0
+}
+
+/// Live score of a key result (0.0-1.0), ignoring any locked final score.
+pub fn key_result_score(key_result: &KeyResult) -> f64 {
+    let score = match key_result.kind.as_str() {
+        "metric" => match (key_result.start_value, key_result.target_value) {
+            (Some(start), Some(target)) if target != start => {
+                let current = key_result.current_value.unwrap_or(start);
+                (current - start) / (target - start)
+            }
+            _ => 0.0,
+        },
+        _ => key_result.score.unwrap_or(0.0),
+    };
+    round_score(score.clamp(0.0, 1.0))
+}
+
+pub fn grade_for(score: f64) -> &'static str {
+    if score >= 0.7 {
+        "green"
+    } else if score >= 0.4 {
+        "yellow"
+    } else {
+        "red"
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(round_score(
+            values.iter().sum::<f64>() / values.len() as f64,
+        ))
+    }
+}
+
+/// Scores of an objective and its key results; locked scores once graded.
+fn objective_report(
+    conn: &rusqlite::Connection,
+    objective: Objective,
+) -> Result<ObjectiveReport, String> {
+    let key_results: Vec<KeyResultReport> = list_key_results(conn, &objective.id)?
+        .into_iter()
+        .map(|key_result| {
+            let score = key_result
+                .final_score
+                .unwrap_or_else(|| key_result_score(&key_result));
+            KeyResultReport {
+                grade: grade_for(score).to_string(),
+                score,
+                key_result,
+            }
+        })
+        .collect();
+
+    let scores: Vec<f64> = key_results.iter().map(|k| k.score).collect();
+    let confidences: Vec<f64> = key_results
+        .iter()
+        .map(|k| k.key_result.confidence)
+        .collect();
+    let score = objective.final_score.or_else(|| average(&scores));
+
+    Ok(ObjectiveReport {
+        grade: score.map(|s| grade_for(s).to_string()),
+        score,
+        confidence: average(&confidences),
+        key_results,
+        children: Vec::new(),
+        objective,
+    })
+}
+
+/// Locks the current scores of an objective and its key results.
+pub fn grade_scores(
+    conn: &rusqlite::Connection,
+    id: &str,
+    note: Option<&str>,
+) -> Result<(), String> {
+    let objective = get_objective_by_id(conn, id)?;
+    check_not_graded(&objective)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut scores = Vec::new();
+    for key_result in list_key_results(conn, id)? {
+        let score = key_result_score(&key_result);
+        scores.push(score);
+        conn.execute(
+            "UPDATE key_results SET final_score = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![score, now, key_result.id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    conn.execute(
+        "UPDATE objectives SET status = 'graded', final_score = ?, grade_note = ?, graded_at = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![average(&scores), note, now, now, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Report of a period. Objectives aligned to another objective of the period
+/// are nested under it; the rest are listed at the top level.
+pub fn okr_report(conn: &rusqlite::Connection, period: &str) -> Result<OkrReport, String> {
+    let mut reports = Vec::new();
+    for objective in list_objectives(conn, Some(period))? {
+        reports.push(objective_report(conn, objective)?);
+    }
+
+    let scores: Vec<f64> = reports.iter().filter_map(|r| r.score).collect();
+    let mut grades: HashMap<String, i32> = HashMap::new();
+    for grade in reports.iter().filter_map(|r| r.grade.clone()) {
+        *grades.entry(grade).or_insert(0) += 1;
+    }
+    let at_risk_key_results = reports
+        .iter()
+        .filter(|r| r.objective.status != "graded")
+        .flat_map(|r| &r.key_results)
+        .filter(|k| k.key_result.confidence < LOW_CONFIDENCE)
+        .map(|k| k.key_result.id.clone())
+        .collect();
+    let graded_count = reports
+        .iter()
+        .filter(|r| r.objective.status == "graded")
+        .count() as i32;
+
+    // Attach children deepest first so nested children move with their parent
+    let in_period: HashMap<String, Option<String>> = reports
+        .iter()
+        .map(|r| (r.objective.id.clone(), r.objective.parent_id.clone()))
+        .collect();
+    let depth = |id: &str| {
+        let mut depth = 0;
+        let mut seen = HashSet::from([id.to_string()]);
+        let mut current = in_period.get(id).cloned().flatten();
+        while let Some(parent) = current.filter(|p| in_period.contains_key(p)) {
+            if !seen.insert(parent.clone()) {
+                break;
+            }
+            depth += 1;
+            current = in_period.get(&parent).cloned().flatten();
+        }
+        depth
+    };
+    let mut by_depth: Vec<(usize, ObjectiveReport)> = reports
+        .into_iter()
+        .map(|r| (depth(&r.objective.id), r))
+        .collect();
+    by_depth.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+
+    let mut children: HashMap<String, Vec<ObjectiveReport>> = HashMap::new();
+    let mut objectives = Vec::new();
+    for (_, mut report) in by_depth {
+        if let Some(mut own) = children.remove(&report.objective.id) {
+            own.sort_by(|a, b| a.objective.created_at.cmp(&b.objective.created_at));
+            report.children = own;
+        }
+        match report
+            .objective
+            .parent_id
+            .clone()
+            .filter(|p| in_period.contains_key(p))
+        {
+            Some(parent) => children.entry(parent).or_default().push(report),
+            None => objectives.push(report),
+        }
+    }
+    objectives.sort_by(|a, b| a.objective.created_at.cmp(&b.objective.created_at));
+
+    Ok(OkrReport {
+        period: period.to_string(),
+        average_score: average(&scores),
+        graded_count,
+        grades,
+        at_risk_key_results,
+        objectives,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_objectives(
+    state: tauri::State<AppState>,
+    period: Option<String>,
+) -> Result<Vec<Objective>, String> {
+    log_command!("get_objectives", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        list_objectives(&conn, period.as_deref())
+    })
+}
+
+#[tauri::command]
+pub fn create_objective(
+    state: tauri::State<AppState>,
+    title: String,
+    description: Option<String>,
+    period: String,
+    parent_id: Option<String>,
+) -> Result<Objective, String> {
+    log_command!("create_objective", {
+        validate_period(&period)?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        if let Some(parent_id) = &parent_id {
+            check_alignment(&conn, None, parent_id)?;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO objectives (id, title, description, period, parent_id, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'active', ?, ?)",
+            rusqlite::params![id, title, description, period, parent_id, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_objective_by_id(&conn, &id)
+    })
+}
+
+/// Updates an objective. An empty `parent_id` removes the alignment.
+#[tauri::command]
+pub fn update_objective(
+    state: tauri::State<AppState>,
+    id: String,
+    title: Option<String>,
+    description: Option<String>,
+    period: Option<String>,
+    parent_id: Option<String>,
+) -> Result<Objective, String> {
+    log_command!("update_objective", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let objective = get_objective_by_id(&conn, &id)?;
+        check_not_graded(&objective)?;
+
+        if let Some(period) = &period {
+            validate_period(period)?;
+        }
+        let new_parent_id = match parent_id {
+            Some(p) if p.is_empty() => None,
+            Some(p) => {
+                check_alignment(&conn, Some(&id), &p)?;
+                Some(p)
+            }
+            None => objective.parent_id,
+        };
+
+        conn.execute(
+            "UPDATE objectives SET title = ?, description = ?, period = ?, parent_id = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![
+                title.unwrap_or(objective.title),
+                description.or(objective.description),
+                period.unwrap_or(objective.period),
+                new_parent_id,
+                chrono::Utc::now().to_rfc3339(),
+                id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_objective_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_objective(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_objective", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        // Aligned objectives stay, without the alignment
+        tx.execute(
+            "UPDATE objectives SET parent_id = NULL WHERE parent_id = ?",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM key_results WHERE objective_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM objectives WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_key_results(
+    state: tauri::State<AppState>,
+    objective_id: String,
+) -> Result<Vec<KeyResult>, String> {
+    log_command!("get_key_results", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        list_key_results(&conn, &objective_id)
+    })
+}
+
+/// Adds a key result. Metric key results need distinct start and target
+/// values; manual ones start at score 0.0 and ignore the values.
+#[tauri::command]
+pub fn create_key_result(
+    state: tauri::State<AppState>,
+    objective_id: String,
+    title: String,
+    kind: String,
+    unit: Option<String>,
+    start_value: Option<f64>,
+    target_value: Option<f64>,
+    confidence: Option<f64>,
+) -> Result<KeyResult, String> {
+    log_command!("create_key_result", {
+        let confidence = confidence.unwrap_or(0.5);
+        validate_unit_interval("confidence", confidence)?;
+        let (score, start_value, target_value) = match kind.as_str() {
+            "metric" => {
+                let (Some(start), Some(target)) = (start_value, target_value) else {
+                    return Err("Metric key results need start and target values".to_string());
+                };
+                if !start.is_finite() || !target.is_finite() || start == target {
+                    return Err("Start and target values must be distinct numbers".to_string());
+                }
+                (None, Some(start), Some(target))
+            }
+            "manual" => (Some(0.0), None, None),
+            _ => return Err("kind must be 'metric' or 'manual'".to_string()),
+        };
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        check_not_graded(&get_objective_by_id(&conn, &objective_id)?)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO key_results (id, objective_id, title, kind, unit, start_value, target_value, current_value, score, confidence, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, objective_id, title, kind, unit, start_value, target_value, start_value, score, confidence, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_key_result_by_id(&conn, &id)
+    })
+}
+
+/// Updates a key result; also used for check-ins of the current value (metric),
+/// the score (manual) and the confidence.
+#[tauri::command]
+pub fn update_key_result(
+    state: tauri::State<AppState>,
+    id: String,
+    title: Option<String>,
+    unit: Option<String>,
+    target_value: Option<f64>,
+    current_value: Option<f64>,
+    score: Option<f64>,
+    confidence: Option<f64>,
+) -> Result<KeyResult, String> {
+    log_command!("update_key_result", {
+        if let Some(score) = score {
+            validate_unit_interval("score", score)?;
+        }
+        if let Some(confidence) = confidence {
+            validate_unit_interval("confidence", confidence)?;
+        }
+        if current_value.is_some_and(|v| !v.is_finite()) {
+            return Err("Value must be a finite number".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let key_result = get_key_result_by_id(&conn, &id)?;
+        check_not_graded(&get_objective_by_id(&conn, &key_result.objective_id)?)?;
+
+        if score.is_some() && key_result.kind == "metric" {
+            return Err("Metric key results are scored from their values".to_string());
+        }
+        let new_target_value = target_value.or(key_result.target_value);
+        if key_result.kind == "metric"
+            && (new_target_value == key_result.start_value
+                || new_target_value.is_some_and(|v| !v.is_finite()))
+        {
+            return Err("Start and target values must be distinct numbers".to_string());
+        }
+
+        conn.execute(
+            "UPDATE key_results SET title = ?, unit = ?, target_value = ?, current_value = ?, score = ?, confidence = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![
+                title.unwrap_or(key_result.title),
+                unit.or(key_result.unit),
+                new_target_value,
+                current_value.or(key_result.current_value),
+                score.or(key_result.score),
+                confidence.unwrap_or(key_result.confidence),
+                chrono::Utc::now().to_rfc3339(),
+                id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_key_result_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_key_result(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_key_result", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let key_result = get_key_result_by_id(&conn, &id)?;
+        check_not_graded(&get_objective_by_id(&conn, &key_result.objective_id)?)?;
+
+        conn.execute("DELETE FROM key_results WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Grades an objective at the end of its period, locking its score and the
+/// scores of its key results.
+#[tauri::command]
+pub fn grade_objective(
+    state: tauri::State<AppState>,
+    id: String,
+    note: Option<String>,
+) -> Result<ObjectiveReport, String> {
+    log_command!("grade_objective", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        grade_scores(&tx, &id, note.as_deref())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        objective_report(&conn, get_objective_by_id(&conn, &id)?)
+    })
+}
+
+/// Grades every objective of a period that is not graded yet.
+#[tauri::command]
+pub fn grade_okr_period(
+    state: tauri::State<AppState>,
+    period: String,
+) -> Result<OkrReport, String> {
+    log_command!("grade_okr_period", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        for objective in list_objectives(&tx, Some(&period))? {
+            if objective.status != "graded" {
+                grade_scores(&tx, &objective.id, None)?;
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        okr_report(&conn, &period)
+    })
+}
+
+/// Unlocks a graded objective; its key results are scored live again.
+#[tauri::command]
+pub fn reopen_objective(state: tauri::State<AppState>, id: String) -> Result<Objective, String> {
+    log_command!("reopen_objective", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE key_results SET final_score = NULL, updated_at = ? WHERE objective_id = ?",
+            rusqlite::params![now, id],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE objectives SET status = 'active', final_score = NULL, graded_at = NULL, updated_at = ? WHERE id = ?",
+            rusqlite::params![now, id],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_objective_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn get_okr_report(state: tauri::State<AppState>, period: String) -> Result<OkrReport, String> {
+    log_command!("get_okr_report", {
+        validate_period(&period)?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        okr_report(&conn, &period)
+    })
+}
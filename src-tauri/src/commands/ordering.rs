@@ -0,0 +1,487 @@
+// Manual ordering (fractional index keys for todos, tasks and steps)
+//
+// A position is a base-62 string key. A key between two neighbours can always
+// be generated, so moving an item only rewrites that item. Keys have an
+// integer part (head letter + digits) and an optional fraction, as in the
+// common "fractional-indexing" scheme: 'a0' < 'a1' < ... < 'az' < 'b00'.
+
+use super::step_weights::{rebalance_weights, target_weight_mode};
+use super::subtasks::move_task_to;
+use crate::log_command;
+use crate::AppState;
+use std::collections::HashSet;
+
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const SMALLEST_INTEGER: &str = "A00000000000000000000000000";
+
+// ============================================================================
+// Key Generation
+// ============================================================================
+
+fn digit_value(c: u8) -> Result<usize, String> {
+    DIGITS
+        .iter()
+        .position(|&d| d == c)
+        .ok_or_else(|| format!("Invalid position character '{}'", c as char))
+}
+
+/// Key strictly between fraction parts `a` and `b` (None = no upper bound).
+fn midpoint(a: &str, b: Option<&str>) -> Result<String, String> {
+    if let Some(b) = b {
+        // Common prefix, with `a` padded by zeros
+        let (a_bytes, b_bytes) = (a.as_bytes(), b.as_bytes());
+        let mut n = 0;
+        while n < b_bytes.len() && a_bytes.get(n).copied().unwrap_or(b'0') == b_bytes[n] {
+            n += 1;
+        }
+        if n > 0 {
+            let rest_a = a.get(n..).unwrap_or("");
+            return Ok(format!("{}{}", &b[..n], midpoint(rest_a, Some(&b[n..]))?));
+        }
+    }
+
+    let digit_a = match a.as_bytes().first() {
+        Some(&c) => digit_value(c)?,
+        None => 0,
+    };
+    let digit_b = match b.and_then(|b| b.as_bytes().first().copied()) {
+        Some(c) => digit_value(c)?,
+        None => DIGITS.len(),
+    };
+
+    if digit_b - digit_a > 1 {
+        let mid = (digit_a + digit_b).div_ceil(2);
+        Ok((DIGITS[mid] as char).to_string())
+    } else if let Some(b) = b.filter(|b| b.len() > 1) {
+        Ok(b[..1].to_string())
+    } else {
+        Ok(format!(
+            "{}{}",
+            DIGITS[digit_a] as char,
+            midpoint(a.get(1..).unwrap_or(""), None)?
+        ))
+    }
+}
+
+fn integer_length(head: u8) -> Result<usize, String> {
+    match head {
+        b'a'..=b'z' => Ok((head - b'a') as usize + 2),
+        b'A'..=b'Z' => Ok((b'Z' - head) as usize + 2),
+        _ => Err(format!("Invalid position head '{}'", head as char)),
+    }
+}
+
+fn integer_part(key: &str) -> Result<&str, String> {
+    let head = *key.as_bytes().first().ok_or("Empty position")?;
+    let length = integer_length(head)?;
+    key.get(..length)
+        .ok_or_else(|| format!("Invalid position '{}'", key))
+}
+
+fn validate_key(key: &str) -> Result<(), String> {
+    if key == SMALLEST_INTEGER {
+        return Err(format!("Invalid position '{}'", key));
+    }
+    let integer = integer_part(key)?;
+    for &c in key.as_bytes() {
+        digit_value(c)?;
+    }
+    if key.len() > integer.len() && key.ends_with('0') {
+        return Err(format!("Invalid position '{}'", key));
+    }
+    Ok(())
+}
+
+fn increment_integer(x: &str) -> Result<Option<String>, String> {
+    let head = x.as_bytes()[0];
+    let mut digits: Vec<u8> = x.as_bytes()[1..].to_vec();
+
+    let mut carry = true;
+    for digit in digits.iter_mut().rev() {
+        let value = digit_value(*digit)? + 1;
+        if value == DIGITS.len() {
+            *digit = b'0';
+        } else {
+            *digit = DIGITS[value];
+            carry = false;
+            break;
+        }
+    }
+
+    if carry {
+        if head == b'Z' {
+            return Ok(Some("a0".to_string()));
+        }
+        if head == b'z' {
+            return Ok(None);
+        }
+        let new_head = head + 1;
+        if new_head > b'a' {
+            digits.push(b'0');
+        } else {
+            digits.pop();
+        }
+        let mut result = vec![new_head];
+        result.extend(digits);
+        return Ok(Some(String::from_utf8(result).map_err(|e| e.to_string())?));
+    }
+
+    let mut result = vec![head];
+    result.extend(digits);
+    Ok(Some(String::from_utf8(result).map_err(|e| e.to_string())?))
+}
+
+fn decrement_integer(x: &str) -> Result<Option<String>, String> {
+    let head = x.as_bytes()[0];
+    let mut digits: Vec<u8> = x.as_bytes()[1..].to_vec();
+
+    let mut borrow = true;
+    for digit in digits.iter_mut().rev() {
+        let value = digit_value(*digit)?;
+        if value == 0 {
+            *digit = DIGITS[DIGITS.len() - 1];
+        } else {
+            *digit = DIGITS[value - 1];
+            borrow = false;
+            break;
+        }
+    }
+
+    if borrow {
+        if head == b'a' {
+            return Ok(Some("Zz".to_string()));
+        }
+        if head == b'A' {
+            return Ok(None);
+        }
+        let new_head = head - 1;
+        if new_head < b'Z' {
+            digits.push(DIGITS[DIGITS.len() - 1]);
+        } else {
+            digits.pop();
+        }
+        let mut result = vec![new_head];
+        result.extend(digits);
+        return Ok(Some(String::from_utf8(result).map_err(|e| e.to_string())?));
+    }
+
+    let mut result = vec![head];
+    result.extend(digits);
+    Ok(Some(String::from_utf8(result).map_err(|e| e.to_string())?))
+}
+
+/// Generates a key that sorts after `a` and before `b` (None = open end).
+pub fn key_between(a: Option<&str>, b: Option<&str>) -> Result<String, String> {
+    if let Some(a) = a {
+        validate_key(a)?;
+    }
+    if let Some(b) = b {
+        validate_key(b)?;
+    }
+
+    match (a, b) {
+        (None, None) => Ok("a0".to_string()),
+        (None, Some(b)) => {
+            let ib = integer_part(b)?;
+            let fb = &b[ib.len()..];
+            if ib == SMALLEST_INTEGER {
+                return Ok(format!("{}{}", ib, midpoint("", Some(fb))?));
+            }
+            if ib.len() < b.len() {
+                return Ok(ib.to_string());
+            }
+            decrement_integer(ib)?.ok_or_else(|| "Cannot place before the first item".to_string())
+        }
+        (Some(a), None) => {
+            let ia = integer_part(a)?;
+            let fa = &a[ia.len()..];
+            match increment_integer(ia)? {
+                Some(next) => Ok(next),
+                None => Ok(format!("{}{}", ia, midpoint(fa, None)?)),
+            }
+        }
+        (Some(a), Some(b)) => {
+            if a >= b {
+                return Err(format!("Position '{}' is not before '{}'", a, b));
+            }
+            let ia = integer_part(a)?;
+            let fa = &a[ia.len()..];
+            let ib = integer_part(b)?;
+            let fb = &b[ib.len()..];
+            if ia == ib {
+                return Ok(format!("{}{}", ia, midpoint(fa, Some(fb))?));
+            }
+            let next = increment_integer(ia)?
+                .ok_or_else(|| "Cannot place after the last item".to_string())?;
+            if next.as_str() < b {
+                return Ok(next);
+            }
+            Ok(format!("{}{}", ia, midpoint(fa, None)?))
+        }
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Table and scope column (list the item belongs to) of an orderable entity.
+fn entity_table(entity_type: &str) -> Result<(&'static str, Option<&'static str>), String> {
+    match entity_type {
+        "todo" => Ok(("todos", None)),
+        "task" => Ok(("tasks", Some("plan_id"))),
+        "step" => Ok(("steps", Some("target_id"))),
+        _ => Err(format!(
+            "Invalid entity_type '{}'. Use 'todo', 'task' or 'step'",
+            entity_type
+        )),
+    }
+}
+
+/// Position for a new item at the end of its list (`scope_id` is the plan
+/// for tasks and the target for steps).
+pub fn next_position(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    scope_id: Option<&str>,
+) -> Result<String, String> {
+    let (table, scope) = entity_table(entity_type)?;
+    let last: Option<String> = match scope {
+        Some(column) => conn.query_row(
+            &format!("SELECT MAX(position) FROM {} WHERE {} = ?", table, column),
+            [scope_id.unwrap_or_default()],
+            |row| row.get(0),
+        ),
+        None => conn.query_row(&format!("SELECT MAX(position) FROM {}", table), [], |row| {
+            row.get(0)
+        }),
+    }
+    .map_err(|e| e.to_string())?;
+
+    key_between(last.as_deref(), None)
+}
+
+/// Clears positions that are not valid keys or repeat an earlier item's key in
+/// the same list, so `backfill_positions` gives those items fresh ones.
+pub fn clear_invalid_positions(conn: &rusqlite::Connection) -> Result<(), String> {
+    for entity_type in ["todo", "task", "step"] {
+        let (table, scope) = entity_table(entity_type)?;
+        let scope_column = scope.unwrap_or("NULL");
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, {}, position FROM {} WHERE position IS NOT NULL ORDER BY created_at, id",
+                scope_column, table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows: Vec<(String, Option<String>, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut seen = HashSet::new();
+        for (id, scope_id, position) in rows {
+            if validate_key(&position).is_ok() && seen.insert((scope_id, position)) {
+                continue;
+            }
+            conn.execute(
+                &format!("UPDATE {} SET position = NULL WHERE id = ?", table),
+                [&id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Gives items without a position one after the current last item of their
+/// list, in creation order (older databases and imported data).
+pub fn backfill_positions(conn: &rusqlite::Connection) -> Result<(), String> {
+    for entity_type in ["todo", "task", "step"] {
+        let (table, scope) = entity_table(entity_type)?;
+        let scope_column = scope.unwrap_or("NULL");
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, {} FROM {} WHERE position IS NULL ORDER BY created_at",
+                scope_column, table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows: Vec<(String, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        for (id, scope_id) in rows {
+            let position = next_position(conn, entity_type, scope_id.as_deref())?;
+            conn.execute(
+                &format!("UPDATE {} SET position = ? WHERE id = ?", table),
+                rusqlite::params![position, id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Position and scope of an item.
+fn item_position(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    id: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    let (table, scope) = entity_table(entity_type)?;
+    conn.query_row(
+        &format!(
+            "SELECT position, {} FROM {} WHERE id = ?",
+            scope.unwrap_or("NULL"),
+            table
+        ),
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|_| format!("{} {} not found", entity_type, id))
+}
+
+/// Moves a step to another target. Manual targets must stay within 100%;
+/// targets with an automatic weight mode are rebalanced on both sides.
+fn move_step_to(conn: &rusqlite::Connection, id: &str, target_id: &str) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM targets WHERE id = ?)",
+            [target_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("Target {} not found", target_id));
+    }
+
+    let (old_target_id, weight, other_weight): (String, i32, i32) = conn
+        .query_row(
+            "SELECT target_id, weight, (SELECT COALESCE(SUM(weight), 0) FROM steps WHERE target_id = ?2)
+             FROM steps WHERE id = ?1",
+            rusqlite::params![id, target_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    if target_weight_mode(conn, target_id)? == "manual" && other_weight + weight > 100 {
+        return Err(format!(
+            "Step weights would exceed 100%. Target: {}%, Step: {}%",
+            other_weight, weight
+        ));
+    }
+
+    conn.execute(
+        "UPDATE steps SET target_id = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![target_id, chrono::Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|e| e.to_string())?;
+    rebalance_weights(conn, &old_target_id, None)?;
+    rebalance_weights(conn, target_id, Some(id))?;
+    Ok(())
+}
+
+/// Places an item between `before_id` (the item that ends up directly above
+/// it) and `after_id` (directly below). Neighbours from another plan or
+/// target move the task or step there; `scope_id` names the plan or target
+/// when moving into an empty list. Returns the new position.
+pub fn reorder_item(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    id: &str,
+    before_id: Option<&str>,
+    after_id: Option<&str>,
+    scope_id: Option<&str>,
+) -> Result<String, String> {
+    let (table, _) = entity_table(entity_type)?;
+    let (_, current_scope) = item_position(conn, entity_type, id)?;
+
+    let neighbour =
+        |neighbour_id: Option<&str>| -> Result<Option<(String, Option<String>)>, String> {
+            let Some(neighbour_id) = neighbour_id else {
+                return Ok(None);
+            };
+            if neighbour_id == id {
+                return Err("An item cannot be placed next to itself".to_string());
+            }
+            let (position, scope) = item_position(conn, entity_type, neighbour_id)?;
+            Ok(Some((position.unwrap_or_default(), scope)))
+        };
+    let before = neighbour(before_id)?;
+    let after = neighbour(after_id)?;
+
+    let target_scope = match (&before, &after) {
+        (Some((_, a)), Some((_, b))) if a != b => {
+            return Err("Neighbours belong to different lists".to_string())
+        }
+        (Some((_, scope)), _) | (_, Some((_, scope))) => {
+            if scope_id.is_some_and(|s| Some(s) != scope.as_deref()) {
+                return Err("Neighbours belong to another list".to_string());
+            }
+            scope.clone()
+        }
+        (None, None) => scope_id.map(String::from).or_else(|| current_scope.clone()),
+    };
+
+    if target_scope != current_scope {
+        let target = target_scope.as_deref().unwrap_or_default();
+        match entity_type {
+            "task" => move_task_to(conn, id, None, Some(target.to_string()))?,
+            "step" => move_step_to(conn, id, target)?,
+            _ => {}
+        }
+    }
+
+    let position = match (&before, &after) {
+        (None, None) => next_position(conn, entity_type, target_scope.as_deref())?,
+        _ => key_between(
+            before.as_ref().map(|(p, _)| p.as_str()),
+            after.as_ref().map(|(p, _)| p.as_str()),
+        )?,
+    };
+
+    conn.execute(
+        &format!(
+            "UPDATE {} SET position = ?, updated_at = ? WHERE id = ?",
+            table
+        ),
+        rusqlite::params![position, chrono::Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(position)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn reorder(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    id: String,
+    before_id: Option<String>,
+    after_id: Option<String>,
+    scope_id: Option<String>,
+) -> Result<String, String> {
+    log_command!("reorder", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let position = reorder_item(
+            &tx,
+            &entity_type,
+            &id,
+            before_id.as_deref(),
+            after_id.as_deref(),
+            scope_id.as_deref(),
+        )?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(position)
+    })
+}
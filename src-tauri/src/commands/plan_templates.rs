@@ -0,0 +1,598 @@
+// Plan template commands (reusable plan shapes with relative dates)
+//
+// Dates are stored as day offsets from the plan start, so a template can be
+// instantiated from any start date.
+
+use super::ordering::next_position;
+use super::plans::{refresh_plan_progress, MAX_SHIFT_DAYS};
+use super::schedule::parse_day;
+use super::subtasks::MAX_TASK_DEPTH;
+use super::tags::ensure_tag;
+use crate::log_command;
+use crate::models::PlanTemplate;
+use crate::AppState;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlanTemplateContent {
+    pub plan_title: String,
+    pub plan_description: Option<String>,
+    pub end_offset: Option<i64>, // Plan end, days after start
+    #[serde(default)]
+    pub weighted_progress: bool,
+    #[serde(default)]
+    pub auto_complete: bool,
+    #[serde(default)]
+    pub tags: Vec<String>, // Tag names
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+    #[serde(default)]
+    pub dependencies: Vec<TemplateDependency>,
+    #[serde(default)]
+    pub milestones: Vec<TemplateMilestone>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateTask {
+    pub key: String, // Id within the template
+    pub parent_key: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: String,
+    pub start_offset: Option<i64>,
+    pub end_offset: Option<i64>,
+    pub estimate: Option<f64>,
+    pub estimate_unit: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateDependency {
+    pub blocker_key: String,
+    pub blocked_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateMilestone {
+    #[serde(default)]
+    pub key: Option<String>, // Id within the template
+    pub title: String,
+    pub target_offset: Option<i64>,
+    pub task_key: Option<String>, // None = linked to the plan
+}
+
+/// File format of an exported template.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanTemplateFile {
+    pub version: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: PlanTemplateContent,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn offset_from(base: NaiveDate, value: Option<&str>) -> Option<i64> {
+    value.and_then(parse_day).map(|day| (day - base).num_days())
+}
+
+fn date_at(start: NaiveDate, offset: Option<i64>) -> Result<Option<String>, String> {
+    let Some(days) = offset else {
+        return Ok(None);
+    };
+    chrono::Duration::try_days(days)
+        .and_then(|d| start.checked_add_signed(d))
+        .map(|day| Some(day.format("%Y-%m-%d").to_string()))
+        .ok_or_else(|| format!("Template offset out of range: {} days", days))
+}
+
+/// Checks that offsets stay within `MAX_SHIFT_DAYS`, that task parents form a
+/// tree at most `MAX_TASK_DEPTH` levels deep and that dependencies have no
+/// cycles. Unknown parent keys make root tasks, as in `instantiate`.
+pub fn validate_content(content: &PlanTemplateContent) -> Result<(), String> {
+    let offsets = content
+        .tasks
+        .iter()
+        .flat_map(|t| [t.start_offset, t.end_offset])
+        .chain(content.milestones.iter().map(|m| m.target_offset))
+        .chain([content.end_offset]);
+    for offset in offsets.flatten() {
+        if !(-MAX_SHIFT_DAYS..=MAX_SHIFT_DAYS).contains(&offset) {
+            return Err(format!("Template offset out of range: {} days", offset));
+        }
+    }
+
+    let parents: HashMap<&str, &str> = content
+        .tasks
+        .iter()
+        .filter_map(|t| Some((t.key.as_str(), t.parent_key.as_deref()?)))
+        .collect();
+    for task in &content.tasks {
+        let mut seen = HashSet::from([task.key.as_str()]);
+        let mut current = parents.get(task.key.as_str());
+        while let Some(parent) = current.filter(|p| content.tasks.iter().any(|t| t.key == **p)) {
+            if !seen.insert(parent) {
+                return Err(format!("Template task {} is its own ancestor", task.key));
+            }
+            if seen.len() > MAX_TASK_DEPTH {
+                return Err(format!(
+                    "Subtasks can be nested at most {} levels deep",
+                    MAX_TASK_DEPTH
+                ));
+            }
+            current = parents.get(parent);
+        }
+    }
+
+    let mut blocks: HashMap<&str, Vec<&str>> = HashMap::new();
+    for dep in &content.dependencies {
+        let (blocker, blocked) = (dep.blocker_key.as_str(), dep.blocked_key.as_str());
+        // Adding blocker -> blocked closes a cycle if blocked already leads to blocker
+        let mut seen = HashSet::new();
+        let mut queue = vec![blocked];
+        while let Some(current) = queue.pop() {
+            if current == blocker {
+                return Err("Dependency would create a cycle".to_string());
+            }
+            if seen.insert(current) {
+                queue.extend(blocks.get(current).into_iter().flatten());
+            }
+        }
+        blocks.entry(blocker).or_default().push(blocked);
+    }
+    Ok(())
+}
+
+fn tag_names(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name FROM tags t JOIN entity_tags et ON et.tag_id = t.id
+             WHERE et.entity_type = ? AND et.entity_id = ? ORDER BY t.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(names)
+}
+
+fn attach_tags(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+    names: &[String],
+) -> Result<(), String> {
+    for name in names {
+        let tag_id = ensure_tag(conn, name)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO entity_tags (entity_type, entity_id, tag_id) VALUES (?, ?, ?)",
+            rusqlite::params![entity_type, entity_id, tag_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Captures a plan with its tasks, task dependencies, milestones and tags.
+/// Offsets are relative to the returned date: the plan start, or its earliest
+/// task date.
+pub fn capture_plan(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+) -> Result<(PlanTemplateContent, NaiveDate), String> {
+    let (title, description, start_date, end_date, created_at, weighted_progress, auto_complete): (
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        String,
+        bool,
+        bool,
+    ) = conn
+        .query_row(
+            "SELECT title, description, start_date, end_date, created_at, weighted_progress, auto_complete FROM plans WHERE id = ?",
+            [plan_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, parent_task_id, title, description, priority, start_date, end_date, estimate, estimate_unit
+             FROM tasks WHERE plan_id = ? ORDER BY position, created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    // Tasks with their dates, offsets are filled in once the base date is known
+    let rows: Vec<(TemplateTask, Option<String>, Option<String>)> = stmt
+        .query_map([plan_id], |row| {
+            Ok((
+                TemplateTask {
+                    key: row.get(0)?,
+                    parent_key: row.get(1)?,
+                    title: row.get(2)?,
+                    description: row.get(3)?,
+                    priority: row.get(4)?,
+                    start_offset: None,
+                    end_offset: None,
+                    estimate: row.get(7)?,
+                    estimate_unit: row.get(8)?,
+                    tags: Vec::new(),
+                },
+                row.get(5)?,
+                row.get(6)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let base = start_date
+        .as_deref()
+        .and_then(parse_day)
+        .or_else(|| {
+            rows.iter()
+                .flat_map(|(_, start, end)| [start.as_deref(), end.as_deref()])
+                .flatten()
+                .filter_map(parse_day)
+                .min()
+        })
+        .or_else(|| parse_day(&created_at))
+        .ok_or("Plan has no usable dates")?;
+
+    let mut tasks = Vec::with_capacity(rows.len());
+    for (mut task, start, end) in rows {
+        task.start_offset = offset_from(base, start.as_deref());
+        task.end_offset = offset_from(base, end.as_deref());
+        task.tags = tag_names(conn, "task", &task.key)?;
+        tasks.push(task);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.blocker_id, d.blocked_id FROM dependencies d
+             JOIN tasks a ON a.id = d.blocker_id
+             JOIN tasks b ON b.id = d.blocked_id
+             WHERE d.blocker_type = 'task' AND d.blocked_type = 'task'
+             AND a.plan_id = ?1 AND b.plan_id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let dependencies = stmt
+        .query_map([plan_id], |row| {
+            Ok(TemplateDependency {
+                blocker_key: row.get(0)?,
+                blocked_key: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT title, target_date, biz_type, biz_id, id FROM milestones
+             WHERE (biz_type = 'plan' AND biz_id = ?1)
+             OR (biz_type = 'task' AND biz_id IN (SELECT id FROM tasks WHERE plan_id = ?1))",
+        )
+        .map_err(|e| e.to_string())?;
+    let milestones = stmt
+        .query_map([plan_id], |row| {
+            let target_date: Option<String> = row.get(1)?;
+            let biz_type: String = row.get(2)?;
+            let biz_id: String = row.get(3)?;
+            Ok(TemplateMilestone {
+                key: row.get(4)?,
+                title: row.get(0)?,
+                target_offset: offset_from(base, target_date.as_deref()),
+                task_key: (biz_type == "task").then_some(biz_id),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok((
+        PlanTemplateContent {
+            plan_title: title,
+            plan_description: description,
+            end_offset: offset_from(base, end_date.as_deref()),
+            weighted_progress,
+            auto_complete,
+            tags: tag_names(conn, "plan", plan_id)?,
+            tasks,
+            dependencies,
+            milestones,
+        },
+        base,
+    ))
+}
+
+/// Creates a new plan from template content starting on `start`. Returns the
+/// plan id and the new task or milestone id for each template key.
+pub fn instantiate(
+    conn: &rusqlite::Connection,
+    content: &PlanTemplateContent,
+    start: NaiveDate,
+    title: Option<String>,
+) -> Result<(String, HashMap<String, String>), String> {
+    validate_content(content)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let plan_id = uuid::Uuid::new_v4().to_string();
+
+    conn.execute(
+        "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete) VALUES (?, ?, ?, ?, ?, 'active', ?, ?, 0, ?, ?)",
+        rusqlite::params![
+            plan_id,
+            title.unwrap_or_else(|| content.plan_title.clone()),
+            content.plan_description,
+            date_at(start, Some(0))?,
+            date_at(start, content.end_offset)?,
+            now,
+            now,
+            content.weighted_progress,
+            content.auto_complete
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    attach_tags(conn, "plan", &plan_id, &content.tags)?;
+
+    // New ids for every task, so parents can be resolved in any order
+    let ids: HashMap<&str, String> = content
+        .tasks
+        .iter()
+        .map(|t| (t.key.as_str(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+
+    for task in &content.tasks {
+        let parent_id = task
+            .parent_key
+            .as_deref()
+            .and_then(|key| ids.get(key).cloned());
+        conn.execute(
+            "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, estimate, estimate_unit, parent_task_id, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, 'pending', ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                ids[task.key.as_str()],
+                plan_id,
+                task.title,
+                task.description,
+                date_at(start, task.start_offset)?,
+                date_at(start, task.end_offset)?,
+                task.priority,
+                task.estimate,
+                task.estimate_unit,
+                parent_id,
+                next_position(conn, "task", Some(&plan_id))?,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        attach_tags(conn, "task", &ids[task.key.as_str()], &task.tags)?;
+    }
+
+    for dep in &content.dependencies {
+        let (Some(blocker), Some(blocked)) = (
+            ids.get(dep.blocker_key.as_str()),
+            ids.get(dep.blocked_key.as_str()),
+        ) else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, 'task', ?, 'task', ?, ?)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), blocker, blocked, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut ids: HashMap<String, String> =
+        ids.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    for milestone in &content.milestones {
+        let (biz_type, biz_id) = match milestone.task_key.as_deref() {
+            Some(key) => match ids.get(key) {
+                Some(id) => ("task", id.clone()),
+                None => continue,
+            },
+            None => ("plan", plan_id.clone()),
+        };
+        let milestone_id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO milestones (id, title, target_date, biz_type, biz_id, status, progress, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'pending', 0, ?, ?)",
+            rusqlite::params![
+                milestone_id,
+                milestone.title,
+                date_at(start, milestone.target_offset)?,
+                biz_type,
+                biz_id,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        if let Some(key) = &milestone.key {
+            ids.insert(key.clone(), milestone_id);
+        }
+    }
+
+    refresh_plan_progress(conn, &plan_id)?;
+    Ok((plan_id, ids))
+}
+
+fn get_template_by_id(conn: &rusqlite::Connection, id: &str) -> Result<PlanTemplate, String> {
+    conn.query_row(
+        "SELECT id, name, description, content, created_at, updated_at FROM plan_templates WHERE id = ?",
+        [id],
+        |row| {
+            Ok(PlanTemplate {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                content: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn insert_template(
+    conn: &rusqlite::Connection,
+    name: String,
+    description: Option<String>,
+    content: &PlanTemplateContent,
+) -> Result<PlanTemplate, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let content = serde_json::to_string(content).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO plan_templates (id, name, description, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+        rusqlite::params![id, name, description, content, now, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(PlanTemplate {
+        id,
+        name,
+        description,
+        content,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn create_plan_template(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<PlanTemplate, String> {
+    log_command!("create_plan_template", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let (content, _) = capture_plan(&conn, &plan_id)?;
+        insert_template(&conn, name, description, &content)
+    })
+}
+
+#[tauri::command]
+pub fn get_plan_templates(state: tauri::State<AppState>) -> Result<Vec<PlanTemplate>, String> {
+    log_command!("get_plan_templates", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, name, description, content, created_at, updated_at FROM plan_templates ORDER BY name")
+            .map_err(|e| e.to_string())?;
+
+        let templates = stmt
+            .query_map([], |row| {
+                Ok(PlanTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    content: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(templates.filter_map(|t| t.ok()).collect())
+    })
+}
+
+#[tauri::command]
+pub fn delete_plan_template(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_plan_template", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM plan_templates WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Creates a plan from the template with dates shifted to `start_date`.
+#[tauri::command]
+pub fn instantiate_plan_template(
+    state: tauri::State<AppState>,
+    id: String,
+    start_date: String,
+    title: Option<String>,
+) -> Result<String, String> {
+    log_command!("instantiate_plan_template", {
+        let start = parse_day(&start_date).ok_or("Invalid start_date, use YYYY-MM-DD")?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let template = get_template_by_id(&conn, &id)?;
+        let content: PlanTemplateContent =
+            serde_json::from_str(&template.content).map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let (plan_id, _) = instantiate(&tx, &content, start, title)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(plan_id)
+    })
+}
+
+#[tauri::command]
+pub fn export_plan_template(
+    state: tauri::State<AppState>,
+    id: String,
+    path: String,
+) -> Result<(), String> {
+    log_command!("export_plan_template", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let template = get_template_by_id(&conn, &id)?;
+
+        let file = PlanTemplateFile {
+            version: "1.0".to_string(),
+            name: template.name,
+            description: template.description,
+            content: serde_json::from_str(&template.content).map_err(|e| e.to_string())?,
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub fn import_plan_template(
+    state: tauri::State<AppState>,
+    path: String,
+) -> Result<PlanTemplate, String> {
+    log_command!("import_plan_template", {
+        let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let file: PlanTemplateFile =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid template file: {}", e))?;
+
+        validate_content(&file.content)?;
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        insert_template(&conn, file.name, file.description, &file.content)
+    })
+}
@@ -1,19 +1,22 @@
 // Plan CRUD commands
 
+use super::schedule::parse_day;
+use super::step_links::{delete_plan_step_links, sync_plan_links, sync_steps};
+use super::workflows::task_category_sql;
 use crate::log_command;
 use crate::models::Plan;
 use crate::AppState;
+use chrono::{Datelike, Duration, NaiveDate};
 
-#[tauri::command]
-pub fn get_plan(state: tauri::State<AppState>, id: String) -> Result<Plan, String> {
-    log_command!("get_plan", {
-        let conn = state.db.lock().map_err(|e| e.to_string())?;
-
-        let mut stmt = conn
-            .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at FROM plans WHERE id = ?")
-            .map_err(|e| e.to_string())?;
+// ============================================================================
+// Helper Functions
+// ============================================================================
 
-        stmt.query_row([&id], |row| {
+pub fn get_plan_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Plan, String> {
+    conn.query_row(
+        "SELECT id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete FROM plans WHERE id = ?",
+        [id],
+        |row| {
             Ok(Plan {
                 id: row.get(0)?,
                 title: row.get(1)?,
@@ -23,9 +26,431 @@ pub fn get_plan(state: tauri::State<AppState>, id: String) -> Result<Plan, Strin
                 status: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                progress: row.get(8)?,
+                weighted_progress: row.get(9)?,
+                auto_complete: row.get(10)?,
             })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Rejects task dates outside the plan's start_date..end_date (by day) and
+/// tasks that end before they start.
+pub fn check_task_dates(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(), String> {
+    let (plan_start, plan_end): (Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT start_date, end_date FROM plans WHERE id = ?",
+            [plan_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| format!("Plan {} not found", plan_id))?;
+
+    let start = start_date.and_then(parse_day);
+    let end = end_date.and_then(parse_day);
+    if let (Some(start), Some(end)) = (start, end) {
+        if end < start {
+            return Err("Task end_date is before its start_date".to_string());
+        }
+    }
+
+    let plan_start = plan_start.as_deref().and_then(parse_day);
+    let plan_end = plan_end.as_deref().and_then(parse_day);
+    for day in [start, end].into_iter().flatten() {
+        let too_early = plan_start.is_some_and(|s| day < s);
+        let too_late = plan_end.is_some_and(|e| day > e);
+        if too_early || too_late {
+            return Err(format!(
+                "Task dates must fall within the plan ({} to {})",
+                plan_start.map_or("open".to_string(), |d| d.to_string()),
+                plan_end.map_or("open".to_string(), |d| d.to_string())
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects a new start_date..end_date for a plan that would leave any of its
+/// tasks or sprints outside the range.
+pub fn check_plan_range(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(), String> {
+    let start = start_date.and_then(parse_day);
+    let end = end_date.and_then(parse_day);
+    if let (Some(start), Some(end)) = (start, end) {
+        if end < start {
+            return Err("Plan end_date is before its start_date".to_string());
+        }
+    }
+
+    for (table, label, name_column) in [("tasks", "Task", "title"), ("sprints", "Sprint", "name")] {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {}, start_date, end_date FROM {} WHERE plan_id = ?",
+                name_column, table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows: Vec<(String, Option<String>, Option<String>)> = stmt
+            .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        for (name, item_start, item_end) in rows {
+            let days = [item_start, item_end];
+            let outside = days
+                .iter()
+                .flatten()
+                .filter_map(|d| parse_day(d))
+                .any(|day| start.is_some_and(|s| day < s) || end.is_some_and(|e| day > e));
+            if outside {
+                return Err(format!(
+                    "{} '{}' would fall outside the plan's new dates",
+                    label, name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn is_weekend(day: NaiveDate) -> bool {
+    day.weekday().number_from_monday() > 5
+}
+
+/// Largest shift accepted, about a hundred years either way.
+pub const MAX_SHIFT_DAYS: i64 = 36_500;
+
+/// Moves a date by `delta` days; with `skip_weekends` the days are working
+/// days (Mon-Fri).
+fn add_days(day: NaiveDate, delta: i64, skip_weekends: bool) -> Result<NaiveDate, String> {
+    let out_of_range = || format!("Date out of range: {} shifted by {} days", day, delta);
+    let shift = |from: NaiveDate, days: i64| {
+        Duration::try_days(days)
+            .and_then(|d| from.checked_add_signed(d))
+            .ok_or_else(out_of_range)
+    };
+    if !skip_weekends {
+        return shift(day, delta);
+    }
+
+    let step = delta.signum();
+    let mut result = day;
+    let mut remaining = delta.saturating_abs();
+    // From a weekday, every five working days are exactly one week
+    while remaining > 0 && is_weekend(result) {
+        result = shift(result, step)?;
+        if !is_weekend(result) {
+            remaining -= 1;
+        }
+    }
+    let weeks = (remaining / 5)
+        .checked_mul(7 * step)
+        .ok_or_else(out_of_range)?;
+    result = shift(result, weeks)?;
+    remaining %= 5;
+    while remaining > 0 {
+        result = shift(result, step)?;
+        if !is_weekend(result) {
+            remaining -= 1;
+        }
+    }
+    Ok(result)
+}
+
+/// Signed number of working days from `from` to `to`.
+fn working_days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let (low, high, sign) = if to >= from {
+        (from, to, 1)
+    } else {
+        (to, from, -1)
+    };
+    let count = low
+        .iter_days()
+        .skip(1)
+        .take_while(|d| *d <= high)
+        .filter(|d| !is_weekend(*d))
+        .count() as i64;
+    sign * count
+}
+
+/// Shifts a stored date, keeping any time part. Unparseable values are kept.
+fn shift_value(
+    value: Option<String>,
+    delta: i64,
+    skip_weekends: bool,
+) -> Result<Option<String>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    match parse_day(&value) {
+        Some(day) => Ok(Some(format!(
+            "{}{}",
+            add_days(day, delta, skip_weekends)?.format("%Y-%m-%d"),
+            &value[10..]
+        ))),
+        None => Ok(Some(value)),
+    }
+}
+
+/// Shifts a plan, its tasks and sprints, and the milestones linked to the
+/// plan or its tasks by `delta` days.
+pub fn shift_plan_dates(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    delta: i64,
+    skip_weekends: bool,
+) -> Result<(), String> {
+    if !(-MAX_SHIFT_DAYS..=MAX_SHIFT_DAYS).contains(&delta) {
+        return Err(format!(
+            "Plans can be shifted by at most {} days",
+            MAX_SHIFT_DAYS
+        ));
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    let plan = get_plan_by_id(conn, plan_id)?;
+    conn.execute(
+        "UPDATE plans SET start_date = ?, end_date = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![
+            shift_value(plan.start_date, delta, skip_weekends)?,
+            shift_value(plan.end_date, delta, skip_weekends)?,
+            now,
+            plan_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, start_date, end_date FROM tasks WHERE plan_id = ?")
+        .map_err(|e| e.to_string())?;
+    let tasks: Vec<(String, Option<String>, Option<String>)> = stmt
+        .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|t| t.ok())
+        .collect();
+    for (id, start_date, end_date) in tasks {
+        conn.execute(
+            "UPDATE tasks SET start_date = ?, end_date = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![
+                shift_value(start_date, delta, skip_weekends)?,
+                shift_value(end_date, delta, skip_weekends)?,
+                now,
+                id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id, start_date, end_date FROM sprints WHERE plan_id = ?")
+        .map_err(|e| e.to_string())?;
+    let sprints: Vec<(String, Option<String>, Option<String>)> = stmt
+        .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|s| s.ok())
+        .collect();
+    for (id, start_date, end_date) in sprints {
+        conn.execute(
+            "UPDATE sprints SET start_date = ?, end_date = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![
+                shift_value(start_date, delta, skip_weekends)?,
+                shift_value(end_date, delta, skip_weekends)?,
+                now,
+                id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, target_date FROM milestones
+             WHERE (biz_type = 'plan' AND biz_id = ?1)
+             OR (biz_type = 'task' AND biz_id IN (SELECT id FROM tasks WHERE plan_id = ?1))",
+        )
+        .map_err(|e| e.to_string())?;
+    let milestones: Vec<(String, Option<String>)> = stmt
+        .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|m| m.ok())
+        .collect();
+    for (id, target_date) in milestones {
+        conn.execute(
+            "UPDATE milestones SET target_date = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![shift_value(target_date, delta, skip_weekends)?, now, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Days to shift a plan so it starts on `new_start`. Plans without a start
+/// date use their earliest task date.
+pub fn reschedule_delta(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    new_start: NaiveDate,
+    skip_weekends: bool,
+) -> Result<i64, String> {
+    let current: Option<String> = conn
+        .query_row(
+            "SELECT COALESCE(p.start_date, (SELECT MIN(COALESCE(t.start_date, t.end_date)) FROM tasks t WHERE t.plan_id = p.id))
+             FROM plans p WHERE p.id = ?",
+            [plan_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| format!("Plan {} not found", plan_id))?;
+    let current = current
+        .as_deref()
+        .and_then(parse_day)
+        .ok_or("Plan has no dates to reschedule from")?;
+
+    Ok(if skip_weekends {
+        working_days_between(current, new_start)
+    } else {
+        (new_start - current).num_days()
+    })
+}
+
+pub fn priority_weight(priority: &str) -> i64 {
+    match priority {
+        "P0" => 4,
+        "P1" => 3,
+        "P3" => 1,
+        _ => 2,
+    }
+}
+
+/// Progress of a plan from its leaf tasks (0-100), optionally weighted by
+/// priority. Returns the progress and whether every leaf task is done.
+pub fn calculate_plan_progress(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    weighted: bool,
+) -> Result<(i32, bool), String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT priority, {} FROM tasks t WHERE plan_id = ?
+             AND NOT EXISTS(SELECT 1 FROM tasks c WHERE c.parent_task_id = t.id)",
+            task_category_sql("t")
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let tasks: Vec<(String, String)> = stmt
+        .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|t| t.ok())
+        .collect();
+
+    let mut total = 0i64;
+    let mut done = 0i64;
+    for (priority, category) in &tasks {
+        let weight = if weighted {
+            priority_weight(priority)
+        } else {
+            1
+        };
+        total += weight;
+        if category == "done" {
+            done += weight;
+        }
+    }
+
+    if total == 0 {
+        return Ok((0, false));
+    }
+    Ok(((done * 100 / total) as i32, done == total))
+}
+
+/// Recomputes and stores a plan's progress. With auto_complete on, an active
+/// plan whose tasks are all done becomes completed, and reopens if a task does.
+/// Steps linked to the plan or its tasks follow.
+pub fn refresh_plan_progress(conn: &rusqlite::Connection, plan_id: &str) -> Result<(), String> {
+    let settings: Option<(String, bool, bool)> = conn
+        .query_row(
+            "SELECT status, weighted_progress, auto_complete FROM plans WHERE id = ?",
+            [plan_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .ok();
+    let Some((status, weighted, auto_complete)) = settings else {
+        return Ok(());
+    };
+
+    let (progress, all_done) = calculate_plan_progress(conn, plan_id, weighted)?;
+    conn.execute(
+        "UPDATE plans SET progress = ? WHERE id = ?",
+        rusqlite::params![progress, plan_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if auto_complete {
+        let new_status = match status.as_str() {
+            "active" if all_done => Some("completed"),
+            "completed" if !all_done => Some("active"),
+            _ => None,
+        };
+        if let Some(new_status) = new_status {
+            conn.execute(
+                "UPDATE plans SET status = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![new_status, chrono::Utc::now().to_rfc3339(), plan_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    sync_plan_links(conn, plan_id)
+}
+
+/// Recomputes the plan of a task, if the task exists.
+pub fn refresh_task_plan_progress(
+    conn: &rusqlite::Connection,
+    task_id: &str,
+) -> Result<(), String> {
+    let plan_id: Option<String> = conn
+        .query_row("SELECT plan_id FROM tasks WHERE id = ?", [task_id], |row| {
+            row.get(0)
         })
-        .map_err(|e| e.to_string())
+        .ok();
+    match plan_id {
+        Some(plan_id) => refresh_plan_progress(conn, &plan_id),
+        None => Ok(()),
+    }
+}
+
+pub fn refresh_all_plan_progress(conn: &rusqlite::Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM plans")
+        .map_err(|e| e.to_string())?;
+    let ids: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for id in ids {
+        refresh_plan_progress(conn, &id)?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_plan(state: tauri::State<AppState>, id: String) -> Result<Plan, String> {
+    log_command!("get_plan", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        get_plan_by_id(&conn, &id)
     })
 }
 
@@ -35,7 +460,7 @@ pub fn get_plans(state: tauri::State<AppState>) -> Result<Vec<Plan>, String> {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
-            .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at FROM plans")
+            .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete FROM plans")
             .map_err(|e| e.to_string())?;
 
         let plan_iter = stmt
@@ -49,6 +474,9 @@ pub fn get_plans(state: tauri::State<AppState>) -> Result<Vec<Plan>, String> {
                     status: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
+                    progress: row.get(8)?,
+                    weighted_progress: row.get(9)?,
+                    auto_complete: row.get(10)?,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -64,16 +492,20 @@ pub fn create_plan(
     description: Option<String>,
     start_date: Option<String>,
     end_date: Option<String>,
+    weighted_progress: Option<bool>,
+    auto_complete: Option<bool>,
 ) -> Result<Plan, String> {
     log_command!("create_plan", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
+        let weighted_progress = weighted_progress.unwrap_or(false);
+        let auto_complete = auto_complete.unwrap_or(false);
 
         conn.execute(
-            "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'active', ?, ?)",
-            rusqlite::params![id, title, description, start_date, end_date, now, now],
+            "INSERT INTO plans (id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete) VALUES (?, ?, ?, ?, ?, 'active', ?, ?, 0, ?, ?)",
+            rusqlite::params![id, title, description, start_date, end_date, now, now, weighted_progress, auto_complete],
         ).map_err(|e| e.to_string())?;
 
         Ok(Plan {
@@ -85,6 +517,9 @@ pub fn create_plan(
             status: "active".to_string(),
             created_at: now.clone(),
             updated_at: now,
+            progress: 0,
+            weighted_progress,
+            auto_complete,
         })
     })
 }
@@ -98,13 +533,15 @@ pub fn update_plan(
     start_date: Option<String>,
     end_date: Option<String>,
     status: Option<String>,
+    weighted_progress: Option<bool>,
+    auto_complete: Option<bool>,
 ) -> Result<Plan, String> {
     log_command!("update_plan", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
 
         let mut stmt = conn
-            .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at FROM plans WHERE id = ?")
+            .prepare("SELECT id, title, description, start_date, end_date, status, created_at, updated_at, progress, weighted_progress, auto_complete FROM plans WHERE id = ?")
             .map_err(|e| e.to_string())?;
 
         let plan: Plan = stmt
@@ -118,21 +555,54 @@ pub fn update_plan(
                     status: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
+                    progress: row.get(8)?,
+                    weighted_progress: row.get(9)?,
+                    auto_complete: row.get(10)?,
                 })
             })
             .map_err(|e| e.to_string())?;
 
         let new_title = title.unwrap_or(plan.title);
         let new_description = description.or(plan.description);
+        let range_changed = start_date
+            .as_ref()
+            .is_some_and(|d| Some(d) != plan.start_date.as_ref())
+            || end_date
+                .as_ref()
+                .is_some_and(|d| Some(d) != plan.end_date.as_ref());
         let new_start_date = start_date.or(plan.start_date);
         let new_end_date = end_date.or(plan.end_date);
+        if range_changed {
+            check_plan_range(
+                &conn,
+                &id,
+                new_start_date.as_deref(),
+                new_end_date.as_deref(),
+            )?;
+        }
+        let status_changed = status.as_ref().is_some_and(|s| *s != plan.status);
         let new_status = status.unwrap_or(plan.status);
+        let new_weighted_progress = weighted_progress.unwrap_or(plan.weighted_progress);
+        let new_auto_complete = auto_complete.unwrap_or(plan.auto_complete);
 
         conn.execute(
-            "UPDATE plans SET title = ?, description = ?, start_date = ?, end_date = ?, status = ?, updated_at = ? WHERE id = ?",
-            rusqlite::params![new_title, new_description, new_start_date, new_end_date, new_status, now, id],
+            "UPDATE plans SET title = ?, description = ?, start_date = ?, end_date = ?, status = ?, weighted_progress = ?, auto_complete = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_title, new_description, new_start_date, new_end_date, new_status, new_weighted_progress, new_auto_complete, now, id],
         ).map_err(|e| e.to_string())?;
 
+        // Steps linked to the plan follow its status
+        if status_changed {
+            sync_plan_links(&conn, &id)?;
+        }
+
+        // Options may change the value or the status
+        if new_weighted_progress != plan.weighted_progress
+            || new_auto_complete != plan.auto_complete
+        {
+            refresh_plan_progress(&conn, &id)?;
+            return get_plan_by_id(&conn, &id);
+        }
+
         Ok(Plan {
             id: plan.id,
             title: new_title,
@@ -142,6 +612,9 @@ pub fn update_plan(
             status: new_status,
             created_at: plan.created_at,
             updated_at: now,
+            progress: plan.progress,
+            weighted_progress: new_weighted_progress,
+            auto_complete: new_auto_complete,
         })
     })
 }
@@ -151,11 +624,94 @@ pub fn delete_plan(state: tauri::State<AppState>, id: String) -> Result<(), Stri
     log_command!("delete_plan", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
+        conn.execute(
+            "DELETE FROM dependencies
+             WHERE (blocker_type = 'task' AND blocker_id IN (SELECT id FROM tasks WHERE plan_id = ?1))
+             OR (blocked_type = 'task' AND blocked_id IN (SELECT id FROM tasks WHERE plan_id = ?1))",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM comments
+             WHERE (entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?1))
+             OR (entity_type = 'plan' AND entity_id = ?1)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM status_history
+             WHERE entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM time_entries
+             WHERE entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM pomodoro_sessions
+             WHERE entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        let linked_steps = delete_plan_step_links(&conn, &id)?;
         conn.execute("DELETE FROM tasks WHERE plan_id = ?", [&id])
             .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM sprints WHERE plan_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM workflow_statuses WHERE plan_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM workflow_transitions WHERE plan_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
         conn.execute("DELETE FROM plans WHERE id = ?", [&id])
             .map_err(|e| e.to_string())?;
+        sync_steps(&conn, &linked_steps)?;
 
         Ok(())
     })
 }
+
+/// Moves the plan, its tasks and linked milestones by `delta_days`. With
+/// `skip_weekends` the delta counts working days and dates land on weekdays.
+#[tauri::command]
+pub fn shift_plan(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    delta_days: i64,
+    skip_weekends: Option<bool>,
+) -> Result<Plan, String> {
+    log_command!("shift_plan", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        shift_plan_dates(&tx, &plan_id, delta_days, skip_weekends.unwrap_or(false))?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_plan_by_id(&conn, &plan_id)
+    })
+}
+
+/// Moves the plan so it starts on `new_start` (YYYY-MM-DD), shifting its
+/// tasks and linked milestones by the same amount.
+#[tauri::command]
+pub fn reschedule_plan(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    new_start: String,
+    skip_weekends: Option<bool>,
+) -> Result<Plan, String> {
+    log_command!("reschedule_plan", {
+        let new_start = parse_day(&new_start).ok_or("Invalid new_start, use YYYY-MM-DD")?;
+        let skip_weekends = skip_weekends.unwrap_or(false);
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let delta = reschedule_delta(&conn, &plan_id, new_start, skip_weekends)?;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        shift_plan_dates(&tx, &plan_id, delta, skip_weekends)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_plan_by_id(&conn, &plan_id)
+    })
+}
@@ -0,0 +1,572 @@
+// Pomodoro commands (focus sessions linked to todos and tasks)
+//
+// The engine lives in memory (managed as `PomodoroState`); a background ticker
+// completes phases when their time is up. Work sessions are persisted to
+// `pomodoro_sessions` when they end.
+
+use super::time_tracking::validate_entity_type;
+use crate::log_command;
+use crate::models::PomodoroSession;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+pub const PHASE_CHANGED_EVENT: &str = "pomodoro-phase-changed";
+
+/// Longest phase allowed, one day.
+pub const MAX_PHASE_MINUTES: i64 = 24 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSettings {
+    pub id: String,
+    pub work_minutes: i64,
+    pub short_break_minutes: i64,
+    pub long_break_minutes: i64,
+    pub long_break_interval: u32, // Long break after this many completed work sessions
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PomodoroStatus {
+    pub phase: String, // work | short_break | long_break
+    pub state: String, // idle | running | paused
+    pub remaining_seconds: i64,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub completed_work_sessions: u32, // Since the last long break
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PhaseChangedEvent {
+    pub previous_phase: String,
+    pub reason: String, // completed | skipped | abandoned
+    pub status: PomodoroStatus,
+}
+
+/// A work session that just ended and should be persisted.
+#[derive(Debug, Clone)]
+pub struct FinishedSession {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub planned_minutes: i64,
+    pub focus_seconds: i64,
+    pub status: String,
+}
+
+// ============================================================================
+// Engine
+// ============================================================================
+
+#[derive(Debug)]
+pub struct PomodoroEngine {
+    phase: String,
+    state: String,
+    remaining_seconds: i64,
+    ends_at: Option<DateTime<Utc>>,
+    entity_type: Option<String>,
+    entity_id: Option<String>,
+    work_started_at: Option<DateTime<Utc>>,
+    completed_work_sessions: u32,
+}
+
+impl Default for PomodoroEngine {
+    fn default() -> Self {
+        PomodoroEngine {
+            phase: "work".to_string(),
+            state: "idle".to_string(),
+            remaining_seconds: 0,
+            ends_at: None,
+            entity_type: None,
+            entity_id: None,
+            work_started_at: None,
+            completed_work_sessions: 0,
+        }
+    }
+}
+
+fn phase_seconds(settings: &PomodoroSettings, phase: &str) -> i64 {
+    let minutes = match phase {
+        "short_break" => settings.short_break_minutes,
+        "long_break" => settings.long_break_minutes,
+        _ => settings.work_minutes,
+    };
+    minutes.clamp(1, MAX_PHASE_MINUTES) * 60
+}
+
+/// The moment a phase of `seconds` started at `now` ends, None if out of range.
+fn phase_end(now: DateTime<Utc>, seconds: i64) -> Option<DateTime<Utc>> {
+    chrono::Duration::try_seconds(seconds).and_then(|d| now.checked_add_signed(d))
+}
+
+impl PomodoroEngine {
+    fn remaining_at(&self, now: DateTime<Utc>) -> i64 {
+        match self.ends_at {
+            Some(ends_at) => (ends_at - now).num_seconds().max(0),
+            None => self.remaining_seconds,
+        }
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> PomodoroStatus {
+        PomodoroStatus {
+            phase: self.phase.clone(),
+            state: self.state.clone(),
+            remaining_seconds: self.remaining_at(now),
+            entity_type: self.entity_type.clone(),
+            entity_id: self.entity_id.clone(),
+            completed_work_sessions: self.completed_work_sessions,
+        }
+    }
+
+    /// Starts the current (idle) phase. The entity is only changed for work phases.
+    pub fn start(
+        &mut self,
+        settings: &PomodoroSettings,
+        entity: Option<(String, String)>,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        if self.state != "idle" {
+            return Err("A pomodoro phase is already in progress".to_string());
+        }
+
+        let seconds = phase_seconds(settings, &self.phase);
+        let ends_at = phase_end(now, seconds).ok_or("Phase end is out of range")?;
+        if self.phase == "work" {
+            self.entity_type = entity.as_ref().map(|e| e.0.clone());
+            self.entity_id = entity.map(|e| e.1);
+            self.work_started_at = Some(now);
+        }
+        self.state = "running".to_string();
+        self.remaining_seconds = seconds;
+        self.ends_at = Some(ends_at);
+        Ok(())
+    }
+
+    pub fn pause(&mut self, now: DateTime<Utc>) -> Result<(), String> {
+        if self.state != "running" {
+            return Err("No running pomodoro".to_string());
+        }
+        self.remaining_seconds = self.remaining_at(now);
+        self.ends_at = None;
+        self.state = "paused".to_string();
+        Ok(())
+    }
+
+    pub fn resume(&mut self, now: DateTime<Utc>) -> Result<(), String> {
+        if self.state != "paused" {
+            return Err("No paused pomodoro".to_string());
+        }
+        let ends_at = phase_end(now, self.remaining_seconds).ok_or("Phase end is out of range")?;
+        self.ends_at = Some(ends_at);
+        self.state = "running".to_string();
+        Ok(())
+    }
+
+    /// Completes the phase if its time is up.
+    pub fn tick(
+        &mut self,
+        settings: &PomodoroSettings,
+        now: DateTime<Utc>,
+    ) -> Option<(PhaseChangedEvent, Option<FinishedSession>)> {
+        match self.ends_at {
+            Some(ends_at) if self.state == "running" && ends_at <= now => {
+                Some(self.advance(settings, now, "completed"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Ends the current phase early and moves to the next one.
+    pub fn skip(
+        &mut self,
+        settings: &PomodoroSettings,
+        now: DateTime<Utc>,
+    ) -> (PhaseChangedEvent, Option<FinishedSession>) {
+        self.advance(settings, now, "skipped")
+    }
+
+    /// Stops everything and goes back to an idle work phase.
+    pub fn abandon(
+        &mut self,
+        settings: &PomodoroSettings,
+        now: DateTime<Utc>,
+    ) -> (PhaseChangedEvent, Option<FinishedSession>) {
+        let previous_phase = self.phase.clone();
+        let finished = self.finish_work(settings, now, "abandoned");
+
+        self.phase = "work".to_string();
+        self.state = "idle".to_string();
+        self.remaining_seconds = phase_seconds(settings, "work");
+        self.ends_at = None;
+
+        (
+            PhaseChangedEvent {
+                previous_phase,
+                reason: "abandoned".to_string(),
+                status: self.status(now),
+            },
+            finished,
+        )
+    }
+
+    fn finish_work(
+        &mut self,
+        settings: &PomodoroSettings,
+        now: DateTime<Utc>,
+        status: &str,
+    ) -> Option<FinishedSession> {
+        if self.phase != "work" {
+            return None;
+        }
+        let started_at = self.work_started_at.take()?;
+        let planned = phase_seconds(settings, "work");
+
+        Some(FinishedSession {
+            entity_type: self.entity_type.clone(),
+            entity_id: self.entity_id.clone(),
+            started_at,
+            ended_at: now,
+            planned_minutes: settings.work_minutes,
+            focus_seconds: (planned - self.remaining_at(now)).max(0),
+            status: status.to_string(),
+        })
+    }
+
+    /// Breaks start right after a work phase; work phases wait for `start`.
+    fn advance(
+        &mut self,
+        settings: &PomodoroSettings,
+        now: DateTime<Utc>,
+        reason: &str,
+    ) -> (PhaseChangedEvent, Option<FinishedSession>) {
+        let previous_phase = self.phase.clone();
+        let finished = self.finish_work(settings, now, reason);
+
+        if previous_phase == "work" {
+            if reason == "completed" {
+                self.completed_work_sessions += 1;
+            }
+            let interval = settings.long_break_interval.max(1);
+            self.phase = if self.completed_work_sessions > 0
+                && self.completed_work_sessions.is_multiple_of(interval)
+            {
+                "long_break".to_string()
+            } else {
+                "short_break".to_string()
+            };
+            let seconds = phase_seconds(settings, &self.phase);
+            self.state = "running".to_string();
+            self.remaining_seconds = seconds;
+            self.ends_at = phase_end(now, seconds);
+        } else {
+            if previous_phase == "long_break" {
+                self.completed_work_sessions = 0;
+            }
+            self.phase = "work".to_string();
+            self.state = "idle".to_string();
+            self.remaining_seconds = phase_seconds(settings, "work");
+            self.ends_at = None;
+        }
+
+        (
+            PhaseChangedEvent {
+                previous_phase,
+                reason: reason.to_string(),
+                status: self.status(now),
+            },
+            finished,
+        )
+    }
+}
+
+/// Managed Tauri state holding the engine.
+#[derive(Default)]
+pub struct PomodoroState {
+    pub engine: Mutex<PomodoroEngine>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+pub fn load_settings(conn: &rusqlite::Connection) -> Result<PomodoroSettings, String> {
+    conn.query_row(
+        "SELECT id, work_minutes, short_break_minutes, long_break_minutes, long_break_interval, created_at, updated_at
+         FROM pomodoro_settings LIMIT 1",
+        [],
+        |row| {
+            Ok(PomodoroSettings {
+                id: row.get(0)?,
+                work_minutes: row.get(1)?,
+                short_break_minutes: row.get(2)?,
+                long_break_minutes: row.get(3)?,
+                long_break_interval: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Removes all recorded focus sessions of the entity.
+pub fn delete_entity_pomodoro_sessions(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM pomodoro_sessions WHERE entity_type = ? AND entity_id = ?",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn record_session(conn: &rusqlite::Connection, session: &FinishedSession) -> Result<(), String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO pomodoro_sessions (id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            id,
+            session.entity_type,
+            session.entity_id,
+            session.started_at.to_rfc3339(),
+            session.ended_at.to_rfc3339(),
+            session.planned_minutes,
+            session.focus_seconds,
+            session.status,
+            now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn finish_transition(
+    app: &tauri::AppHandle,
+    conn: &rusqlite::Connection,
+    event: PhaseChangedEvent,
+    finished: Option<FinishedSession>,
+) -> Result<PomodoroStatus, String> {
+    if let Some(session) = finished {
+        record_session(conn, &session)?;
+    }
+    app.emit(PHASE_CHANGED_EVENT, event.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(event.status)
+}
+
+/// Checks every second whether the running phase is over.
+pub fn spawn_ticker(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        let pomodoro = app.state::<PomodoroState>();
+        let state = app.state::<AppState>();
+        let result = (|| -> Result<(), String> {
+            let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+            if engine.state != "running" {
+                return Ok(());
+            }
+            let conn = state.db.lock().map_err(|e| e.to_string())?;
+            let settings = load_settings(&conn)?;
+            if let Some((event, finished)) = engine.tick(&settings, Utc::now()) {
+                finish_transition(&app, &conn, event, finished)?;
+            }
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            log::error!("Pomodoro ticker failed: {}", e);
+        }
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_pomodoro_settings(state: tauri::State<AppState>) -> Result<PomodoroSettings, String> {
+    log_command!("get_pomodoro_settings", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    })
+}
+
+#[tauri::command]
+pub fn update_pomodoro_settings(
+    state: tauri::State<AppState>,
+    work_minutes: i64,
+    short_break_minutes: i64,
+    long_break_minutes: i64,
+    long_break_interval: u32,
+) -> Result<PomodoroSettings, String> {
+    log_command!("update_pomodoro_settings", {
+        let lengths = [work_minutes, short_break_minutes, long_break_minutes];
+        if lengths.iter().any(|m| !(1..=MAX_PHASE_MINUTES).contains(m)) {
+            return Err(format!(
+                "Phase lengths must be between 1 and {} minutes",
+                MAX_PHASE_MINUTES
+            ));
+        }
+        if long_break_interval == 0 {
+            return Err("long_break_interval must be at least 1".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE pomodoro_settings SET work_minutes = ?, short_break_minutes = ?, long_break_minutes = ?, long_break_interval = ?, updated_at = ? WHERE id = 'default'",
+            rusqlite::params![work_minutes, short_break_minutes, long_break_minutes, long_break_interval, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        load_settings(&conn)
+    })
+}
+
+#[tauri::command]
+pub fn get_pomodoro_status(
+    pomodoro: tauri::State<PomodoroState>,
+) -> Result<PomodoroStatus, String> {
+    log_command!("get_pomodoro_status", {
+        let engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        Ok(engine.status(Utc::now()))
+    })
+}
+
+#[tauri::command]
+pub fn start_pomodoro(
+    pomodoro: tauri::State<PomodoroState>,
+    state: tauri::State<AppState>,
+    entity_type: Option<String>,
+    entity_id: Option<String>,
+) -> Result<PomodoroStatus, String> {
+    log_command!("start_pomodoro", {
+        let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let entity = match (entity_type, entity_id) {
+            (Some(entity_type), Some(entity_id)) => {
+                let table = validate_entity_type(&entity_type)?;
+                let exists: bool = conn
+                    .query_row(
+                        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+                        [&entity_id],
+                        |row| row.get(0),
+                    )
+                    .map_err(|e| e.to_string())?;
+                if !exists {
+                    return Err(format!("{} {} not found", entity_type, entity_id));
+                }
+                Some((entity_type, entity_id))
+            }
+            (None, None) => None,
+            _ => return Err("entity_type and entity_id must be given together".to_string()),
+        };
+
+        let settings = load_settings(&conn)?;
+        let now = Utc::now();
+        engine.start(&settings, entity, now)?;
+        Ok(engine.status(now))
+    })
+}
+
+#[tauri::command]
+pub fn pause_pomodoro(pomodoro: tauri::State<PomodoroState>) -> Result<PomodoroStatus, String> {
+    log_command!("pause_pomodoro", {
+        let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now();
+        engine.pause(now)?;
+        Ok(engine.status(now))
+    })
+}
+
+#[tauri::command]
+pub fn resume_pomodoro(pomodoro: tauri::State<PomodoroState>) -> Result<PomodoroStatus, String> {
+    log_command!("resume_pomodoro", {
+        let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now();
+        engine.resume(now)?;
+        Ok(engine.status(now))
+    })
+}
+
+#[tauri::command]
+pub fn skip_pomodoro(
+    app: tauri::AppHandle,
+    pomodoro: tauri::State<PomodoroState>,
+    state: tauri::State<AppState>,
+) -> Result<PomodoroStatus, String> {
+    log_command!("skip_pomodoro", {
+        let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let settings = load_settings(&conn)?;
+
+        let (event, finished) = engine.skip(&settings, Utc::now());
+        finish_transition(&app, &conn, event, finished)
+    })
+}
+
+#[tauri::command]
+pub fn abandon_pomodoro(
+    app: tauri::AppHandle,
+    pomodoro: tauri::State<PomodoroState>,
+    state: tauri::State<AppState>,
+) -> Result<PomodoroStatus, String> {
+    log_command!("abandon_pomodoro", {
+        let mut engine = pomodoro.engine.lock().map_err(|e| e.to_string())?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let settings = load_settings(&conn)?;
+
+        let (event, finished) = engine.abandon(&settings, Utc::now());
+        finish_transition(&app, &conn, event, finished)
+    })
+}
+
+#[tauri::command]
+pub fn get_pomodoro_sessions(
+    state: tauri::State<AppState>,
+    entity_type: Option<String>,
+    entity_id: Option<String>,
+) -> Result<Vec<PomodoroSession>, String> {
+    log_command!("get_pomodoro_sessions", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at
+                 FROM pomodoro_sessions
+                 WHERE (?1 IS NULL OR entity_type = ?1) AND (?2 IS NULL OR entity_id = ?2)
+                 ORDER BY started_at DESC",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let sessions = stmt
+            .query_map(rusqlite::params![entity_type, entity_id], |row| {
+                Ok(PomodoroSession {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    entity_id: row.get(2)?,
+                    started_at: row.get(3)?,
+                    ended_at: row.get(4)?,
+                    planned_minutes: row.get(5)?,
+                    focus_seconds: row.get(6)?,
+                    status: row.get(7)?,
+                    created_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(sessions.filter_map(|s| s.ok()).collect())
+    })
+}
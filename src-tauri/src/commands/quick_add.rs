@@ -0,0 +1,669 @@
+// Quick-add command: parse natural language input (Chinese / English) into an entity
+//
+// Supported syntax:
+//   #tag            tag (tags starting with a digit, e.g. "#123", stay in the title)
+//   !P0 .. !P3      priority
+//   @plan:<name>    create a task under the matching plan instead of a todo
+//   every friday / 每周五 / 每天 / 每月 ...      recurrence (creates a periodic circulation)
+//   tomorrow 3pm / 明天下午3点 / next monday / 下周一 / 2026-03-01 ...  due date and time
+
+use super::ordering::next_position;
+use super::plans::{check_task_dates, refresh_plan_progress};
+use super::tags::ensure_tag;
+use super::workflows::initial_status;
+use crate::log_command;
+use crate::AppState;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Recurrence {
+    pub frequency: String,  // 'daily' | 'weekly' | 'monthly'
+    pub weekdays: Vec<u32>, // 1 = Monday ... 7 = Sunday (weekly only)
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct QuickAddParsed {
+    pub title: String,
+    pub due_date: Option<String>, // "YYYY-MM-DD", or RFC 3339 when a time was given
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
+    pub plan: Option<String>, // Plan name as typed after "@plan:"
+    pub recurrence: Option<Recurrence>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuickAddResult {
+    pub parsed: QuickAddParsed,
+    pub entity_type: Option<String>, // 'todo' | 'task' | 'circulation' (None in parse_only mode)
+    pub entity_id: Option<String>,
+    pub plan_id: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+/// Parses quick-add text relative to `now` (local time).
+pub fn parse_quick_add(text: &str, now: NaiveDateTime) -> QuickAddParsed {
+    let today = now.date();
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+
+    let mut parsed = QuickAddParsed::default();
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut title_parts: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let lower = token
+            .trim_end_matches([',', '，', '.', '。'])
+            .to_lowercase();
+
+        // #tag
+        if let Some(tag) = token.strip_prefix('#') {
+            if tag.chars().next().is_some_and(|c| !c.is_ascii_digit()) {
+                parsed.tags.push(tag.to_string());
+                i += 1;
+                continue;
+            }
+        }
+
+        // !P0 .. !P3
+        if let Some(p) = lower.strip_prefix('!') {
+            if matches!(p, "p0" | "p1" | "p2" | "p3") {
+                parsed.priority = Some(p.to_uppercase());
+                i += 1;
+                continue;
+            }
+        }
+
+        // @plan:<name>
+        if let Some(plan) = token.strip_prefix("@plan:") {
+            if !plan.is_empty() {
+                parsed.plan = Some(plan.to_string());
+                i += 1;
+                continue;
+            }
+        }
+
+        // English recurrence: "every <day|week|month|weekday|monday...>", "everyday"
+        if lower == "everyday" {
+            parsed.recurrence = Some(recurrence("daily", vec![]));
+            i += 1;
+            continue;
+        }
+        if lower == "every" && i + 1 < tokens.len() {
+            if let Some(rec) = parse_en_recurrence(&tokens[i + 1].to_lowercase()) {
+                parsed.recurrence = Some(rec);
+                i += 2;
+                continue;
+            }
+        }
+
+        // English date / time, optionally introduced by a connector word
+        let offset = usize::from(matches!(lower.as_str(), "on" | "at" | "by" | "due"));
+        if i + offset < tokens.len() {
+            if let Some((d, t, n)) = parse_en_date(&tokens[i + offset..], today) {
+                date = Some(d);
+                time = t.or(time);
+                i += offset + n;
+                continue;
+            }
+            if let Some((t, n)) = parse_en_time(&tokens[i + offset..]) {
+                time = Some(t);
+                i += offset + n;
+                continue;
+            }
+        }
+
+        // Chinese components glued to the start of a token, e.g. "明天下午3点"
+        let mut rest = token;
+        loop {
+            if let Some((rec, n)) = consume_zh_recurrence(rest) {
+                parsed.recurrence = Some(rec);
+                rest = &rest[n..];
+            } else if let Some((d, t, n)) = consume_zh_date(rest, today) {
+                date = Some(d);
+                time = t.or(time);
+                rest = &rest[n..];
+            } else if let Some((t, n)) = consume_zh_time(rest) {
+                time = Some(t);
+                rest = &rest[n..];
+            } else {
+                break;
+            }
+        }
+        if !rest.is_empty() {
+            title_parts.push(rest.to_string());
+        }
+
+        i += 1;
+    }
+
+    parsed.title = title_parts.join(" ");
+    parsed.due_date = match (date, time) {
+        (None, None) => None,
+        (Some(d), None) => Some(d.format("%Y-%m-%d").to_string()),
+        // Local wall time without an offset, so date(due_date) stays on the
+        // local day instead of converting to UTC
+        (d, Some(t)) => Some(
+            d.unwrap_or(today)
+                .and_time(t)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+        ),
+    };
+
+    parsed
+}
+
+fn recurrence(frequency: &str, weekdays: Vec<u32>) -> Recurrence {
+    Recurrence {
+        frequency: frequency.to_string(),
+        weekdays,
+    }
+}
+
+fn weekday_from_en(s: &str) -> Option<Weekday> {
+    match s.trim_end_matches([',', '.']) {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_from_zh(c: char) -> Option<Weekday> {
+    match c {
+        '一' | '1' => Some(Weekday::Mon),
+        '二' | '2' => Some(Weekday::Tue),
+        '三' | '3' => Some(Weekday::Wed),
+        '四' | '4' => Some(Weekday::Thu),
+        '五' | '5' => Some(Weekday::Fri),
+        '六' | '6' => Some(Weekday::Sat),
+        '日' | '天' | '7' => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Next occurrence of the weekday, today included.
+fn upcoming_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let diff = (weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today + Duration::days(diff)
+}
+
+/// The weekday in the following (Monday-based) week.
+fn next_week_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    monday + Duration::days(7 + weekday.num_days_from_monday() as i64)
+}
+
+fn parse_en_recurrence(word: &str) -> Option<Recurrence> {
+    let word = word.trim_end_matches([',', '.']);
+    match word {
+        "day" => Some(recurrence("daily", vec![])),
+        "week" => Some(recurrence("weekly", vec![])),
+        "month" => Some(recurrence("monthly", vec![])),
+        "weekday" => Some(recurrence("weekly", vec![1, 2, 3, 4, 5])),
+        _ => weekday_from_en(word).map(|wd| recurrence("weekly", vec![wd.number_from_monday()])),
+    }
+}
+
+fn parse_en_date(
+    tokens: &[&str],
+    today: NaiveDate,
+) -> Option<(NaiveDate, Option<NaiveTime>, usize)> {
+    let word = |i: usize| -> String {
+        tokens
+            .get(i)
+            .map(|t| t.trim_end_matches([',', '.']).to_lowercase())
+            .unwrap_or_default()
+    };
+
+    let first = word(0);
+    match first.as_str() {
+        "today" => return Some((today, None, 1)),
+        "tonight" => return Some((today, NaiveTime::from_hms_opt(20, 0, 0), 1)),
+        "tomorrow" | "tmr" | "tmrw" => return Some((today + Duration::days(1), None, 1)),
+        "day" if word(1) == "after" && word(2) == "tomorrow" => {
+            return Some((today + Duration::days(2), None, 3))
+        }
+        "next" => {
+            let second = word(1);
+            if second == "week" {
+                return Some((next_week_weekday(today, Weekday::Mon), None, 2));
+            }
+            if second == "month" {
+                let (y, m) = if today.month() == 12 {
+                    (today.year() + 1, 1)
+                } else {
+                    (today.year(), today.month() + 1)
+                };
+                return NaiveDate::from_ymd_opt(y, m, 1).map(|d| (d, None, 2));
+            }
+            return weekday_from_en(&second).map(|wd| (next_week_weekday(today, wd), None, 2));
+        }
+        "in" => {
+            let amount: i64 = word(1).parse().ok()?;
+            let days = match word(2).as_str() {
+                "day" | "days" => amount,
+                "week" | "weeks" => amount.checked_mul(7)?,
+                _ => return None,
+            };
+            let day = today.checked_add_signed(Duration::try_days(days)?)?;
+            return Some((day, None, 3));
+        }
+        _ => {}
+    }
+
+    // Only full weekday names on their own, so words like "sat" stay in the title
+    if first.len() > 4 {
+        if let Some(wd) = weekday_from_en(&first) {
+            return Some((upcoming_weekday(today, wd), None, 1));
+        }
+    }
+
+    NaiveDate::parse_from_str(&first, "%Y-%m-%d")
+        .ok()
+        .map(|d| (d, None, 1))
+}
+
+fn parse_en_time(tokens: &[&str]) -> Option<(NaiveTime, usize)> {
+    let first = tokens.first()?.trim_end_matches([',', '.']).to_lowercase();
+
+    match first.as_str() {
+        "noon" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(23, 59, 0)?, 1)),
+        _ => {}
+    }
+
+    // "3 pm" written as two tokens
+    let (clock, meridiem, consumed) = if let Some(c) = first.strip_suffix("am") {
+        (c.to_string(), Some("am"), 1)
+    } else if let Some(c) = first.strip_suffix("pm") {
+        (c.to_string(), Some("pm"), 1)
+    } else {
+        match tokens.get(1).map(|t| t.to_lowercase()) {
+            Some(m) if m == "am" || m == "pm" => {
+                (first.clone(), Some(if m == "am" { "am" } else { "pm" }), 2)
+            }
+            _ => (first.clone(), None, 1),
+        }
+    };
+
+    // Bare numbers are not times unless they have a meridiem or a colon
+    if meridiem.is_none() && !clock.contains(':') {
+        return None;
+    }
+
+    let (h, m) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+
+    let hour = match meridiem {
+        Some("pm") if h < 12 => h + 12,
+        Some("am") if h == 12 => 0,
+        Some(_) if h > 12 => return None,
+        _ => h,
+    };
+
+    NaiveTime::from_hms_opt(hour, m, 0).map(|t| (t, consumed))
+}
+
+/// Parses a leading Arabic or Chinese number (up to 99); returns (value, bytes consumed).
+fn parse_zh_number(s: &str) -> Option<(u32, usize)> {
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if !digits.is_empty() {
+        return digits.parse().ok().map(|n| (n, digits.len()));
+    }
+
+    let numeral = |c: char| -> Option<u32> {
+        match c {
+            '零' => Some(0),
+            '一' => Some(1),
+            '二' | '两' => Some(2),
+            '三' => Some(3),
+            '四' => Some(4),
+            '五' => Some(5),
+            '六' => Some(6),
+            '七' => Some(7),
+            '八' => Some(8),
+            '九' => Some(9),
+            _ => None,
+        }
+    };
+
+    let mut value: Option<u32> = None;
+    let mut tens: Option<u32> = None;
+    let mut len = 0;
+    for c in s.chars() {
+        if c == '十' {
+            if tens.is_some() {
+                break;
+            }
+            tens = Some(value.take().unwrap_or(1) * 10);
+        } else if let Some(n) = numeral(c) {
+            if value.is_some() {
+                break;
+            }
+            value = Some(n);
+        } else {
+            break;
+        }
+        len += c.len_utf8();
+    }
+
+    match (tens, value) {
+        (None, None) => None,
+        (t, v) => Some((t.unwrap_or(0) + v.unwrap_or(0), len)),
+    }
+}
+
+fn consume_zh_recurrence(s: &str) -> Option<(Recurrence, usize)> {
+    for prefix in ["每个工作日", "工作日"] {
+        if s.starts_with(prefix) {
+            return Some((recurrence("weekly", vec![1, 2, 3, 4, 5]), prefix.len()));
+        }
+    }
+    for prefix in ["每天", "每日"] {
+        if s.starts_with(prefix) {
+            return Some((recurrence("daily", vec![]), prefix.len()));
+        }
+    }
+    if s.starts_with("每月") {
+        return Some((recurrence("monthly", vec![]), "每月".len()));
+    }
+    for prefix in ["每星期", "每礼拜", "每周"] {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            if let Some(wd) = rest.chars().next().and_then(weekday_from_zh) {
+                let len = prefix.len() + rest.chars().next().map_or(0, char::len_utf8);
+                return Some((recurrence("weekly", vec![wd.number_from_monday()]), len));
+            }
+            return Some((recurrence("weekly", vec![]), prefix.len()));
+        }
+    }
+    None
+}
+
+fn consume_zh_date(s: &str, today: NaiveDate) -> Option<(NaiveDate, Option<NaiveTime>, usize)> {
+    let evening = NaiveTime::from_hms_opt(20, 0, 0);
+    let relative = [
+        ("大后天", 3, None),
+        ("后天", 2, None),
+        ("明天", 1, None),
+        ("明日", 1, None),
+        ("明晚", 1, evening),
+        ("今天", 0, None),
+        ("今日", 0, None),
+        ("今晚", 0, evening),
+    ];
+    for (word, days, time) in relative {
+        if s.starts_with(word) {
+            return Some((today + Duration::days(days), time, word.len()));
+        }
+    }
+
+    for prefix in ["下星期", "下礼拜", "下周"] {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            let c = rest.chars().next()?;
+            let wd = weekday_from_zh(c)?;
+            return Some((
+                next_week_weekday(today, wd),
+                None,
+                prefix.len() + c.len_utf8(),
+            ));
+        }
+    }
+    for prefix in ["星期", "礼拜", "周"] {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            let c = rest.chars().next()?;
+            let wd = weekday_from_zh(c)?;
+            return Some((
+                upcoming_weekday(today, wd),
+                None,
+                prefix.len() + c.len_utf8(),
+            ));
+        }
+    }
+
+    // "3月15日" / "三月十五号"
+    let (month, n1) = parse_zh_number(s)?;
+    let rest = s[n1..].strip_prefix('月')?;
+    let (day, n2) = parse_zh_number(rest)?;
+    let suffix = rest[n2..].chars().next();
+    let suffix_len = match suffix {
+        Some('日') | Some('号') => suffix.map_or(0, char::len_utf8),
+        _ => 0,
+    };
+    let mut date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        date = NaiveDate::from_ymd_opt(today.year() + 1, month, day)?;
+    }
+    Some((date, None, n1 + '月'.len_utf8() + n2 + suffix_len))
+}
+
+fn consume_zh_time(s: &str) -> Option<(NaiveTime, usize)> {
+    let periods = [
+        ("凌晨", 0),
+        ("早上", 8),
+        ("早晨", 8),
+        ("上午", 9),
+        ("中午", 12),
+        ("下午", 15),
+        ("傍晚", 18),
+        ("晚上", 20),
+    ];
+
+    let mut i = 0;
+    let mut period: Option<(&str, u32)> = None;
+    for (word, default_hour) in periods {
+        if s.starts_with(word) {
+            period = Some((word, default_hour));
+            i = word.len();
+            break;
+        }
+    }
+
+    if let Some((h, n)) = parse_zh_number(&s[i..]) {
+        let after = &s[i + n..];
+        let mut minute = 0;
+        let mut consumed = None;
+
+        if let Some(rest) = after
+            .strip_prefix('点')
+            .or_else(|| after.strip_prefix('时'))
+        {
+            let mut len = i + n + '点'.len_utf8();
+            if rest.starts_with('半') {
+                minute = 30;
+                len += '半'.len_utf8();
+            } else if let Some((m, n2)) = parse_zh_number(rest) {
+                if m < 60 {
+                    minute = m;
+                    len += n2;
+                    if rest[n2..].starts_with('分') {
+                        len += '分'.len_utf8();
+                    }
+                }
+            }
+            consumed = Some(len);
+        } else if let Some(rest) = after.strip_prefix(':').or_else(|| after.strip_prefix('：')) {
+            let (m, n2) = parse_zh_number(rest)?;
+            minute = m;
+            consumed = Some(i + n + after.len() - rest.len() + n2);
+        }
+
+        if let Some(len) = consumed {
+            let hour = match period.map(|p| p.0) {
+                Some("下午") | Some("傍晚") | Some("晚上") if h < 12 => h + 12,
+                Some("中午") if h < 6 => h + 12,
+                _ => h,
+            };
+            return NaiveTime::from_hms_opt(hour, minute, 0).map(|t| (t, len));
+        }
+    }
+
+    let (_, default_hour) = period?;
+    NaiveTime::from_hms_opt(default_hour, 0, 0).map(|t| (t, i))
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Finds a plan by exact title first, then by partial match.
+fn find_plan(conn: &rusqlite::Connection, name: &str) -> Result<Option<String>, String> {
+    let exact = conn.query_row(
+        "SELECT id FROM plans WHERE title = ? ORDER BY created_at DESC LIMIT 1",
+        [name],
+        |row| row.get(0),
+    );
+    match exact {
+        Ok(id) => return Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+
+    let pattern = format!("%{}%", name);
+    match conn.query_row(
+        "SELECT id FROM plans WHERE title LIKE ? ORDER BY created_at DESC LIMIT 1",
+        [&pattern],
+        |row| row.get(0),
+    ) {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ============================================================================
+// Command
+// ============================================================================
+
+#[tauri::command]
+pub fn quick_add(
+    state: tauri::State<AppState>,
+    text: String,
+    parse_only: Option<bool>,
+) -> Result<QuickAddResult, String> {
+    log_command!("quick_add", {
+        let parsed = parse_quick_add(&text, chrono::Local::now().naive_local());
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut warnings = Vec::new();
+        let plan_id = match &parsed.plan {
+            Some(name) => {
+                let found = find_plan(&conn, name)?;
+                if found.is_none() {
+                    warnings.push(format!("Plan '{}' not found, creating a todo", name));
+                }
+                found
+            }
+            None => None,
+        };
+        if plan_id.is_some() && parsed.recurrence.is_some() {
+            warnings.push("Recurrence is ignored for plan tasks".to_string());
+        }
+        if plan_id.is_none() && parsed.recurrence.is_some() {
+            if parsed.due_date.is_some() {
+                warnings.push("Due date is ignored for recurring items".to_string());
+            }
+            if parsed.priority.is_some() {
+                warnings.push("Priority is ignored for recurring items".to_string());
+            }
+        }
+
+        // Tasks store the day only, and it must fall within the plan
+        let task_end = parsed.due_date.as_ref().map(|d| d[..10].to_string());
+        let date_check = match &plan_id {
+            Some(plan_id) => check_task_dates(&conn, plan_id, None, task_end.as_deref()),
+            None => Ok(()),
+        };
+
+        if parse_only.unwrap_or(false) {
+            if let Err(e) = date_check {
+                warnings.push(e);
+            }
+            return Ok(QuickAddResult {
+                parsed,
+                entity_type: None,
+                entity_id: None,
+                plan_id,
+                warnings,
+            });
+        }
+
+        if parsed.title.trim().is_empty() {
+            return Err("Title cannot be empty".to_string());
+        }
+        date_check?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let priority = parsed.priority.clone().unwrap_or_else(|| "P2".to_string());
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+        let entity_type = if let Some(plan_id) = &plan_id {
+            tx.execute(
+                "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, position, created_at, updated_at) VALUES (?, ?, ?, NULL, NULL, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![id, plan_id, parsed.title, task_end, initial_status(&tx, plan_id)?, priority, next_position(&tx, "task", Some(plan_id))?, now, now],
+            )
+            .map_err(|e| e.to_string())?;
+            "task"
+        } else if let Some(rec) = &parsed.recurrence {
+            let frequency_config = if rec.weekdays.is_empty() {
+                None
+            } else {
+                Some(serde_json::json!({ "weekdays": rec.weekdays }).to_string())
+            };
+            tx.execute(
+                "INSERT INTO circulations (id, title, content, circulation_type, frequency, frequency_config, target_count, current_count, streak_count, best_streak, last_completed_at, status, created_at, updated_at)
+                 VALUES (?, ?, NULL, 'periodic', ?, ?, NULL, 0, 0, 0, NULL, 'active', ?, ?)",
+                rusqlite::params![id, parsed.title, rec.frequency, frequency_config, now, now],
+            )
+            .map_err(|e| e.to_string())?;
+            "circulation"
+        } else {
+            tx.execute(
+                "INSERT INTO todos (id, title, content, due_date, status, priority, position, created_at, updated_at) VALUES (?, ?, NULL, ?, 'pending', ?, ?, ?, ?)",
+                rusqlite::params![id, parsed.title, parsed.due_date, priority, next_position(&tx, "todo", None)?, now, now],
+            )
+            .map_err(|e| e.to_string())?;
+            "todo"
+        };
+
+        for name in &parsed.tags {
+            let tag_id = ensure_tag(&tx, name)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO entity_tags (entity_type, entity_id, tag_id) VALUES (?, ?, ?)",
+                rusqlite::params![entity_type, id, tag_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+
+        if let Some(plan_id) = &plan_id {
+            refresh_plan_progress(&conn, plan_id)?;
+        }
+
+        Ok(QuickAddResult {
+            parsed,
+            entity_type: Some(entity_type.to_string()),
+            entity_id: Some(id),
+            plan_id,
+            warnings,
+        })
+    })
+}
@@ -0,0 +1,298 @@
+// Plan schedule (critical path over finish-to-start task dependencies)
+//
+// Days are whole calendar days. A task occupies [start, start + duration), so
+// a successor may start on the day after its predecessor's end_date.
+
+use crate::log_command;
+use crate::AppState;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Serialize)]
+pub struct PlanSchedule {
+    pub plan_id: String,
+    pub project_start: String,
+    pub project_finish: String, // Last day of the latest task
+    pub duration_days: i64,
+    pub tasks: Vec<ScheduledTask>, // In topological order
+    pub links: Vec<ScheduleLink>,
+    pub critical_path: Vec<String>, // Task ids with zero slack, by earliest start
+    pub violations: Vec<DependencyViolation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduledTask {
+    pub task_id: String,
+    pub title: String,
+    pub status: String,
+    pub start_date: Option<String>, // As planned
+    pub end_date: Option<String>,
+    pub duration_days: i64,
+    pub earliest_start: String,
+    pub earliest_finish: String,
+    pub latest_start: String,
+    pub latest_finish: String,
+    pub slack_days: i64,
+    pub critical: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleLink {
+    pub dependency_id: String,
+    pub predecessor_id: String,
+    pub successor_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyViolation {
+    pub dependency_id: String,
+    pub predecessor_id: String,
+    pub successor_id: String,
+    pub predecessor_end: String,
+    pub successor_start: String,
+    pub overlap_days: i64, // Days the successor starts too early
+}
+
+/// Task input for the schedule calculation.
+pub struct ScheduleTaskInput {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Parses "YYYY-MM-DD" or an RFC 3339 timestamp to its date.
+pub fn parse_day(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn format_day(start: NaiveDate, offset: i64) -> Result<String, String> {
+    chrono::Duration::try_days(offset)
+        .and_then(|d| start.checked_add_signed(d))
+        .map(|day| day.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| format!("Schedule date out of range: {} days", offset))
+}
+
+/// Builds the schedule. Planned start dates act as "start no earlier than";
+/// tasks without dates last one day.
+pub fn compute_schedule(
+    plan_id: &str,
+    plan_start: Option<NaiveDate>,
+    tasks: Vec<ScheduleTaskInput>,
+    links: Vec<ScheduleLink>,
+) -> Result<PlanSchedule, String> {
+    let starts: Vec<Option<NaiveDate>> = tasks
+        .iter()
+        .map(|t| t.start_date.as_deref().and_then(parse_day))
+        .collect();
+    let ends: Vec<Option<NaiveDate>> = tasks
+        .iter()
+        .map(|t| t.end_date.as_deref().and_then(parse_day))
+        .collect();
+
+    let origin = plan_start
+        .into_iter()
+        .chain(starts.iter().flatten().copied())
+        .min()
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    let index: HashMap<&str, usize> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.id.as_str(), i))
+        .collect();
+
+    let n = tasks.len();
+    let durations: Vec<i64> = (0..n)
+        .map(|i| match (starts[i], ends[i]) {
+            (Some(s), Some(e)) => ((e - s).num_days() + 1).max(1),
+            _ => 1,
+        })
+        .collect();
+
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for link in &links {
+        if let (Some(&p), Some(&s)) = (
+            index.get(link.predecessor_id.as_str()),
+            index.get(link.successor_id.as_str()),
+        ) {
+            successors[p].push(s);
+            predecessors[s].push(p);
+        }
+    }
+
+    // Topological order (Kahn)
+    let mut in_degree: Vec<usize> = predecessors.iter().map(|p| p.len()).collect();
+    let mut queue: VecDeque<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(i) = queue.pop_front() {
+        order.push(i);
+        for &s in &successors[i] {
+            in_degree[s] -= 1;
+            if in_degree[s] == 0 {
+                queue.push_back(s);
+            }
+        }
+    }
+    if order.len() != n {
+        return Err("Task dependencies contain a cycle".to_string());
+    }
+
+    // Forward pass
+    let mut es = vec![0i64; n];
+    let mut ef = vec![0i64; n];
+    for &i in &order {
+        let planned = starts[i].map(|s| (s - origin).num_days()).unwrap_or(0);
+        let after_preds = predecessors[i].iter().map(|&p| ef[p]).max().unwrap_or(0);
+        es[i] = planned.max(after_preds);
+        ef[i] = es[i] + durations[i];
+    }
+    let finish = ef.iter().copied().max().unwrap_or(0);
+
+    // Backward pass
+    let mut lf = vec![finish; n];
+    let mut ls = vec![0i64; n];
+    for &i in order.iter().rev() {
+        if let Some(min_succ) = successors[i].iter().map(|&s| ls[s]).min() {
+            lf[i] = min_succ;
+        }
+        ls[i] = lf[i] - durations[i];
+    }
+
+    let mut violations = Vec::new();
+    for link in &links {
+        let (Some(&p), Some(&s)) = (
+            index.get(link.predecessor_id.as_str()),
+            index.get(link.successor_id.as_str()),
+        ) else {
+            continue;
+        };
+        if let (Some(pred_end), Some(succ_start)) = (ends[p], starts[s]) {
+            if succ_start <= pred_end {
+                violations.push(DependencyViolation {
+                    dependency_id: link.dependency_id.clone(),
+                    predecessor_id: link.predecessor_id.clone(),
+                    successor_id: link.successor_id.clone(),
+                    predecessor_end: pred_end.format("%Y-%m-%d").to_string(),
+                    successor_start: succ_start.format("%Y-%m-%d").to_string(),
+                    overlap_days: (pred_end - succ_start).num_days() + 1,
+                });
+            }
+        }
+    }
+
+    let mut critical: Vec<usize> = (0..n).filter(|&i| ls[i] == es[i]).collect();
+    critical.sort_by_key(|&i| (es[i], ef[i]));
+    let critical_path = critical.iter().map(|&i| tasks[i].id.clone()).collect();
+
+    let mut tasks: Vec<Option<ScheduleTaskInput>> = tasks.into_iter().map(Some).collect();
+    let scheduled = order
+        .iter()
+        .map(|&i| {
+            let task = tasks[i].take().expect("each task is scheduled once");
+            Ok(ScheduledTask {
+                task_id: task.id,
+                title: task.title,
+                status: task.status,
+                start_date: task.start_date,
+                end_date: task.end_date,
+                duration_days: durations[i],
+                earliest_start: format_day(origin, es[i])?,
+                earliest_finish: format_day(origin, ef[i] - 1)?,
+                latest_start: format_day(origin, ls[i])?,
+                latest_finish: format_day(origin, lf[i] - 1)?,
+                slack_days: ls[i] - es[i],
+                critical: ls[i] == es[i],
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(PlanSchedule {
+        plan_id: plan_id.to_string(),
+        project_start: format_day(origin, 0)?,
+        project_finish: format_day(origin, (finish - 1).max(0))?,
+        duration_days: finish,
+        tasks: scheduled,
+        links,
+        critical_path,
+        violations,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_plan_schedule(
+    state: tauri::State<AppState>,
+    plan_id: String,
+) -> Result<PlanSchedule, String> {
+    log_command!("get_plan_schedule", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let plan_start: Option<String> = conn
+            .query_row(
+                "SELECT start_date FROM plans WHERE id = ?",
+                [&plan_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, title, status, start_date, end_date FROM tasks WHERE plan_id = ?
+                 ORDER BY start_date, created_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let tasks: Vec<ScheduleTaskInput> = stmt
+            .query_map([&plan_id], |row| {
+                Ok(ScheduleTaskInput {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    status: row.get(2)?,
+                    start_date: row.get(3)?,
+                    end_date: row.get(4)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|t| t.ok())
+            .collect();
+
+        // Only links between tasks of this plan take part in the schedule
+        let mut stmt = conn
+            .prepare(
+                "SELECT d.id, d.blocker_id, d.blocked_id FROM dependencies d
+                 JOIN tasks a ON a.id = d.blocker_id
+                 JOIN tasks b ON b.id = d.blocked_id
+                 WHERE d.blocker_type = 'task' AND d.blocked_type = 'task'
+                 AND a.plan_id = ?1 AND b.plan_id = ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let links: Vec<ScheduleLink> = stmt
+            .query_map([&plan_id], |row| {
+                Ok(ScheduleLink {
+                    dependency_id: row.get(0)?,
+                    predecessor_id: row.get(1)?,
+                    successor_id: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|l| l.ok())
+            .collect();
+
+        compute_schedule(
+            &plan_id,
+            plan_start.as_deref().and_then(parse_day),
+            tasks,
+            links,
+        )
+    })
+}
@@ -110,6 +110,45 @@ pub fn search_all(
             .map_err(|e| e.to_string())?;
         results.extend(milestones.filter_map(|r| r.ok()));
 
+        // Search comments (body), reported as the entity they belong to
+        let mut stmt = conn
+            .prepare(
+                "SELECT c.entity_type, c.entity_id,
+                    COALESCE(t.title, p.title, k.title, g.title),
+                    c.body,
+                    COALESCE(t.status, p.status, k.status, g.status)
+                 FROM comments c
+                 LEFT JOIN todos t ON c.entity_type = 'todo' AND t.id = c.entity_id
+                 LEFT JOIN plans p ON c.entity_type = 'plan' AND p.id = c.entity_id
+                 LEFT JOIN tasks k ON c.entity_type = 'task' AND k.id = c.entity_id
+                 LEFT JOIN targets g ON c.entity_type = 'target' AND g.id = c.entity_id
+                 WHERE c.body LIKE ?
+                 AND COALESCE(t.id, p.id, k.id, g.id) IS NOT NULL
+                 ORDER BY c.created_at DESC",
+            )
+            .map_err(|e| e.to_string())?;
+        let comments: Vec<SearchResult> = stmt
+            .query_map([&pattern], |row| {
+                Ok(SearchResult {
+                    entity_type: row.get(0)?,
+                    id: row.get(1)?,
+                    title: row.get(2)?,
+                    content: row.get(3)?,
+                    status: row.get(4)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        for comment in comments {
+            let found = results
+                .iter()
+                .any(|r| r.entity_type == comment.entity_type && r.id == comment.id);
+            if !found {
+                results.push(comment);
+            }
+        }
+
         Ok(results)
     })
 }
@@ -0,0 +1,131 @@
+// Snooze commands (deferred start for todos and tasks)
+
+use crate::log_command;
+use crate::AppState;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SnoozeResult {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub defer_until: Option<String>,
+}
+
+fn entity_table(entity_type: &str) -> Result<&'static str, String> {
+    match entity_type {
+        "todo" => Ok("todos"),
+        "task" => Ok("tasks"),
+        _ => Err("Invalid entity_type. Use 'todo' or 'task'".to_string()),
+    }
+}
+
+/// Parses a snooze duration like "30m", "2h", "1d" or "1w".
+pub fn parse_snooze_duration(duration: &str) -> Result<chrono::Duration, String> {
+    let duration = duration.trim();
+    let invalid = || {
+        format!(
+            "Invalid duration '{}'. Use a number followed by m, h, d or w (e.g. '2h')",
+            duration
+        )
+    };
+
+    let unit = duration.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = duration[..duration.len() - unit.len_utf8()]
+        .trim()
+        .parse()
+        .map_err(|_| invalid())?;
+
+    if amount <= 0 {
+        return Err(invalid());
+    }
+
+    let delta = match unit {
+        'm' => chrono::Duration::try_minutes(amount),
+        'h' => chrono::Duration::try_hours(amount),
+        'd' => chrono::Duration::try_days(amount),
+        'w' => chrono::Duration::try_weeks(amount),
+        _ => None,
+    };
+    delta.ok_or_else(invalid)
+}
+
+/// The moment a snooze of `delta` from `now` ends.
+pub fn snooze_until(
+    now: chrono::DateTime<chrono::Utc>,
+    delta: chrono::Duration,
+    duration: &str,
+) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    now.checked_add_signed(delta)
+        .ok_or_else(|| format!("Invalid duration '{}': too far in the future", duration))
+}
+
+#[tauri::command]
+pub fn snooze(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+    duration: String,
+) -> Result<SnoozeResult, String> {
+    log_command!("snooze", {
+        let table = entity_table(&entity_type)?;
+        let delta = parse_snooze_duration(&duration)?;
+        let now = chrono::Utc::now();
+        let defer_until = snooze_until(now, delta, &duration)?.to_rfc3339();
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let rows = conn
+            .execute(
+                &format!(
+                    "UPDATE {} SET defer_until = ?, updated_at = ? WHERE id = ?",
+                    table
+                ),
+                rusqlite::params![defer_until, now.to_rfc3339(), entity_id],
+            )
+            .map_err(|e| e.to_string())?;
+
+        if rows == 0 {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        Ok(SnoozeResult {
+            entity_type,
+            entity_id,
+            defer_until: Some(defer_until),
+        })
+    })
+}
+
+#[tauri::command]
+pub fn unsnooze(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<SnoozeResult, String> {
+    log_command!("unsnooze", {
+        let table = entity_table(&entity_type)?;
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let rows = conn
+            .execute(
+                &format!(
+                    "UPDATE {} SET defer_until = NULL, updated_at = ? WHERE id = ?",
+                    table
+                ),
+                rusqlite::params![now, entity_id],
+            )
+            .map_err(|e| e.to_string())?;
+
+        if rows == 0 {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        Ok(SnoozeResult {
+            entity_type,
+            entity_id,
+            defer_until: None,
+        })
+    })
+}
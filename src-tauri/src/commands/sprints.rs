@@ -0,0 +1,363 @@
+// Sprint commands (time-boxed iterations inside a plan)
+//
+// A sprint goes planned -> active -> closed. Sprints of a plan do not overlap
+// and lie within the plan dates. Closing a sprint carries its unfinished tasks
+// over to the next open sprint, or back to the backlog when there is none.
+
+use super::schedule::parse_day;
+use super::workflows::task_category_sql;
+use crate::log_command;
+use crate::models::Sprint;
+use crate::AppState;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CloseSprintResult {
+    pub sprint: Sprint,
+    pub next_sprint_id: Option<String>, // None = moved to the backlog
+    pub carried_over: Vec<String>,      // Unfinished task ids
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const SPRINT_COLUMNS: &str =
+    "id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at";
+
+fn row_to_sprint(row: &rusqlite::Row) -> rusqlite::Result<Sprint> {
+    Ok(Sprint {
+        id: row.get(0)?,
+        plan_id: row.get(1)?,
+        name: row.get(2)?,
+        goal: row.get(3)?,
+        start_date: row.get(4)?,
+        end_date: row.get(5)?,
+        status: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+pub fn get_sprint_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Sprint, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM sprints WHERE id = ?", SPRINT_COLUMNS),
+        [id],
+        row_to_sprint,
+    )
+    .map_err(|_| format!("Sprint {} not found", id))
+}
+
+pub fn list_sprints(conn: &rusqlite::Connection, plan_id: &str) -> Result<Vec<Sprint>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM sprints WHERE plan_id = ? ORDER BY start_date, created_at",
+            SPRINT_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let sprints = stmt
+        .query_map([plan_id], row_to_sprint)
+        .map_err(|e| e.to_string())?
+        .filter_map(|s| s.ok())
+        .collect();
+    Ok(sprints)
+}
+
+/// Checks that sprint dates are ordered, inside the plan and do not overlap
+/// another sprint of the plan.
+fn check_sprint_dates(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    sprint_id: Option<&str>,
+    start_date: &str,
+    end_date: &str,
+) -> Result<(), String> {
+    let start = parse_day(start_date).ok_or("Invalid sprint start_date")?;
+    let end = parse_day(end_date).ok_or("Invalid sprint end_date")?;
+    if end < start {
+        return Err("Sprint end_date is before its start_date".to_string());
+    }
+
+    let (plan_start, plan_end): (Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT start_date, end_date FROM plans WHERE id = ?",
+            [plan_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| format!("Plan {} not found", plan_id))?;
+    let too_early = plan_start
+        .as_deref()
+        .and_then(parse_day)
+        .is_some_and(|s| start < s);
+    let too_late = plan_end
+        .as_deref()
+        .and_then(parse_day)
+        .is_some_and(|e| end > e);
+    if too_early || too_late {
+        return Err("Sprint dates must fall within the plan".to_string());
+    }
+
+    for other in list_sprints(conn, plan_id)? {
+        if Some(other.id.as_str()) == sprint_id {
+            continue;
+        }
+        let (Some(other_start), Some(other_end)) =
+            (parse_day(&other.start_date), parse_day(&other.end_date))
+        else {
+            continue;
+        };
+        if start <= other_end && other_start <= end {
+            return Err(format!("Sprint overlaps with {}", other.name));
+        }
+    }
+    Ok(())
+}
+
+/// Assigns tasks to a sprint of their plan, or back to the backlog with None.
+pub fn assign_sprint(
+    conn: &rusqlite::Connection,
+    task_ids: &[String],
+    sprint_id: Option<&str>,
+) -> Result<i32, String> {
+    let sprint = match sprint_id {
+        Some(id) => {
+            let sprint = get_sprint_by_id(conn, id)?;
+            if sprint.status == "closed" {
+                return Err("Cannot assign tasks to a closed sprint".to_string());
+            }
+            Some(sprint)
+        }
+        None => None,
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut updated = 0;
+    for task_id in task_ids {
+        let plan_id: String = conn
+            .query_row("SELECT plan_id FROM tasks WHERE id = ?", [task_id], |row| {
+                row.get(0)
+            })
+            .map_err(|_| format!("Task {} not found", task_id))?;
+        if sprint.as_ref().is_some_and(|s| s.plan_id != plan_id) {
+            return Err("Sprint belongs to another plan".to_string());
+        }
+        updated += conn
+            .execute(
+                "UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![sprint_id, now, task_id],
+            )
+            .map_err(|e| e.to_string())? as i32;
+    }
+    Ok(updated)
+}
+
+/// Closes a sprint and moves its unfinished tasks to `next_sprint_id`, or to
+/// the next open sprint of the plan when not given.
+pub fn close_sprint_and_carry_over(
+    conn: &rusqlite::Connection,
+    id: &str,
+    next_sprint_id: Option<&str>,
+) -> Result<CloseSprintResult, String> {
+    let sprint = get_sprint_by_id(conn, id)?;
+    if sprint.status == "closed" {
+        return Err("Sprint is already closed".to_string());
+    }
+
+    let next = match next_sprint_id {
+        Some(next_id) => {
+            let next = get_sprint_by_id(conn, next_id)?;
+            if next.id == sprint.id || next.plan_id != sprint.plan_id || next.status == "closed" {
+                return Err("Next sprint must be another open sprint of the plan".to_string());
+            }
+            Some(next)
+        }
+        None => list_sprints(conn, &sprint.plan_id)?.into_iter().find(|s| {
+            s.id != sprint.id && s.status != "closed" && s.start_date > sprint.start_date
+        }),
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id FROM tasks t WHERE sprint_id = ? AND {} != 'done'",
+            task_category_sql("t")
+        ))
+        .map_err(|e| e.to_string())?;
+    let carried_over: Vec<String> = stmt
+        .query_map([id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let next_sprint_id = next.map(|s| s.id);
+    let now = chrono::Utc::now().to_rfc3339();
+    for task_id in &carried_over {
+        conn.execute(
+            "UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![next_sprint_id, now, task_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE sprints SET status = 'closed', updated_at = ? WHERE id = ?",
+        rusqlite::params![now, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(CloseSprintResult {
+        sprint: get_sprint_by_id(conn, id)?,
+        next_sprint_id,
+        carried_over,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_sprints(state: tauri::State<AppState>, plan_id: String) -> Result<Vec<Sprint>, String> {
+    log_command!("get_sprints", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        list_sprints(&conn, &plan_id)
+    })
+}
+
+#[tauri::command]
+pub fn create_sprint(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    name: String,
+    goal: Option<String>,
+    start_date: String,
+    end_date: String,
+) -> Result<Sprint, String> {
+    log_command!("create_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        check_sprint_dates(&conn, &plan_id, None, &start_date, &end_date)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO sprints (id, plan_id, name, goal, start_date, end_date, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, 'planned', ?, ?)",
+            rusqlite::params![id, plan_id, name, goal, start_date, end_date, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_sprint_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn update_sprint(
+    state: tauri::State<AppState>,
+    id: String,
+    name: Option<String>,
+    goal: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Sprint, String> {
+    log_command!("update_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let sprint = get_sprint_by_id(&conn, &id)?;
+
+        let new_start_date = start_date.unwrap_or(sprint.start_date);
+        let new_end_date = end_date.unwrap_or(sprint.end_date);
+        check_sprint_dates(
+            &conn,
+            &sprint.plan_id,
+            Some(&id),
+            &new_start_date,
+            &new_end_date,
+        )?;
+
+        conn.execute(
+            "UPDATE sprints SET name = ?, goal = ?, start_date = ?, end_date = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![
+                name.unwrap_or(sprint.name),
+                goal.or(sprint.goal),
+                new_start_date,
+                new_end_date,
+                chrono::Utc::now().to_rfc3339(),
+                id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_sprint_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn start_sprint(state: tauri::State<AppState>, id: String) -> Result<Sprint, String> {
+    log_command!("start_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let sprint = get_sprint_by_id(&conn, &id)?;
+        if sprint.status != "planned" {
+            return Err(format!("Sprint is {}", sprint.status));
+        }
+        let active = list_sprints(&conn, &sprint.plan_id)?
+            .into_iter()
+            .find(|s| s.status == "active");
+        if let Some(active) = active {
+            return Err(format!("Sprint {} is still active", active.name));
+        }
+
+        conn.execute(
+            "UPDATE sprints SET status = 'active', updated_at = ? WHERE id = ?",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_sprint_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn close_sprint(
+    state: tauri::State<AppState>,
+    id: String,
+    next_sprint_id: Option<String>,
+) -> Result<CloseSprintResult, String> {
+    log_command!("close_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let result = close_sprint_and_carry_over(&tx, &id, next_sprint_id.as_deref())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(result)
+    })
+}
+
+#[tauri::command]
+pub fn delete_sprint(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        // Tasks of the sprint go back to the backlog
+        conn.execute(
+            "UPDATE tasks SET sprint_id = NULL WHERE sprint_id = ?",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM sprints WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn assign_tasks_to_sprint(
+    state: tauri::State<AppState>,
+    task_ids: Vec<String>,
+    sprint_id: Option<String>,
+) -> Result<i32, String> {
+    log_command!("assign_tasks_to_sprint", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let updated = assign_sprint(&tx, &task_ids, sprint_id.as_deref())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(updated)
+    })
+}
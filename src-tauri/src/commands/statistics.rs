@@ -1,5 +1,7 @@
 // Statistics and analytics commands
 
+use super::time_tracking::ENTRY_SECONDS_SQL;
+use super::workflows::task_category_sql;
 use crate::log_command;
 use crate::AppState;
 use serde::Serialize;
@@ -10,6 +12,9 @@ pub struct Statistics {
     pub completion: CompletionStats,
     pub trends: TrendStats,
     pub efficiency: EfficiencyStats,
+    pub time: TimeStats,
+    pub focus: FocusStats,
+    pub velocity: VelocityStats,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,6 +63,68 @@ pub struct EfficiencyStats {
     pub productivity_score: i32,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TimeStats {
+    pub today_seconds: i64,
+    pub week_seconds: i64,
+    pub daily: Vec<DailyTimeStat>,   // Last 7 days
+    pub weekly: Vec<WeeklyTimeStat>, // Last 4 weeks
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyTimeStat {
+    pub date: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeeklyTimeStat {
+    pub week_start: String, // Monday
+    pub seconds: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FocusStats {
+    pub today_minutes: i64,
+    pub daily: Vec<DailyFocusStat>,      // Last 7 days
+    pub by_entity: Vec<EntityFocusStat>, // Last 30 days, most focused first
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyFocusStat {
+    pub date: String,
+    pub minutes: i64,
+    pub sessions: i32, // Completed pomodoros
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntityFocusStat {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub title: Option<String>, // None if the entity was deleted
+    pub minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VelocityStats {
+    pub sprints: Vec<SprintVelocity>, // By start date
+    pub average_completed: f32,       // Closed sprints only
+    pub average_points: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SprintVelocity {
+    pub sprint_id: String,
+    pub plan_id: String,
+    pub name: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub status: String,
+    pub completed: i32,        // Done tasks
+    pub completed_points: f64, // Estimates of done tasks in points
+    pub remaining: i32,        // Unfinished tasks still in the sprint
+}
+
 #[tauri::command]
 pub fn get_statistics(state: tauri::State<AppState>) -> Result<Statistics, String> {
     log_command!("get_statistics", {
@@ -67,16 +134,33 @@ pub fn get_statistics(state: tauri::State<AppState>) -> Result<Statistics, Strin
         let completion = get_completion_stats(&conn)?;
         let trends = get_trend_stats(&conn)?;
         let efficiency = get_efficiency_stats(&conn)?;
+        let time = get_time_stats(&conn)?;
+        let focus = get_focus_stats(&conn)?;
+        let velocity = get_velocity_stats(&conn, None)?;
 
         Ok(Statistics {
             counts,
             completion,
             trends,
             efficiency,
+            time,
+            focus,
+            velocity,
         })
     })
 }
 
+#[tauri::command]
+pub fn get_sprint_velocity(
+    state: tauri::State<AppState>,
+    plan_id: String,
+) -> Result<VelocityStats, String> {
+    log_command!("get_sprint_velocity", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        get_velocity_stats(&conn, Some(&plan_id))
+    })
+}
+
 fn get_counts(conn: &rusqlite::Connection) -> Result<EntityCounts, String> {
     let todo_count: i32 = conn
         .query_row("SELECT COUNT(*) FROM todos", [], |row| row.get(0))
@@ -117,7 +201,7 @@ fn get_completion_stats(conn: &rusqlite::Connection) -> Result<CompletionStats,
     let (todo_done, todo_total): (i32, i32) = conn
         .query_row(
             "SELECT 
-                COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN status IN ('done', 'archived') THEN 1 ELSE 0 END), 0),
                 COUNT(*)
             FROM todos",
             [],
@@ -133,10 +217,13 @@ fn get_completion_stats(conn: &rusqlite::Connection) -> Result<CompletionStats,
     // Task stats
     let (task_done, task_total): (i32, i32) = conn
         .query_row(
-            "SELECT 
-                COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0),
-                COUNT(*)
-            FROM tasks",
+            &format!(
+                "SELECT
+                    COALESCE(SUM(CASE WHEN {} = 'done' THEN 1 ELSE 0 END), 0),
+                    COUNT(*)
+                FROM tasks",
+                task_category_sql("tasks")
+            ),
             [],
             |row| {
                 Ok((
@@ -231,7 +318,7 @@ fn get_trend_stats(conn: &rusqlite::Connection) -> Result<TrendStats, String> {
                 date(updated_at) as date,
                 COUNT(*) as completed
             FROM todos
-            WHERE status = 'done'
+            WHERE status IN ('done', 'archived')
                 AND date(updated_at) >= date('now', '-7 days')
             GROUP BY date(updated_at)
             ORDER BY date(updated_at)",
@@ -259,7 +346,7 @@ fn get_efficiency_stats(conn: &rusqlite::Connection) -> Result<EfficiencyStats,
     let today_completed: i32 = conn
         .query_row(
             "SELECT COUNT(*) FROM todos 
-            WHERE status = 'done' 
+            WHERE status IN ('done', 'archived') 
             AND date(updated_at) = date('now')",
             [],
             |row| row.get(0),
@@ -270,7 +357,7 @@ fn get_efficiency_stats(conn: &rusqlite::Connection) -> Result<EfficiencyStats,
     let week_completed: i32 = conn
         .query_row(
             "SELECT COUNT(*) FROM todos 
-            WHERE status = 'done' 
+            WHERE status IN ('done', 'archived') 
             AND date(updated_at) >= date('now', '-7 days')",
             [],
             |row| row.get(0),
@@ -281,7 +368,7 @@ fn get_efficiency_stats(conn: &rusqlite::Connection) -> Result<EfficiencyStats,
     let month_completed: i32 = conn
         .query_row(
             "SELECT COUNT(*) FROM todos 
-            WHERE status = 'done' 
+            WHERE status IN ('done', 'archived') 
             AND date(updated_at) >= date('now', '-30 days')",
             [],
             |row| row.get(0),
@@ -309,7 +396,7 @@ fn calculate_streak(conn: &rusqlite::Connection) -> Result<i32, String> {
         .prepare(
             "SELECT DISTINCT date(updated_at) as completion_date
             FROM todos
-            WHERE status = 'done'
+            WHERE status IN ('done', 'archived')
                 AND date(updated_at) >= date('now', '-30 days')
             ORDER BY completion_date DESC",
         )
@@ -361,11 +448,11 @@ fn calculate_productivity_score(conn: &rusqlite::Connection, streak: i32) -> Res
     let (completed, total): (i32, i32) = conn
         .query_row(
             "SELECT 
-                COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN status IN ('done', 'archived') THEN 1 ELSE 0 END), 0),
                 COUNT(*)
             FROM todos
             WHERE date(updated_at) >= date('now', '-7 days')
-                OR status != 'done'",
+                OR status NOT IN ('done', 'archived')",
             [],
             |row| {
                 Ok((
@@ -386,3 +473,203 @@ fn calculate_productivity_score(conn: &rusqlite::Connection, streak: i32) -> Res
     let score = (completion_rate * 0.7) + (streak.min(10) as f32 * 3.0);
     Ok(score.min(100.0) as i32)
 }
+
+fn get_time_stats(conn: &rusqlite::Connection) -> Result<TimeStats, String> {
+    let sum_since = |modifier: &str| -> Result<i64, String> {
+        conn.query_row(
+            &format!(
+                "SELECT COALESCE(SUM({}), 0) FROM time_entries WHERE date(started_at) >= date('now', ?)",
+                ENTRY_SECONDS_SQL
+            ),
+            [modifier],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+    };
+
+    let today_seconds = sum_since("+0 days")?;
+    let week_seconds = sum_since("-7 days")?;
+
+    // Daily breakdown (last 7 days)
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT date(started_at) as date, SUM({})
+            FROM time_entries
+            WHERE date(started_at) >= date('now', '-7 days')
+            GROUP BY date(started_at)
+            ORDER BY date(started_at)",
+            ENTRY_SECONDS_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let daily = stmt
+        .query_map([], |row| {
+            Ok(DailyTimeStat {
+                date: row.get(0)?,
+                seconds: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Weekly breakdown (last 4 weeks, weeks start on Monday)
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT date(started_at, '-6 days', 'weekday 1') as week_start, SUM({})
+            FROM time_entries
+            WHERE date(started_at) >= date('now', '-6 days', 'weekday 1', '-21 days')
+            GROUP BY week_start
+            ORDER BY week_start",
+            ENTRY_SECONDS_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let weekly = stmt
+        .query_map([], |row| {
+            Ok(WeeklyTimeStat {
+                week_start: row.get(0)?,
+                seconds: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(TimeStats {
+        today_seconds,
+        week_seconds,
+        daily,
+        weekly,
+    })
+}
+
+fn get_focus_stats(conn: &rusqlite::Connection) -> Result<FocusStats, String> {
+    let today_minutes: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(focus_seconds), 0) / 60 FROM pomodoro_sessions WHERE date(started_at) = date('now')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Daily breakdown (last 7 days)
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(started_at) as date, SUM(focus_seconds) / 60,
+                SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END)
+            FROM pomodoro_sessions
+            WHERE date(started_at) >= date('now', '-7 days')
+            GROUP BY date(started_at)
+            ORDER BY date(started_at)",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let daily = stmt
+        .query_map([], |row| {
+            Ok(DailyFocusStat {
+                date: row.get(0)?,
+                minutes: row.get(1)?,
+                sessions: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Per todo / task (last 30 days)
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.entity_type, p.entity_id,
+                CASE p.entity_type
+                    WHEN 'todo' THEN (SELECT title FROM todos WHERE id = p.entity_id)
+                    WHEN 'task' THEN (SELECT title FROM tasks WHERE id = p.entity_id)
+                END,
+                SUM(p.focus_seconds) / 60 as minutes
+            FROM pomodoro_sessions p
+            WHERE p.entity_id IS NOT NULL AND date(p.started_at) >= date('now', '-30 days')
+            GROUP BY p.entity_type, p.entity_id
+            ORDER BY minutes DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let by_entity = stmt
+        .query_map([], |row| {
+            Ok(EntityFocusStat {
+                entity_type: row.get(0)?,
+                entity_id: row.get(1)?,
+                title: row.get(2)?,
+                minutes: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(FocusStats {
+        today_minutes,
+        daily,
+        by_entity,
+    })
+}
+
+/// Sprint velocity of one plan, or of the last 10 sprints of all plans.
+pub fn get_velocity_stats(
+    conn: &rusqlite::Connection,
+    plan_id: Option<&str>,
+) -> Result<VelocityStats, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT * FROM (
+                SELECT s.id, s.plan_id, s.name, s.start_date, s.end_date, s.status,
+                    (SELECT COUNT(*) FROM tasks t WHERE t.sprint_id = s.id AND {done}),
+                    (SELECT COALESCE(SUM(t.estimate), 0) FROM tasks t WHERE t.sprint_id = s.id AND t.estimate_unit = 'points' AND {done}),
+                    (SELECT COUNT(*) FROM tasks t WHERE t.sprint_id = s.id AND NOT {done})
+                FROM sprints s
+                WHERE ?1 IS NULL OR s.plan_id = ?1
+                ORDER BY s.start_date DESC
+                LIMIT CASE WHEN ?1 IS NULL THEN 10 ELSE -1 END
+            ) ORDER BY start_date",
+            done = format!("{} = 'done'", task_category_sql("t"))
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let sprints: Vec<SprintVelocity> = stmt
+        .query_map([plan_id], |row| {
+            Ok(SprintVelocity {
+                sprint_id: row.get(0)?,
+                plan_id: row.get(1)?,
+                name: row.get(2)?,
+                start_date: row.get(3)?,
+                end_date: row.get(4)?,
+                status: row.get(5)?,
+                completed: row.get(6)?,
+                completed_points: row.get(7)?,
+                remaining: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let closed: Vec<&SprintVelocity> = sprints.iter().filter(|s| s.status == "closed").collect();
+    let (average_completed, average_points) = if closed.is_empty() {
+        (0.0, 0.0)
+    } else {
+        let n = closed.len() as f32;
+        (
+            closed.iter().map(|s| s.completed as f32).sum::<f32>() / n,
+            closed
+                .iter()
+                .map(|s| s.completed_points as f32)
+                .sum::<f32>()
+                / n,
+        )
+    };
+
+    Ok(VelocityStats {
+        sprints,
+        average_completed,
+        average_points,
+    })
+}
@@ -0,0 +1,347 @@
+// Step link commands (target steps backed by todos, tasks or whole plans)
+//
+// A linked step follows its work: it completes when every linked item is done
+// and reopens when one is not. Until then the linked items' average progress
+// counts towards the target as a share of the step's weight. The stored
+// targets.progress is refreshed whenever linked work changes.
+
+use super::burndown::{record_status_change, step_category};
+use super::targets::calculate_target_progress;
+use super::workflows::task_category_sql;
+use crate::log_command;
+use crate::AppState;
+use serde::Serialize;
+
+pub const LINK_TYPES: [&str; 3] = ["todo", "task", "plan"];
+
+#[derive(Debug, Serialize)]
+pub struct StepLinkInfo {
+    pub id: String,
+    pub step_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub title: String,
+    pub progress: i32, // 0-100
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Title and progress (0-100) of a linkable item, None when it does not exist
+/// or has an unknown type. Archived todos were done before they were archived.
+fn linked_item(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Option<(String, i32)>, String> {
+    let item = match entity_type {
+        "todo" => conn
+            .query_row(
+                "SELECT title, status IN ('done', 'archived') FROM todos WHERE id = ?",
+                [entity_id],
+                |row| Ok((row.get(0)?, if row.get(1)? { 100 } else { 0 })),
+            )
+            .ok(),
+        "task" => conn
+            .query_row(
+                &format!(
+                    "SELECT title, {} = 'done' FROM tasks t WHERE id = ?",
+                    task_category_sql("t")
+                ),
+                [entity_id],
+                |row| Ok((row.get(0)?, if row.get(1)? { 100 } else { 0 })),
+            )
+            .ok(),
+        "plan" => conn
+            .query_row(
+                "SELECT title, CASE WHEN status = 'completed' THEN 100 ELSE progress END FROM plans WHERE id = ?",
+                [entity_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok(),
+        _ => None,
+    };
+    Ok(item)
+}
+
+/// Checks that a link's type is known and its step exists.
+pub fn check_step_link(
+    conn: &rusqlite::Connection,
+    step_id: &str,
+    entity_type: &str,
+) -> Result<(), String> {
+    if !LINK_TYPES.contains(&entity_type) {
+        return Err(format!("Invalid link type: {}", entity_type));
+    }
+    let step_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM steps WHERE id = ?)",
+            [step_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !step_exists {
+        return Err(format!("Step {} not found", step_id));
+    }
+    Ok(())
+}
+
+pub fn list_step_links(
+    conn: &rusqlite::Connection,
+    step_id: &str,
+) -> Result<Vec<StepLinkInfo>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, entity_type, entity_id FROM step_links WHERE step_id = ? ORDER BY created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let links: Vec<(String, String, String)> = stmt
+        .query_map([step_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut result = Vec::new();
+    for (id, entity_type, entity_id) in links {
+        let Some((title, progress)) = linked_item(conn, &entity_type, &entity_id)? else {
+            continue;
+        };
+        result.push(StepLinkInfo {
+            id,
+            step_id: step_id.to_string(),
+            entity_type,
+            entity_id,
+            title,
+            progress,
+        });
+    }
+    Ok(result)
+}
+
+/// Progress of a step from its linked work (0-100), None without links.
+pub fn step_link_progress(
+    conn: &rusqlite::Connection,
+    step_id: &str,
+) -> Result<Option<i32>, String> {
+    let links = list_step_links(conn, step_id)?;
+    if links.is_empty() {
+        return Ok(None);
+    }
+    let total: i32 = links.iter().map(|link| link.progress).sum();
+    Ok(Some(total / links.len() as i32))
+}
+
+/// Stores the calculated progress of a target.
+pub fn refresh_target_progress(conn: &rusqlite::Connection, target_id: &str) -> Result<(), String> {
+    let progress = calculate_target_progress(conn, target_id)?;
+    conn.execute(
+        "UPDATE targets SET progress = ? WHERE id = ?",
+        rusqlite::params![progress, target_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Completes or reopens a linked step after its work changed, then refreshes
+/// the target's progress. Steps without links keep their status.
+pub fn sync_step(conn: &rusqlite::Connection, step_id: &str) -> Result<(), String> {
+    let step: Option<(String, String)> = conn
+        .query_row(
+            "SELECT target_id, status FROM steps WHERE id = ?",
+            [step_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((target_id, status)) = step else {
+        return Ok(());
+    };
+
+    if let Some(progress) = step_link_progress(conn, step_id)? {
+        let new_status = if progress == 100 {
+            "completed"
+        } else {
+            "pending"
+        };
+        if new_status != status {
+            conn.execute(
+                "UPDATE steps SET status = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![new_status, chrono::Utc::now().to_rfc3339(), step_id],
+            )
+            .map_err(|e| e.to_string())?;
+            record_status_change(
+                conn,
+                "step",
+                step_id,
+                Some(&status),
+                new_status,
+                step_category(new_status),
+            )?;
+        }
+    }
+
+    refresh_target_progress(conn, &target_id)
+}
+
+pub fn sync_steps(conn: &rusqlite::Connection, step_ids: &[String]) -> Result<(), String> {
+    for step_id in step_ids {
+        sync_step(conn, step_id)?;
+    }
+    Ok(())
+}
+
+fn linked_step_ids(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT step_id FROM step_links WHERE entity_type = ? AND entity_id = ?")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(rusqlite::params![entity_type, entity_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Steps linked to a plan or to any of its tasks.
+fn plan_step_ids(conn: &rusqlite::Connection, plan_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT step_id FROM step_links
+             WHERE (entity_type = 'plan' AND entity_id = ?1)
+             OR (entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?1))",
+        )
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([plan_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Syncs the steps linked to a todo or task after it changed.
+pub fn sync_linked_steps(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    sync_steps(conn, &linked_step_ids(conn, entity_type, entity_id)?)
+}
+
+/// Syncs the steps linked to a plan or its tasks after the plan's progress
+/// was refreshed.
+pub fn sync_plan_links(conn: &rusqlite::Connection, plan_id: &str) -> Result<(), String> {
+    sync_steps(conn, &plan_step_ids(conn, plan_id)?)
+}
+
+/// Removes the links to a deleted todo or task and syncs the steps that had
+/// them against their remaining links.
+pub fn delete_entity_step_links(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    let step_ids = linked_step_ids(conn, entity_type, entity_id)?;
+    conn.execute(
+        "DELETE FROM step_links WHERE entity_type = ? AND entity_id = ?",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    sync_steps(conn, &step_ids)
+}
+
+/// Removes the links to a plan and its tasks before the plan is deleted.
+/// Returns the steps that had them, to sync once the plan is gone.
+pub fn delete_plan_step_links(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+) -> Result<Vec<String>, String> {
+    let step_ids = plan_step_ids(conn, plan_id)?;
+    conn.execute(
+        "DELETE FROM step_links
+         WHERE (entity_type = 'plan' AND entity_id = ?1)
+         OR (entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?1))",
+        [plan_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(step_ids)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_step_links(
+    state: tauri::State<AppState>,
+    step_id: String,
+) -> Result<Vec<StepLinkInfo>, String> {
+    log_command!("get_step_links", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        list_step_links(&conn, &step_id)
+    })
+}
+
+/// Links a step to a todo, task or plan and syncs the step with it.
+#[tauri::command]
+pub fn link_step(
+    state: tauri::State<AppState>,
+    step_id: String,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<StepLinkInfo>, String> {
+    log_command!("link_step", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        check_step_link(&conn, &step_id, &entity_type)?;
+        if linked_item(&conn, &entity_type, &entity_id)?.is_none() {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT OR IGNORE INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                step_id,
+                entity_type,
+                entity_id,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        sync_step(&tx, &step_id)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        list_step_links(&conn, &step_id)
+    })
+}
+
+/// Removes a link. The step keeps its status; with other links left it is
+/// synced against those.
+#[tauri::command]
+pub fn unlink_step(
+    state: tauri::State<AppState>,
+    step_id: String,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<StepLinkInfo>, String> {
+    log_command!("unlink_step", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "DELETE FROM step_links WHERE step_id = ? AND entity_type = ? AND entity_id = ?",
+            rusqlite::params![step_id, entity_type, entity_id],
+        )
+        .map_err(|e| e.to_string())?;
+        sync_step(&tx, &step_id)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        list_step_links(&conn, &step_id)
+    })
+}
@@ -0,0 +1,184 @@
+// Step weight commands (how a target splits 100% over its steps)
+//
+// A target's weight mode decides what happens when steps come and go:
+// - manual: weights are kept as entered and may not exceed 100% in total
+// - equal: every step gets the same share, recalculated on each change
+// - proportional: the other steps are scaled so the total stays at 100%
+
+use super::steps::list_steps;
+use super::targets::get_target_by_id;
+use crate::log_command;
+use crate::models::{Step, Target};
+use crate::AppState;
+
+pub const WEIGHT_MODES: [&str; 3] = ["manual", "equal", "proportional"];
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Weight mode of a target; unknown or empty values count as manual.
+pub fn target_weight_mode(conn: &rusqlite::Connection, target_id: &str) -> Result<String, String> {
+    let mode: String = conn
+        .query_row(
+            "SELECT weight_mode FROM targets WHERE id = ?",
+            [target_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| format!("Target {} not found", target_id))?;
+    if WEIGHT_MODES.contains(&mode.as_str()) {
+        Ok(mode)
+    } else {
+        Ok("manual".to_string())
+    }
+}
+
+/// Splits `total` into `n` whole parts that differ by at most one.
+pub fn equal_weights(n: usize, total: i32) -> Vec<i32> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let base = total / n as i32;
+    let extra = (total % n as i32) as usize;
+    (0..n).map(|i| base + i32::from(i < extra)).collect()
+}
+
+/// Scales weights to sum to `total`, keeping their ratios. Rounding goes to
+/// the largest remainders; all-zero weights are split equally.
+pub fn scale_weights(weights: &[i32], total: i32) -> Vec<i32> {
+    let sum: i64 = weights.iter().map(|w| i64::from((*w).max(0))).sum();
+    if sum == 0 {
+        return equal_weights(weights.len(), total);
+    }
+
+    let exact: Vec<(i64, i64)> = weights
+        .iter()
+        .map(|w| {
+            let scaled = i64::from((*w).max(0)) * i64::from(total);
+            (scaled / sum, scaled % sum)
+        })
+        .collect();
+    let mut result: Vec<i32> = exact.iter().map(|(whole, _)| *whole as i32).collect();
+
+    let short = total - result.iter().sum::<i32>();
+    let mut by_remainder: Vec<usize> = (0..exact.len()).collect();
+    by_remainder.sort_by(|a, b| exact[*b].1.cmp(&exact[*a].1).then(a.cmp(b)));
+    for i in by_remainder.into_iter().take(short.max(0) as usize) {
+        result[i] += 1;
+    }
+    result
+}
+
+fn write_weights(
+    conn: &rusqlite::Connection,
+    steps: &[Step],
+    weights: &[i32],
+) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    for (step, weight) in steps.iter().zip(weights) {
+        if step.weight == *weight {
+            continue;
+        }
+        conn.execute(
+            "UPDATE steps SET weight = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![weight, now, step.id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Re-applies the target's weight mode after a step was added, removed or
+/// reweighted. In proportional mode `fixed_step_id` keeps its weight and the
+/// other steps share the rest. Manual targets are left alone.
+pub fn rebalance_weights(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+    fixed_step_id: Option<&str>,
+) -> Result<(), String> {
+    let steps = list_steps(conn, target_id)?;
+    match target_weight_mode(conn, target_id)?.as_str() {
+        "equal" => write_weights(conn, &steps, &equal_weights(steps.len(), 100)),
+        "proportional" => {
+            let fixed = fixed_step_id.and_then(|id| steps.iter().find(|s| s.id == id));
+            let Some(fixed) = fixed else {
+                let weights: Vec<i32> = steps.iter().map(|s| s.weight).collect();
+                return write_weights(conn, &steps, &scale_weights(&weights, 100));
+            };
+
+            let fixed_weight = fixed.weight.clamp(0, 100);
+            let others: Vec<Step> = steps.iter().filter(|s| s.id != fixed.id).cloned().collect();
+            if others.is_empty() {
+                return write_weights(conn, std::slice::from_ref(fixed), &[100]);
+            }
+            let weights: Vec<i32> = others.iter().map(|s| s.weight).collect();
+            write_weights(conn, std::slice::from_ref(fixed), &[fixed_weight])?;
+            write_weights(conn, &others, &scale_weights(&weights, 100 - fixed_weight))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Makes a target's step weights sum to 100%: equal shares in equal mode,
+/// otherwise scaled in proportion to the current weights.
+pub fn normalize_weights(conn: &rusqlite::Connection, target_id: &str) -> Result<(), String> {
+    let steps = list_steps(conn, target_id)?;
+    if target_weight_mode(conn, target_id)? == "equal" {
+        return write_weights(conn, &steps, &equal_weights(steps.len(), 100));
+    }
+    let weights: Vec<i32> = steps.iter().map(|s| s.weight).collect();
+    write_weights(conn, &steps, &scale_weights(&weights, 100))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Sets a target's weight mode. Switching to an automatic mode rebalances the
+/// existing steps right away.
+#[tauri::command]
+pub fn set_step_weight_mode(
+    state: tauri::State<AppState>,
+    target_id: String,
+    mode: String,
+) -> Result<Target, String> {
+    log_command!("set_step_weight_mode", {
+        if !WEIGHT_MODES.contains(&mode.as_str()) {
+            return Err(format!("Invalid weight mode: {}", mode));
+        }
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let rows = tx
+            .execute(
+                "UPDATE targets SET weight_mode = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![mode, chrono::Utc::now().to_rfc3339(), target_id],
+            )
+            .map_err(|e| e.to_string())?;
+        if rows == 0 {
+            return Err("Target not found".to_string());
+        }
+        if mode != "manual" {
+            normalize_weights(&tx, &target_id)?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        get_target_by_id(&conn, &target_id)
+    })
+}
+
+#[tauri::command]
+pub fn normalize_step_weights(
+    state: tauri::State<AppState>,
+    target_id: String,
+) -> Result<Vec<Step>, String> {
+    log_command!("normalize_step_weights", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        normalize_weights(&tx, &target_id)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        list_steps(&conn, &target_id)
+    })
+}
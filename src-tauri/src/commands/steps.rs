@@ -1,34 +1,60 @@
 // Step CRUD commands
 
+use super::burndown::{delete_status_history, record_status_change, step_category};
+use super::estimates::normalize_estimate;
+use super::ordering::next_position;
+use super::step_weights::{rebalance_weights, target_weight_mode};
 use crate::log_command;
 use crate::models::Step;
 use crate::AppState;
 
+const STEP_COLUMNS: &str = "id, target_id, title, weight, status, priority, created_at, updated_at, estimate, estimate_unit, position";
+
+fn row_to_step(row: &rusqlite::Row) -> rusqlite::Result<Step> {
+    Ok(Step {
+        id: row.get(0)?,
+        target_id: row.get(1)?,
+        title: row.get(2)?,
+        weight: row.get(3)?,
+        status: row.get(4)?,
+        priority: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        estimate: row.get(8)?,
+        estimate_unit: row.get(9)?,
+        position: row.get(10)?,
+    })
+}
+
+pub fn get_step_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Step, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM steps WHERE id = ?", STEP_COLUMNS),
+        [id],
+        row_to_step,
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn list_steps(conn: &rusqlite::Connection, target_id: &str) -> Result<Vec<Step>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM steps WHERE target_id = ? ORDER BY position, created_at",
+            STEP_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let steps = stmt
+        .query_map([target_id], row_to_step)
+        .map_err(|e| e.to_string())?
+        .filter_map(|s| s.ok())
+        .collect();
+    Ok(steps)
+}
+
 #[tauri::command]
 pub fn get_steps(state: tauri::State<AppState>, target_id: String) -> Result<Vec<Step>, String> {
     log_command!("get_steps", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
-
-        let mut stmt = conn
-            .prepare("SELECT id, target_id, title, weight, status, priority, created_at, updated_at FROM steps WHERE target_id = ?")
-            .map_err(|e| e.to_string())?;
-
-        let step_iter = stmt
-            .query_map([&target_id], |row| {
-                Ok(Step {
-                    id: row.get(0)?,
-                    target_id: row.get(1)?,
-                    title: row.get(2)?,
-                    weight: row.get(3)?,
-                    status: row.get(4)?,
-                    priority: row.get(5)?,
-                    created_at: row.get(6)?,
-                    updated_at: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-
-        Ok(step_iter.filter_map(|s| s.ok()).collect())
+        list_steps(&conn, &target_id)
     })
 }
 
@@ -39,45 +65,49 @@ pub fn create_step(
     title: String,
     weight: i32,
     priority: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
 ) -> Result<Step, String> {
     log_command!("create_step", {
+        let estimate_unit = normalize_estimate(estimate, estimate_unit)?;
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
-        // Validate weight sum (existing + new <= 100)
-        let mut stmt = conn
-            .prepare("SELECT COALESCE(SUM(weight), 0) FROM steps WHERE target_id = ?")
-            .map_err(|e| e.to_string())?;
-
-        let current_weight: i32 = stmt
-            .query_row([&target_id], |row| row.get(0))
-            .map_err(|e| e.to_string())?;
+        // Validate weight sum (existing + new <= 100) in manual mode
+        let manual = target_weight_mode(&conn, &target_id)? == "manual";
+        let weight = if manual { weight } else { weight.clamp(0, 100) };
+        if manual {
+            let current_weight: i32 = conn
+                .query_row(
+                    "SELECT COALESCE(SUM(weight), 0) FROM steps WHERE target_id = ?",
+                    [&target_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
 
-        if current_weight + weight > 100 {
-            return Err(format!(
-                "Step weights would exceed 100%. Current: {}%, New: {}%",
-                current_weight, weight
-            ));
+            if current_weight + weight > 100 {
+                return Err(format!(
+                    "Step weights would exceed 100%. Current: {}%, New: {}%",
+                    current_weight, weight
+                ));
+            }
         }
 
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
         let priority = priority.unwrap_or_else(|| "P2".to_string());
+        let position = next_position(&conn, "step", Some(&target_id))?;
 
-        conn.execute(
-            "INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?)",
-            rusqlite::params![id, target_id, title, weight, priority, now, now],
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO steps (id, target_id, title, weight, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, target_id, title, weight, priority, estimate, estimate_unit, position, now, now],
         ).map_err(|e| e.to_string())?;
+        if !manual {
+            rebalance_weights(&tx, &target_id, Some(&id))?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
 
-        Ok(Step {
-            id,
-            target_id,
-            title,
-            weight,
-            status: "pending".to_string(),
-            priority,
-            created_at: now.clone(),
-            updated_at: now,
-        })
+        get_step_by_id(&conn, &id)
     })
 }
 
@@ -90,37 +120,30 @@ pub fn update_step(
     weight: Option<i32>,
     status: Option<String>,
     priority: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
 ) -> Result<Step, String> {
     log_command!("update_step", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
 
-        let mut stmt = conn
-            .prepare("SELECT id, target_id, title, weight, status, priority, created_at, updated_at FROM steps WHERE id = ?")
-            .map_err(|e| e.to_string())?;
-
-        let step: Step = stmt
-            .query_row([&id], |row| {
-                Ok(Step {
-                    id: row.get(0)?,
-                    target_id: row.get(1)?,
-                    title: row.get(2)?,
-                    weight: row.get(3)?,
-                    status: row.get(4)?,
-                    priority: row.get(5)?,
-                    created_at: row.get(6)?,
-                    updated_at: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+        let step = get_step_by_id(&conn, &id)?;
 
         let new_title = title.unwrap_or(step.title);
-        let new_weight = weight.unwrap_or(step.weight);
-        let new_status = status.unwrap_or(step.status);
+        let manual = target_weight_mode(&conn, &step.target_id)? == "manual";
+        let new_weight = match weight {
+            Some(w) if !manual => w.clamp(0, 100),
+            Some(w) => w,
+            None => step.weight,
+        };
+        let new_status = status.unwrap_or_else(|| step.status.clone());
         let new_priority = priority.unwrap_or(step.priority);
+        let new_estimate = estimate.or(step.estimate);
+        let new_estimate_unit =
+            normalize_estimate(new_estimate, estimate_unit.or(step.estimate_unit))?;
 
-        // Validate weight sum if weight is being changed
-        if let Some(w) = weight {
+        // Validate weight sum if weight is being changed in manual mode
+        if let (Some(w), true) = (weight, manual) {
             let mut check_stmt = conn
                 .prepare(
                     "SELECT COALESCE(SUM(weight), 0) FROM steps WHERE target_id = ? AND id != ?",
@@ -139,22 +162,26 @@ pub fn update_step(
             }
         }
 
-        conn.execute(
-            "UPDATE steps SET title = ?, weight = ?, status = ?, priority = ?, updated_at = ? WHERE id = ?",
-            rusqlite::params![new_title, new_weight, new_status, new_priority, now, id],
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE steps SET title = ?, weight = ?, status = ?, priority = ?, estimate = ?, estimate_unit = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_title, new_weight, new_status, new_priority, new_estimate, new_estimate_unit, now, id],
         )
         .map_err(|e| e.to_string())?;
+        record_status_change(
+            &tx,
+            "step",
+            &id,
+            Some(&step.status),
+            &new_status,
+            step_category(&new_status),
+        )?;
+        if !manual && new_weight != step.weight {
+            rebalance_weights(&tx, &step.target_id, Some(&id))?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
 
-        Ok(Step {
-            id: step.id,
-            target_id: step.target_id,
-            title: new_title,
-            weight: new_weight,
-            status: new_status,
-            priority: new_priority,
-            created_at: step.created_at,
-            updated_at: now,
-        })
+        get_step_by_id(&conn, &id)
     })
 }
 
@@ -162,8 +189,23 @@ pub fn update_step(
 pub fn delete_step(state: tauri::State<AppState>, id: String) -> Result<(), String> {
     log_command!("delete_step", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM steps WHERE id = ?", [&id])
+        let target_id: Option<String> = conn
+            .query_row("SELECT target_id FROM steps WHERE id = ?", [&id], |row| {
+                row.get(0)
+            })
+            .ok();
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM steps WHERE id = ?", [&id])
             .map_err(|e| e.to_string())?;
+        delete_status_history(&tx, "step", &id)?;
+        tx.execute("DELETE FROM step_links WHERE step_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        // The remaining steps take over the weight in the automatic modes
+        if let Some(target_id) = target_id {
+            rebalance_weights(&tx, &target_id, None)?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
         Ok(())
     })
 }
@@ -0,0 +1,495 @@
+// Subtask commands (task hierarchy within a plan)
+
+use super::burndown::{delete_status_history, record_status_change};
+use super::comments::delete_entity_comments;
+use super::dependencies::{delete_entity_dependencies, is_blocked};
+use super::plans::{check_task_dates, refresh_plan_progress};
+use super::pomodoro::delete_entity_pomodoro_sessions;
+use super::step_links::delete_entity_step_links;
+use super::time_tracking::delete_entity_time_entries;
+use super::workflows::{category_status, conform_statuses, task_categories, task_category_sql};
+use crate::log_command;
+use crate::models::Task;
+use crate::AppState;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Maximum nesting, e.g. Epic → Task → Subtask.
+pub const MAX_TASK_DEPTH: usize = 3;
+
+#[derive(Debug, Serialize)]
+pub struct TaskNode {
+    #[serde(flatten)]
+    pub task: Task,
+    pub progress: i32, // Done leaf tasks in this subtree (0-100)
+    pub children: Vec<TaskNode>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn parent_of(conn: &rusqlite::Connection, task_id: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT parent_task_id FROM tasks WHERE id = ?",
+        [task_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn children_of(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM tasks WHERE parent_task_id = ?")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([task_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Level of the task in its tree (1 for a root task).
+fn task_depth(conn: &rusqlite::Connection, task_id: &str) -> Result<usize, String> {
+    let mut depth = 1;
+    let mut current = parent_of(conn, task_id)?;
+    while let Some(parent) = current {
+        depth += 1;
+        if depth > MAX_TASK_DEPTH + 1 {
+            break;
+        }
+        current = parent_of(conn, &parent)?;
+    }
+    Ok(depth)
+}
+
+/// Levels in the subtree below and including the task (1 for a leaf). Tasks
+/// already seen are not walked again, so a cyclic import cannot loop.
+fn subtree_height(conn: &rusqlite::Connection, task_id: &str) -> Result<usize, String> {
+    let mut seen = HashSet::from([task_id.to_string()]);
+    let mut level = vec![task_id.to_string()];
+    let mut height = 0;
+    while !level.is_empty() {
+        height += 1;
+        let mut next = Vec::new();
+        for id in &level {
+            for child in children_of(conn, id)? {
+                if seen.insert(child.clone()) {
+                    next.push(child);
+                }
+            }
+        }
+        level = next;
+    }
+    Ok(height)
+}
+
+/// All tasks below the task, parents before children. Each task is listed
+/// once, and never the task itself.
+pub fn descendant_ids(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>, String> {
+    let mut result = Vec::new();
+    let mut seen = HashSet::from([task_id.to_string()]);
+    let mut queue = vec![task_id.to_string()];
+    while let Some(current) = queue.pop() {
+        for child in children_of(conn, &current)? {
+            if seen.insert(child.clone()) {
+                result.push(child.clone());
+                queue.push(child);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Checks that `task_id` (None for a new task) may go under `parent_id` and
+/// returns the parent's plan.
+pub fn validate_parent(
+    conn: &rusqlite::Connection,
+    task_id: Option<&str>,
+    parent_id: &str,
+) -> Result<String, String> {
+    let plan_id: String = conn
+        .query_row(
+            "SELECT plan_id FROM tasks WHERE id = ?",
+            [parent_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| format!("Parent task {} not found", parent_id))?;
+
+    let height = match task_id {
+        Some(id) => {
+            if id == parent_id || descendant_ids(conn, id)?.iter().any(|d| d == parent_id) {
+                return Err("A task cannot be moved under itself or its subtasks".to_string());
+            }
+            subtree_height(conn, id)?
+        }
+        None => 1,
+    };
+
+    if task_depth(conn, parent_id)? + height > MAX_TASK_DEPTH {
+        return Err(format!(
+            "Subtasks can be nested at most {} levels deep",
+            MAX_TASK_DEPTH
+        ));
+    }
+
+    Ok(plan_id)
+}
+
+/// Checks the stored parent of a task as `validate_parent` does for a move,
+/// and that the parent is in the same plan.
+fn check_task_parent(conn: &rusqlite::Connection, task_id: &str) -> Result<(), String> {
+    let found: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT plan_id, parent_task_id FROM tasks WHERE id = ?",
+            [task_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((plan_id, Some(parent_id))) = found else {
+        return Ok(());
+    };
+    if validate_parent(conn, Some(task_id), &parent_id)? != plan_id {
+        return Err("Parent task belongs to another plan".to_string());
+    }
+    Ok(())
+}
+
+/// Checks the parents of imported tasks, deepest first so a chain that is too
+/// deep loses its lowest link. Invalid parents are cleared; returns the errors.
+pub fn detach_invalid_parents(
+    conn: &rusqlite::Connection,
+    task_ids: &[String],
+) -> Result<Vec<String>, String> {
+    let mut by_depth = Vec::new();
+    for id in task_ids {
+        by_depth.push((task_depth(conn, id).unwrap_or(1), id));
+    }
+    by_depth.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+
+    let mut errors = Vec::new();
+    for (_, id) in by_depth {
+        if let Err(e) = check_task_parent(conn, id) {
+            conn.execute("UPDATE tasks SET parent_task_id = NULL WHERE id = ?", [id])
+                .map_err(|e| e.to_string())?;
+            errors.push(format!("Task {}: {}", id, e));
+        }
+    }
+    Ok(errors)
+}
+
+/// Sets the status category of a task with subtasks from its children, then
+/// repeats for its parent: all done → done, any started or done → doing.
+pub fn rollup_from(conn: &rusqlite::Connection, task_id: &str) -> Result<(), String> {
+    let mut current = Some(task_id.to_string());
+    let mut seen = HashSet::new();
+
+    while let Some(id) = current {
+        if !seen.insert(id.clone()) {
+            break;
+        }
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM tasks WHERE parent_task_id = ?",
+                task_category_sql("tasks")
+            ))
+            .map_err(|e| e.to_string())?;
+        let categories: Vec<String> = stmt
+            .query_map([&id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        if !categories.is_empty() {
+            let category = if categories.iter().all(|c| c == "done") {
+                "done"
+            } else if categories.iter().any(|c| c == "done" || c == "doing") {
+                "doing"
+            } else {
+                "todo"
+            };
+            let (plan_id, current_status, current_category): (String, String, String) = conn
+                .query_row(
+                    &format!(
+                        "SELECT plan_id, status, {} FROM tasks WHERE id = ?",
+                        task_category_sql("tasks")
+                    ),
+                    [&id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .map_err(|e| e.to_string())?;
+            // A parent already in the right category keeps its exact status
+            if current_category != category {
+                let status = category_status(conn, &plan_id, category)?;
+                conn.execute(
+                    "UPDATE tasks SET status = ?, updated_at = ? WHERE id = ?",
+                    rusqlite::params![status, chrono::Utc::now().to_rfc3339(), id],
+                )
+                .map_err(|e| e.to_string())?;
+                record_status_change(conn, "task", &id, Some(&current_status), &status, category)?;
+            }
+        }
+
+        current = parent_of(conn, &id).ok().flatten();
+    }
+
+    Ok(())
+}
+
+/// Rolls up the parents of a task after its status changed.
+pub fn rollup_ancestors(conn: &rusqlite::Connection, task_id: &str) -> Result<(), String> {
+    match parent_of(conn, task_id)? {
+        Some(parent) => rollup_from(conn, &parent),
+        None => Ok(()),
+    }
+}
+
+/// Deletes a task with all its subtasks. Returns false if it did not exist.
+pub fn delete_task_subtree(conn: &rusqlite::Connection, task_id: &str) -> Result<bool, String> {
+    let found: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT plan_id, parent_task_id FROM tasks WHERE id = ?",
+            [task_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((plan_id, parent_id)) = found else {
+        return Ok(false);
+    };
+
+    let mut ids = descendant_ids(conn, task_id)?;
+    ids.push(task_id.to_string());
+    for id in &ids {
+        conn.execute("DELETE FROM tasks WHERE id = ?", [id])
+            .map_err(|e| e.to_string())?;
+        delete_entity_dependencies(conn, "task", id)?;
+        delete_entity_comments(conn, "task", id)?;
+        delete_status_history(conn, "task", id)?;
+        delete_entity_step_links(conn, "task", id)?;
+        delete_entity_time_entries(conn, "task", id)?;
+        delete_entity_pomodoro_sessions(conn, "task", id)?;
+    }
+
+    if let Some(parent_id) = parent_id {
+        rollup_from(conn, &parent_id)?;
+    }
+    refresh_plan_progress(conn, &plan_id)?;
+    Ok(true)
+}
+
+fn build_nodes(
+    parent: Option<&str>,
+    by_parent: &mut HashMap<Option<String>, Vec<Task>>,
+    done: &HashSet<String>,
+) -> Vec<TaskNode> {
+    let tasks = by_parent
+        .remove(&parent.map(String::from))
+        .unwrap_or_default();
+
+    tasks
+        .into_iter()
+        .map(|task| {
+            let children = build_nodes(Some(&task.id), by_parent, done);
+            let (done_leaves, total) = leaf_counts(&task, &children, done);
+            TaskNode {
+                progress: (done_leaves * 100).checked_div(total).unwrap_or(0) as i32,
+                task,
+                children,
+            }
+        })
+        .collect()
+}
+
+fn leaf_counts(task: &Task, children: &[TaskNode], done: &HashSet<String>) -> (usize, usize) {
+    if children.is_empty() {
+        return (done.contains(&task.id) as usize, 1);
+    }
+    children.iter().fold((0, 0), |(done_leaves, total), child| {
+        let (d, t) = leaf_counts(&child.task, &child.children, done);
+        (done_leaves + d, total + t)
+    })
+}
+
+/// Builds the task forest of a plan. Tasks whose parent is missing are roots;
+/// `done` holds the ids of tasks in a done-category status.
+pub fn build_task_tree(tasks: Vec<Task>, done: &HashSet<String>) -> Vec<TaskNode> {
+    let ids: HashSet<String> = tasks.iter().map(|t| t.id.clone()).collect();
+    let mut by_parent: HashMap<Option<String>, Vec<Task>> = HashMap::new();
+    for task in tasks {
+        let parent = task.parent_task_id.clone().filter(|p| ids.contains(p));
+        by_parent.entry(parent).or_default().push(task);
+    }
+    build_nodes(None, &mut by_parent, done)
+}
+
+/// Moves a task and its subtasks under another parent and/or to another plan.
+/// Without a parent the task becomes a root task of `plan_id` (or its own plan).
+pub fn move_task_to(
+    conn: &rusqlite::Connection,
+    id: &str,
+    parent_task_id: Option<String>,
+    plan_id: Option<String>,
+) -> Result<(), String> {
+    let (old_plan_id, old_parent_id): (String, Option<String>) = conn
+        .query_row(
+            "SELECT plan_id, parent_task_id FROM tasks WHERE id = ?",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let new_plan_id = match (&parent_task_id, plan_id) {
+        (Some(parent_id), plan_id) => {
+            let parent_plan_id = validate_parent(conn, Some(id), parent_id)?;
+            if plan_id.is_some_and(|p| p != parent_plan_id) {
+                return Err("Parent task belongs to another plan".to_string());
+            }
+            parent_plan_id
+        }
+        (None, Some(plan_id)) => {
+            let exists: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM plans WHERE id = ?)",
+                    [&plan_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if !exists {
+                return Err(format!("Plan {} not found", plan_id));
+            }
+            plan_id
+        }
+        (None, None) => old_plan_id.clone(),
+    };
+
+    // Statuses must fit the workflow of the new plan
+    let mut subtree = descendant_ids(conn, id)?;
+    subtree.push(id.to_string());
+    if old_plan_id != new_plan_id {
+        for task_id in &subtree {
+            let (start_date, end_date): (Option<String>, Option<String>) = conn
+                .query_row(
+                    "SELECT start_date, end_date FROM tasks WHERE id = ?",
+                    [task_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(|e| e.to_string())?;
+            check_task_dates(
+                conn,
+                &new_plan_id,
+                start_date.as_deref(),
+                end_date.as_deref(),
+            )?;
+        }
+    }
+    let categories: Vec<(String, String)> = task_categories(conn, &old_plan_id)?
+        .into_iter()
+        .filter(|(task_id, _)| subtree.contains(task_id))
+        .collect();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE tasks SET parent_task_id = ?, plan_id = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![parent_task_id, new_plan_id, now, id],
+    )
+    .map_err(|e| e.to_string())?;
+    for descendant in descendant_ids(conn, id)? {
+        conn.execute(
+            "UPDATE tasks SET plan_id = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_plan_id, now, descendant],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if old_plan_id != new_plan_id {
+        conform_statuses(conn, &new_plan_id, &categories)?;
+        // Sprints belong to the old plan
+        for task_id in &subtree {
+            conn.execute("UPDATE tasks SET sprint_id = NULL WHERE id = ?", [task_id])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Some(old_parent_id) = old_parent_id {
+        rollup_from(conn, &old_parent_id)?;
+    }
+    rollup_ancestors(conn, id)?;
+
+    refresh_plan_progress(conn, &new_plan_id)?;
+    if old_plan_id != new_plan_id {
+        refresh_plan_progress(conn, &old_plan_id)?;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_task_tree(
+    state: tauri::State<AppState>,
+    plan_id: String,
+) -> Result<Vec<TaskNode>, String> {
+    log_command!("get_task_tree", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks
+                      WHERE plan_id = ? ORDER BY position, created_at")
+            .map_err(|e| e.to_string())?;
+
+        let mut tasks: Vec<Task> = stmt
+            .query_map([&plan_id], |row| {
+                Ok(Task {
+                    id: row.get(0)?,
+                    plan_id: row.get(1)?,
+                    title: row.get(2)?,
+                    description: row.get(3)?,
+                    start_date: row.get(4)?,
+                    end_date: row.get(5)?,
+                    defer_until: row.get(10)?,
+                    status: row.get(6)?,
+                    priority: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    estimate: row.get(11)?,
+                    estimate_unit: row.get(12)?,
+                    position: row.get(14)?,
+                    sprint_id: row.get(15)?,
+                    parent_task_id: row.get(13)?,
+                    blocked: false,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|t| t.ok())
+            .collect();
+
+        for task in &mut tasks {
+            task.blocked = is_blocked(&conn, "task", &task.id)?;
+        }
+
+        let done: HashSet<String> = task_categories(&conn, &plan_id)?
+            .into_iter()
+            .filter(|(_, category)| category == "done")
+            .map(|(id, _)| id)
+            .collect();
+
+        Ok(build_task_tree(tasks, &done))
+    })
+}
+
+/// Moves a task and its subtasks under another parent and/or to another plan.
+#[tauri::command]
+pub fn move_task(
+    state: tauri::State<AppState>,
+    id: String,
+    parent_task_id: Option<String>,
+    plan_id: Option<String>,
+) -> Result<(), String> {
+    log_command!("move_task", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        move_task_to(&conn, &id, parent_task_id, plan_id)
+    })
+}
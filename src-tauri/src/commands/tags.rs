@@ -20,6 +20,26 @@ pub struct EntityTag {
     pub tag_id: String,
 }
 
+/// Returns the id of the tag with this name, creating it if needed.
+pub fn ensure_tag(conn: &rusqlite::Connection, name: &str) -> Result<String, String> {
+    match conn.query_row("SELECT id FROM tags WHERE name = ?", [name], |row| {
+        row.get(0)
+    }) {
+        Ok(id) => Ok(id),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = chrono::Utc::now().to_rfc3339();
+            conn.execute(
+                "INSERT INTO tags (id, name, color, description, created_at) VALUES (?, ?, '#3B82F6', NULL, ?)",
+                rusqlite::params![id, name, now],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[tauri::command]
 pub fn get_tags(state: tauri::State<AppState>) -> Result<Vec<Tag>, String> {
     log_command!("get_tags", {
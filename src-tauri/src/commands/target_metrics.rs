@@ -0,0 +1,259 @@
+// Target metric commands (numeric targets measured over time)
+//
+// A target with a metric goal is in metric mode: its progress is how far the
+// latest recorded value has moved from the start value towards the goal,
+// instead of the weight of its completed steps.
+
+use super::step_links::refresh_target_progress;
+use super::targets::get_target_by_id;
+use crate::log_command;
+use crate::models::{Target, TargetValue};
+use crate::AppState;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct TargetValueSeries {
+    pub target_id: String,
+    pub unit: Option<String>,
+    pub start_value: f64,
+    pub goal_value: f64,
+    pub direction: String,
+    pub points: Vec<TargetValuePoint>, // By recorded_at
+}
+
+#[derive(Debug, Serialize)]
+pub struct TargetValuePoint {
+    pub id: String,
+    pub recorded_at: String,
+    pub value: f64,
+    pub progress: i32,
+    pub note: Option<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Share of the way from `start` to `goal` covered by `value` (0-100).
+pub fn value_progress(start: f64, goal: f64, value: f64) -> i32 {
+    if goal == start {
+        return if value == goal { 100 } else { 0 };
+    }
+    (((value - start) / (goal - start)) * 100.0).clamp(0.0, 100.0) as i32
+}
+
+fn metric_settings(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+) -> Result<Option<(f64, f64)>, String> {
+    let (start, goal): (Option<f64>, Option<f64>) = conn
+        .query_row(
+            "SELECT metric_start, metric_goal FROM targets WHERE id = ?",
+            [target_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(goal.map(|goal| (start.unwrap_or(0.0), goal)))
+}
+
+pub fn latest_value(conn: &rusqlite::Connection, target_id: &str) -> Result<Option<f64>, String> {
+    let value = conn
+        .query_row(
+            "SELECT value FROM target_values WHERE target_id = ?
+             ORDER BY recorded_at DESC, created_at DESC LIMIT 1",
+            [target_id],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(value)
+}
+
+/// Progress of a target in metric mode, None when it is not.
+pub fn metric_progress(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+) -> Result<Option<i32>, String> {
+    let Some((start, goal)) = metric_settings(conn, target_id)? else {
+        return Ok(None);
+    };
+    let value = latest_value(conn, target_id)?.unwrap_or(start);
+    Ok(Some(value_progress(start, goal, value)))
+}
+
+fn validate_metric(start_value: f64, goal_value: f64, direction: &str) -> Result<(), String> {
+    if !start_value.is_finite() || !goal_value.is_finite() {
+        return Err("Metric values must be finite numbers".to_string());
+    }
+    match direction {
+        "increase" if goal_value > start_value => Ok(()),
+        "decrease" if goal_value < start_value => Ok(()),
+        "increase" => Err("An increasing goal must be above the start value".to_string()),
+        "decrease" => Err("A decreasing goal must be below the start value".to_string()),
+        _ => Err("direction must be 'increase' or 'decrease'".to_string()),
+    }
+}
+
+pub fn get_value_series(
+    conn: &rusqlite::Connection,
+    target_id: &str,
+) -> Result<TargetValueSeries, String> {
+    let target = get_target_by_id(conn, target_id)?;
+    let Some(goal) = target.metric_goal else {
+        return Err("Target has no metric".to_string());
+    };
+    let start = target.metric_start.unwrap_or(0.0);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, recorded_at, value, note FROM target_values WHERE target_id = ?
+             ORDER BY recorded_at, created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let points = stmt
+        .query_map([target_id], |row| {
+            let value: f64 = row.get(2)?;
+            Ok(TargetValuePoint {
+                id: row.get(0)?,
+                recorded_at: row.get(1)?,
+                value,
+                progress: value_progress(start, goal, value),
+                note: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(TargetValueSeries {
+        target_id: target.id,
+        unit: target.metric_unit,
+        start_value: start,
+        goal_value: goal,
+        direction: target
+            .metric_direction
+            .unwrap_or_else(|| "increase".to_string()),
+        points,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn set_target_metric(
+    state: tauri::State<AppState>,
+    target_id: String,
+    unit: Option<String>,
+    start_value: f64,
+    goal_value: f64,
+    direction: String,
+) -> Result<Target, String> {
+    log_command!("set_target_metric", {
+        validate_metric(start_value, goal_value, &direction)?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let rows = conn
+            .execute(
+                "UPDATE targets SET metric_unit = ?, metric_start = ?, metric_goal = ?, metric_direction = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![unit, start_value, goal_value, direction, chrono::Utc::now().to_rfc3339(), target_id],
+            )
+            .map_err(|e| e.to_string())?;
+        if rows == 0 {
+            return Err("Target not found".to_string());
+        }
+        refresh_target_progress(&conn, &target_id)?;
+
+        get_target_by_id(&conn, &target_id)
+    })
+}
+
+/// Switches a target back to step-based progress. Recorded values are kept.
+#[tauri::command]
+pub fn clear_target_metric(
+    state: tauri::State<AppState>,
+    target_id: String,
+) -> Result<Target, String> {
+    log_command!("clear_target_metric", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        conn.execute(
+            "UPDATE targets SET metric_unit = NULL, metric_start = NULL, metric_goal = NULL, metric_direction = NULL, updated_at = ? WHERE id = ?",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), target_id],
+        )
+        .map_err(|e| e.to_string())?;
+        refresh_target_progress(&conn, &target_id)?;
+
+        get_target_by_id(&conn, &target_id)
+    })
+}
+
+#[tauri::command]
+pub fn record_target_value(
+    state: tauri::State<AppState>,
+    target_id: String,
+    value: f64,
+    note: Option<String>,
+    recorded_at: Option<String>,
+) -> Result<TargetValue, String> {
+    log_command!("record_target_value", {
+        if !value.is_finite() {
+            return Err("Value must be a finite number".to_string());
+        }
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        if metric_settings(&conn, &target_id)?.is_none() {
+            return Err("Target has no metric".to_string());
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let recorded_at = recorded_at.unwrap_or_else(|| now.clone());
+
+        conn.execute(
+            "INSERT INTO target_values (id, target_id, value, note, recorded_at, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, target_id, value, note, recorded_at, now],
+        )
+        .map_err(|e| e.to_string())?;
+        refresh_target_progress(&conn, &target_id)?;
+
+        Ok(TargetValue {
+            id,
+            target_id,
+            value,
+            note,
+            recorded_at,
+            created_at: now,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn get_target_values(
+    state: tauri::State<AppState>,
+    target_id: String,
+) -> Result<TargetValueSeries, String> {
+    log_command!("get_target_values", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        get_value_series(&conn, &target_id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_target_value(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_target_value", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let target_id: Option<String> = conn
+            .query_row(
+                "SELECT target_id FROM target_values WHERE id = ?",
+                [&id],
+                |row| row.get(0),
+            )
+            .ok();
+        conn.execute("DELETE FROM target_values WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        if let Some(target_id) = target_id {
+            refresh_target_progress(&conn, &target_id)?;
+        }
+        Ok(())
+    })
+}
@@ -1,67 +1,116 @@
 // Target CRUD commands
 
+use super::comments::delete_entity_comments;
+use super::step_links::step_link_progress;
+use super::target_metrics::{latest_value, metric_progress};
 use crate::log_command;
 use crate::models::Target;
 use crate::AppState;
 
+const TARGET_COLUMNS: &str = "id, title, description, due_date, status, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction, weight_mode";
+
+fn row_to_target(row: &rusqlite::Row) -> rusqlite::Result<Target> {
+    Ok(Target {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        due_date: row.get(3)?,
+        status: row.get(4)?,
+        progress: 0, // Will be calculated
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        metric_unit: row.get(7)?,
+        metric_start: row.get(8)?,
+        metric_goal: row.get(9)?,
+        metric_direction: row.get(10)?,
+        weight_mode: row.get(11)?,
+        metric_value: None,
+    })
+}
+
+/// Fills in the calculated progress and latest metric value.
+fn with_progress(conn: &rusqlite::Connection, target: Target) -> Result<Target, String> {
+    let progress = calculate_target_progress(conn, &target.id)?;
+    let metric_value = match target.metric_goal {
+        Some(_) => latest_value(conn, &target.id)?,
+        None => None,
+    };
+    Ok(Target {
+        progress,
+        metric_value,
+        ..target
+    })
+}
+
+pub fn get_target_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Target, String> {
+    let target = conn
+        .query_row(
+            &format!("SELECT {} FROM targets WHERE id = ?", TARGET_COLUMNS),
+            [id],
+            row_to_target,
+        )
+        .map_err(|e| e.to_string())?;
+    with_progress(conn, target)
+}
+
+/// Progress from the latest metric value in metric mode, else from step weights.
 pub fn calculate_target_progress(
     conn: &rusqlite::Connection,
     target_id: &str,
 ) -> Result<i32, String> {
+    if let Some(progress) = metric_progress(conn, target_id)? {
+        return Ok(progress);
+    }
+
     let mut stmt = conn
-        .prepare("SELECT weight, status FROM steps WHERE target_id = ?")
+        .prepare("SELECT id, weight, status FROM steps WHERE target_id = ?")
         .map_err(|e| e.to_string())?;
 
     let step_iter = stmt
         .query_map([target_id], |row| {
-            Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?))
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, String>(2)?,
+            ))
         })
         .map_err(|e| e.to_string())?;
 
-    let mut total_weight = 0;
-    let mut completed_weight = 0;
+    let steps: Vec<(String, i32, String)> = step_iter.filter_map(|s| s.ok()).collect();
+    if steps.is_empty() {
+        return Ok(0);
+    }
 
-    for step in step_iter.filter_map(|s| s.ok()) {
-        total_weight += step.0;
-        if step.1 == "completed" {
-            completed_weight += step.0;
-        }
+    // Weights need not sum to 100: progress is the completed share of the
+    // total. Steps without any weight count equally, and open steps linked to
+    // work count with the progress of that work.
+    let weighted = steps.iter().any(|(_, weight, _)| *weight > 0);
+    let mut total_weight = 0;
+    let mut completed_weight = 0; // In hundredths of a weight unit
+
+    for (id, weight, status) in &steps {
+        let weight = if weighted { (*weight).max(0) } else { 1 };
+        total_weight += weight;
+        let done = if status == "completed" {
+            100
+        } else {
+            step_link_progress(conn, id)?.unwrap_or(0)
+        };
+        completed_weight += weight * done;
     }
 
     if total_weight == 0 {
         return Ok(0);
     }
 
-    Ok((completed_weight * 100) / total_weight)
+    Ok(completed_weight / total_weight)
 }
 
 #[tauri::command]
 pub fn get_target(state: tauri::State<AppState>, id: String) -> Result<Target, String> {
     log_command!("get_target", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
-
-        let mut stmt = conn
-            .prepare("SELECT id, title, description, due_date, status, created_at, updated_at FROM targets WHERE id = ?")
-            .map_err(|e| e.to_string())?;
-
-        let target: Target = stmt
-            .query_row([&id], |row| {
-                Ok(Target {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    due_date: row.get(3)?,
-                    status: row.get(4)?,
-                    progress: 0,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-
-        // Calculate progress
-        let progress = calculate_target_progress(&conn, &id)?;
-        Ok(Target { progress, ..target })
+        get_target_by_id(&conn, &id)
     })
 }
 
@@ -71,34 +120,20 @@ pub fn get_targets(state: tauri::State<AppState>) -> Result<Vec<Target>, String>
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
-            .prepare(
-                "SELECT id, title, description, due_date, status, created_at, updated_at FROM targets",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let target_iter = stmt
-            .query_map([], |row| {
-                Ok(Target {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    due_date: row.get(3)?,
-                    status: row.get(4)?,
-                    progress: 0, // Will be calculated
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })
+            .prepare(&format!("SELECT {} FROM targets", TARGET_COLUMNS))
             .map_err(|e| e.to_string())?;
 
-        let mut targets: Vec<Target> = target_iter.filter_map(|t| t.ok()).collect();
+        let targets: Vec<Target> = stmt
+            .query_map([], row_to_target)
+            .map_err(|e| e.to_string())?
+            .filter_map(|t| t.ok())
+            .collect();
 
         // Calculate progress for each target
-        for target in &mut targets {
-            target.progress = calculate_target_progress(&conn, &target.id)?;
-        }
-
-        Ok(targets)
+        targets
+            .into_iter()
+            .map(|target| with_progress(&conn, target))
+            .collect()
     })
 }
 
@@ -120,16 +155,7 @@ pub fn create_target(
             rusqlite::params![id, title, description, due_date, now, now],
         ).map_err(|e| e.to_string())?;
 
-        Ok(Target {
-            id,
-            title,
-            description,
-            due_date,
-            status: "active".to_string(),
-            progress: 0,
-            created_at: now.clone(),
-            updated_at: now,
-        })
+        get_target_by_id(&conn, &id)
     })
 }
 
@@ -145,25 +171,7 @@ pub fn update_target(
     log_command!("update_target", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
-
-        let mut stmt = conn
-            .prepare("SELECT id, title, description, due_date, status, created_at, updated_at FROM targets WHERE id = ?")
-            .map_err(|e| e.to_string())?;
-
-        let target: Target = stmt
-            .query_row([&id], |row| {
-                Ok(Target {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    due_date: row.get(3)?,
-                    status: row.get(4)?,
-                    progress: 0,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+        let target = get_target_by_id(&conn, &id)?;
 
         let new_title = title.unwrap_or(target.title);
         let new_description = description.or(target.description);
@@ -175,18 +183,7 @@ pub fn update_target(
             rusqlite::params![new_title, new_description, new_due_date, new_status, now, id],
         ).map_err(|e| e.to_string())?;
 
-        let progress = calculate_target_progress(&conn, &id)?;
-
-        Ok(Target {
-            id: target.id,
-            title: new_title,
-            description: new_description,
-            due_date: new_due_date,
-            status: new_status,
-            progress,
-            created_at: target.created_at,
-            updated_at: now,
-        })
+        get_target_by_id(&conn, &id)
     })
 }
 
@@ -194,9 +191,23 @@ pub fn update_target(
 pub fn delete_target(state: tauri::State<AppState>, id: String) -> Result<(), String> {
     log_command!("delete_target", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM status_history
+             WHERE entity_type = 'step' AND entity_id IN (SELECT id FROM steps WHERE target_id = ?)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM step_links WHERE step_id IN (SELECT id FROM steps WHERE target_id = ?)",
+            [&id],
+        )
+        .map_err(|e| e.to_string())?;
         // Steps will be cascade deleted
         conn.execute("DELETE FROM targets WHERE id = ?", [&id])
             .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM target_values WHERE target_id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        delete_entity_comments(&conn, "target", &id)?;
         Ok(())
     })
 }
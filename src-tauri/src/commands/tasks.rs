@@ -1,5 +1,12 @@
 // Task CRUD commands
 
+use super::burndown::record_status_change;
+use super::dependencies::{check_can_start, is_blocked, notify_unblocked};
+use super::estimates::normalize_estimate;
+use super::ordering::next_position;
+use super::plans::{check_task_dates, refresh_plan_progress};
+use super::subtasks::{delete_task_subtree, rollup_ancestors, validate_parent};
+use super::workflows::{default_status, initial_status, status_category, validate_transition};
 use crate::log_command;
 use crate::models::Task;
 use crate::AppState;
@@ -10,38 +17,54 @@ pub fn get_task(state: tauri::State<AppState>, id: String) -> Result<Task, Strin
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
-            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at FROM tasks WHERE id = ?")
+            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks WHERE id = ?")
             .map_err(|e| e.to_string())?;
 
-        stmt.query_row([&id], |row| {
-            Ok(Task {
-                id: row.get(0)?,
-                plan_id: row.get(1)?,
-                title: row.get(2)?,
-                description: row.get(3)?,
-                start_date: row.get(4)?,
-                end_date: row.get(5)?,
-                status: row.get(6)?,
-                priority: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
+        let task: Task = stmt
+            .query_row([&id], |row| {
+                Ok(Task {
+                    id: row.get(0)?,
+                    plan_id: row.get(1)?,
+                    title: row.get(2)?,
+                    description: row.get(3)?,
+                    start_date: row.get(4)?,
+                    end_date: row.get(5)?,
+                    defer_until: row.get(10)?,
+                    status: row.get(6)?,
+                    priority: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    estimate: row.get(11)?,
+                    estimate_unit: row.get(12)?,
+                    position: row.get(14)?,
+                    sprint_id: row.get(15)?,
+                    parent_task_id: row.get(13)?,
+                    blocked: false,
+                })
             })
-        })
-        .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+
+        let blocked = is_blocked(&conn, "task", &id)?;
+        Ok(Task { blocked, ..task })
     })
 }
 
 #[tauri::command]
-pub fn get_tasks(state: tauri::State<AppState>) -> Result<Vec<Task>, String> {
+pub fn get_tasks(
+    state: tauri::State<AppState>,
+    include_deferred: Option<bool>,
+) -> Result<Vec<Task>, String> {
     log_command!("get_tasks", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
+        // Snoozed tasks are hidden until their defer_until date unless requested
         let mut stmt = conn
-            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at FROM tasks")
+            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks
+                      WHERE ?1 OR defer_until IS NULL OR datetime(defer_until) <= datetime('now')")
             .map_err(|e| e.to_string())?;
 
         let task_iter = stmt
-            .query_map([], |row| {
+            .query_map([include_deferred.unwrap_or(false)], |row| {
                 Ok(Task {
                     id: row.get(0)?,
                     plan_id: row.get(1)?,
@@ -49,15 +72,29 @@ pub fn get_tasks(state: tauri::State<AppState>) -> Result<Vec<Task>, String> {
                     description: row.get(3)?,
                     start_date: row.get(4)?,
                     end_date: row.get(5)?,
+                    defer_until: row.get(10)?,
                     status: row.get(6)?,
                     priority: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    estimate: row.get(11)?,
+                    estimate_unit: row.get(12)?,
+                    position: row.get(14)?,
+                    sprint_id: row.get(15)?,
+                    parent_task_id: row.get(13)?,
+                    blocked: false,
                 })
             })
             .map_err(|e| e.to_string())?;
 
-        Ok(task_iter.filter_map(|t| t.ok()).collect())
+        let mut tasks: Vec<Task> = task_iter.filter_map(|t| t.ok()).collect();
+
+        // Flag tasks with unfinished blockers
+        for task in &mut tasks {
+            task.blocked = is_blocked(&conn, "task", &task.id)?;
+        }
+
+        Ok(tasks)
     })
 }
 
@@ -65,32 +102,52 @@ pub fn get_tasks(state: tauri::State<AppState>) -> Result<Vec<Task>, String> {
 pub fn get_tasks_by_plan(
     state: tauri::State<AppState>,
     plan_id: String,
+    include_deferred: Option<bool>,
 ) -> Result<Vec<Task>, String> {
     log_command!("get_tasks_by_plan", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
-            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at FROM tasks WHERE plan_id = ?")
+            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks
+                      WHERE plan_id = ?1 AND (?2 OR defer_until IS NULL OR datetime(defer_until) <= datetime('now'))
+                      ORDER BY position, created_at")
             .map_err(|e| e.to_string())?;
 
         let task_iter = stmt
-            .query_map([&plan_id], |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    plan_id: row.get(1)?,
-                    title: row.get(2)?,
-                    description: row.get(3)?,
-                    start_date: row.get(4)?,
-                    end_date: row.get(5)?,
-                    status: row.get(6)?,
-                    priority: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            })
+            .query_map(
+                rusqlite::params![plan_id, include_deferred.unwrap_or(false)],
+                |row| {
+                    Ok(Task {
+                        id: row.get(0)?,
+                        plan_id: row.get(1)?,
+                        title: row.get(2)?,
+                        description: row.get(3)?,
+                        start_date: row.get(4)?,
+                        end_date: row.get(5)?,
+                        defer_until: row.get(10)?,
+                        status: row.get(6)?,
+                        priority: row.get(7)?,
+                        created_at: row.get(8)?,
+                        updated_at: row.get(9)?,
+                        estimate: row.get(11)?,
+                        estimate_unit: row.get(12)?,
+                        position: row.get(14)?,
+                        sprint_id: row.get(15)?,
+                        parent_task_id: row.get(13)?,
+                        blocked: false,
+                    })
+                },
+            )
             .map_err(|e| e.to_string())?;
 
-        Ok(task_iter.filter_map(|t| t.ok()).collect())
+        let mut tasks: Vec<Task> = task_iter.filter_map(|t| t.ok()).collect();
+
+        // Flag tasks with unfinished blockers
+        for task in &mut tasks {
+            task.blocked = is_blocked(&conn, "task", &task.id)?;
+        }
+
+        Ok(tasks)
     })
 }
 
@@ -103,36 +160,60 @@ pub fn create_task(
     start_date: Option<String>,
     end_date: Option<String>,
     priority: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
+    parent_task_id: Option<String>,
 ) -> Result<Task, String> {
     log_command!("create_task", {
+        let estimate_unit = normalize_estimate(estimate, estimate_unit)?;
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
+        check_task_dates(&conn, &plan_id, start_date.as_deref(), end_date.as_deref())?;
+        if let Some(parent_id) = &parent_task_id {
+            if validate_parent(&conn, None, parent_id)? != plan_id {
+                return Err("Parent task belongs to another plan".to_string());
+            }
+        }
+
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
         let priority = priority.unwrap_or_else(|| "P2".to_string());
+        let status = initial_status(&conn, &plan_id)?;
+        let position = next_position(&conn, "task", Some(&plan_id))?;
 
         conn.execute(
-            "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, 'pending', ?, ?, ?)",
-            rusqlite::params![id, plan_id, title, description, start_date, end_date, priority, now, now],
+            "INSERT INTO tasks (id, plan_id, title, description, start_date, end_date, status, priority, estimate, estimate_unit, parent_task_id, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, plan_id, title, description, start_date, end_date, status, priority, estimate, estimate_unit, parent_task_id, position, now, now],
         ).map_err(|e| e.to_string())?;
 
+        rollup_ancestors(&conn, &id)?;
+        refresh_plan_progress(&conn, &plan_id)?;
+
         Ok(Task {
             id,
             plan_id,
+            parent_task_id,
             title,
             description,
             start_date,
             end_date,
-            status: "pending".to_string(),
+            defer_until: None,
+            status,
             priority,
             created_at: now.clone(),
             updated_at: now,
+            estimate,
+            estimate_unit,
+            position: Some(position),
+            sprint_id: None,
+            blocked: false,
         })
     })
 }
 
 #[tauri::command]
 pub fn update_task(
+    app: tauri::AppHandle,
     state: tauri::State<AppState>,
     id: String,
     title: Option<String>,
@@ -141,13 +222,16 @@ pub fn update_task(
     end_date: Option<String>,
     status: Option<String>,
     priority: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
+    force: Option<bool>,
 ) -> Result<Task, String> {
     log_command!("update_task", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
 
         let mut stmt = conn
-            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at FROM tasks WHERE id = ?")
+            .prepare("SELECT id, plan_id, title, description, start_date, end_date, status, priority, created_at, updated_at, defer_until, estimate, estimate_unit, parent_task_id, position, sprint_id FROM tasks WHERE id = ?")
             .map_err(|e| e.to_string())?;
 
         let task: Task = stmt
@@ -159,37 +243,97 @@ pub fn update_task(
                     description: row.get(3)?,
                     start_date: row.get(4)?,
                     end_date: row.get(5)?,
+                    defer_until: row.get(10)?,
                     status: row.get(6)?,
                     priority: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    estimate: row.get(11)?,
+                    estimate_unit: row.get(12)?,
+                    position: row.get(14)?,
+                    sprint_id: row.get(15)?,
+                    parent_task_id: row.get(13)?,
+                    blocked: false,
                 })
             })
             .map_err(|e| e.to_string())?;
 
         let new_title = title.unwrap_or(task.title);
         let new_description = description.or(task.description);
+        let dates_changed = start_date.is_some() || end_date.is_some();
         let new_start_date = start_date.or(task.start_date);
         let new_end_date = end_date.or(task.end_date);
-        let new_status = status.unwrap_or(task.status);
+        if dates_changed {
+            check_task_dates(
+                &conn,
+                &task.plan_id,
+                new_start_date.as_deref(),
+                new_end_date.as_deref(),
+            )?;
+        }
+        let old_category = status_category(&conn, &task.plan_id, &task.status)?;
+        let status_changed = status.as_ref().is_some_and(|s| *s != task.status);
+        let new_status = status.unwrap_or_else(|| task.status.clone());
         let new_priority = priority.unwrap_or(task.priority);
+        let new_estimate = estimate.or(task.estimate);
+        let new_estimate_unit =
+            normalize_estimate(new_estimate, estimate_unit.or(task.estimate_unit))?;
+
+        let new_category = status_category(&conn, &task.plan_id, &new_status)?;
+
+        if status_changed {
+            validate_transition(&conn, &task.plan_id, &task.status, &new_status)?;
+            // Blocking rules work on the category of custom statuses
+            check_can_start(
+                &conn,
+                "task",
+                &id,
+                default_status(&new_category),
+                force.unwrap_or(false),
+            )?;
+        }
 
         conn.execute(
-            "UPDATE tasks SET title = ?, description = ?, start_date = ?, end_date = ?, status = ?, priority = ?, updated_at = ? WHERE id = ?",
-            rusqlite::params![new_title, new_description, new_start_date, new_end_date, new_status, new_priority, now, id],
+            "UPDATE tasks SET title = ?, description = ?, start_date = ?, end_date = ?, status = ?, priority = ?, estimate = ?, estimate_unit = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_title, new_description, new_start_date, new_end_date, new_status, new_priority, new_estimate, new_estimate_unit, now, id],
         ).map_err(|e| e.to_string())?;
 
+        if new_category == "done" && old_category != "done" {
+            notify_unblocked(&app, &conn, "task", &id)?;
+        }
+        if status_changed {
+            record_status_change(
+                &conn,
+                "task",
+                &id,
+                Some(&task.status),
+                &new_status,
+                &new_category,
+            )?;
+            rollup_ancestors(&conn, &id)?;
+        }
+        refresh_plan_progress(&conn, &task.plan_id)?;
+
+        let blocked = is_blocked(&conn, "task", &id)?;
+
         Ok(Task {
             id: task.id,
             plan_id: task.plan_id,
+            parent_task_id: task.parent_task_id,
             title: new_title,
             description: new_description,
             start_date: new_start_date,
             end_date: new_end_date,
+            defer_until: task.defer_until,
             status: new_status,
             priority: new_priority,
             created_at: task.created_at,
             updated_at: now,
+            estimate: new_estimate,
+            estimate_unit: new_estimate_unit,
+            position: task.position,
+            sprint_id: task.sprint_id,
+            blocked,
         })
     })
 }
@@ -198,8 +342,8 @@ pub fn update_task(
 pub fn delete_task(state: tauri::State<AppState>, id: String) -> Result<(), String> {
     log_command!("delete_task", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM tasks WHERE id = ?", [&id])
-            .map_err(|e| e.to_string())?;
+        // Subtasks go with their parent
+        delete_task_subtree(&conn, &id)?;
         Ok(())
     })
 }
@@ -0,0 +1,388 @@
+// Time tracking commands (timers and time entries on todos and tasks)
+
+use super::comments::entity_table;
+use crate::log_command;
+use crate::models::TimeEntry;
+use crate::AppState;
+use serde::Serialize;
+
+/// SQL expression for the tracked seconds of a `time_entries` row, including
+/// the current segment of a running timer.
+pub const ENTRY_SECONDS_SQL: &str = "(duration_seconds + CASE WHEN running_since IS NOT NULL
+    THEN MAX(0, CAST(strftime('%s', 'now') AS INTEGER) - CAST(strftime('%s', running_since) AS INTEGER))
+    ELSE 0 END)";
+
+const ENTRY_COLUMNS: &str = "id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at";
+
+#[derive(Debug, Serialize)]
+pub struct TimeTotal {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub total_seconds: i64,
+    pub entry_count: i32,
+    pub running: bool,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Table of an entity that time can be tracked on (todos and tasks).
+pub fn validate_entity_type(entity_type: &str) -> Result<&'static str, String> {
+    match entity_type {
+        "todo" | "task" => entity_table(entity_type),
+        _ => Err("Invalid entity_type. Use 'todo' or 'task'".to_string()),
+    }
+}
+
+/// Removes all time entries of the entity, including a running timer.
+pub fn delete_entity_time_entries(
+    conn: &rusqlite::Connection,
+    entity_type: &str,
+    entity_id: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM time_entries WHERE entity_type = ? AND entity_id = ?",
+        rusqlite::params![entity_type, entity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        entity_id: row.get(2)?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        running_since: row.get(5)?,
+        duration_seconds: row.get(6)?,
+        status: row.get(7)?,
+        note: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn get_entry_by_id(conn: &rusqlite::Connection, id: &str) -> Result<TimeEntry, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM time_entries WHERE id = ?", ENTRY_COLUMNS),
+        [id],
+        row_to_entry,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the running or paused timer, if any.
+pub fn active_timer(conn: &rusqlite::Connection) -> Result<Option<TimeEntry>, String> {
+    match conn.query_row(
+        &format!(
+            "SELECT {} FROM time_entries WHERE status IN ('running', 'paused') LIMIT 1",
+            ENTRY_COLUMNS
+        ),
+        [],
+        row_to_entry,
+    ) {
+        Ok(entry) => Ok(Some(entry)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn seconds_between(from: &str, to: &str) -> Result<i64, String> {
+    let from = chrono::DateTime::parse_from_rfc3339(from).map_err(|e| e.to_string())?;
+    let to = chrono::DateTime::parse_from_rfc3339(to).map_err(|e| e.to_string())?;
+    Ok((to - from).num_seconds().max(0))
+}
+
+/// Stops the given timer, folding the current running segment into its duration.
+pub fn stop_entry(conn: &rusqlite::Connection, entry: &TimeEntry) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let extra = match &entry.running_since {
+        Some(since) => seconds_between(since, &now)?,
+        None => 0,
+    };
+
+    conn.execute(
+        "UPDATE time_entries SET status = 'stopped', ended_at = ?, running_since = NULL, duration_seconds = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![now, entry.duration_seconds + extra, now, entry.id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============================================================================
+// Timer Commands
+// ============================================================================
+
+/// Starts a timer on the entity. Any other active timer is stopped first, so
+/// at most one timer runs at a time.
+#[tauri::command]
+pub fn start_timer(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+    note: Option<String>,
+) -> Result<TimeEntry, String> {
+    log_command!("start_timer", {
+        let table = validate_entity_type(&entity_type)?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let exists: bool = conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+                [&entity_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        if let Some(active) = active_timer(&conn)? {
+            stop_entry(&conn, &active)?;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at)
+             VALUES (?, ?, ?, ?, NULL, ?, 0, 'running', ?, ?, ?)",
+            rusqlite::params![id, entity_type, entity_id, now, now, note, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_entry_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn pause_timer(state: tauri::State<AppState>) -> Result<TimeEntry, String> {
+    log_command!("pause_timer", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let entry = active_timer(&conn)?
+            .filter(|e| e.status == "running")
+            .ok_or_else(|| "No running timer".to_string())?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let extra = match &entry.running_since {
+            Some(since) => seconds_between(since, &now)?,
+            None => 0,
+        };
+
+        conn.execute(
+            "UPDATE time_entries SET status = 'paused', running_since = NULL, duration_seconds = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![entry.duration_seconds + extra, now, entry.id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_entry_by_id(&conn, &entry.id)
+    })
+}
+
+#[tauri::command]
+pub fn resume_timer(state: tauri::State<AppState>) -> Result<TimeEntry, String> {
+    log_command!("resume_timer", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let entry = active_timer(&conn)?
+            .filter(|e| e.status == "paused")
+            .ok_or_else(|| "No paused timer".to_string())?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE time_entries SET status = 'running', running_since = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![now, now, entry.id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_entry_by_id(&conn, &entry.id)
+    })
+}
+
+#[tauri::command]
+pub fn stop_timer(state: tauri::State<AppState>) -> Result<TimeEntry, String> {
+    log_command!("stop_timer", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let entry = active_timer(&conn)?.ok_or_else(|| "No active timer".to_string())?;
+        stop_entry(&conn, &entry)?;
+
+        get_entry_by_id(&conn, &entry.id)
+    })
+}
+
+#[tauri::command]
+pub fn get_active_timer(state: tauri::State<AppState>) -> Result<Option<TimeEntry>, String> {
+    log_command!("get_active_timer", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        active_timer(&conn)
+    })
+}
+
+// ============================================================================
+// Time Entry Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_time_entries(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<TimeEntry>, String> {
+    log_command!("get_time_entries", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM time_entries WHERE entity_type = ? AND entity_id = ? ORDER BY started_at DESC",
+                ENTRY_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let entries = stmt
+            .query_map(rusqlite::params![entity_type, entity_id], row_to_entry)
+            .map_err(|e| e.to_string())?;
+
+        Ok(entries.filter_map(|e| e.ok()).collect())
+    })
+}
+
+#[tauri::command]
+pub fn create_time_entry(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+    started_at: String,
+    ended_at: String,
+    note: Option<String>,
+) -> Result<TimeEntry, String> {
+    log_command!("create_time_entry", {
+        let table = validate_entity_type(&entity_type)?;
+        let duration = seconds_between(&started_at, &ended_at)?;
+        if duration == 0 {
+            return Err("ended_at must be after started_at".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let exists: bool = conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+                [&entity_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("{} {} not found", entity_type, entity_id));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, note, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, NULL, ?, 'stopped', ?, ?, ?)",
+            rusqlite::params![id, entity_type, entity_id, started_at, ended_at, duration, note, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_entry_by_id(&conn, &id)
+    })
+}
+
+/// Edits a stopped entry. Changing the start or end recomputes the duration
+/// unless `duration_seconds` is given explicitly.
+#[tauri::command]
+pub fn update_time_entry(
+    state: tauri::State<AppState>,
+    id: String,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+    duration_seconds: Option<i64>,
+    note: Option<String>,
+) -> Result<TimeEntry, String> {
+    log_command!("update_time_entry", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let entry = get_entry_by_id(&conn, &id)?;
+
+        if entry.status != "stopped" {
+            return Err("Stop the timer before editing this entry".to_string());
+        }
+
+        let times_changed = started_at.is_some() || ended_at.is_some();
+        let new_started_at = started_at.unwrap_or(entry.started_at);
+        let new_ended_at = ended_at.or(entry.ended_at);
+        let new_note = note.or(entry.note);
+
+        let new_duration = match (duration_seconds, &new_ended_at) {
+            (Some(d), _) if d < 0 => {
+                return Err("duration_seconds must not be negative".to_string())
+            }
+            (Some(d), _) => d,
+            (None, Some(end)) if times_changed => seconds_between(&new_started_at, end)?,
+            _ => entry.duration_seconds,
+        };
+
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE time_entries SET started_at = ?, ended_at = ?, duration_seconds = ?, note = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_started_at, new_ended_at, new_duration, new_note, now, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        get_entry_by_id(&conn, &id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_time_entry(state: tauri::State<AppState>, id: String) -> Result<(), String> {
+    log_command!("delete_time_entry", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM time_entries WHERE id = ?", [&id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Total tracked time of a todo or task, or of all tasks in a plan
+/// (entity_type = 'plan').
+#[tauri::command]
+pub fn get_time_total(
+    state: tauri::State<AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<TimeTotal, String> {
+    log_command!("get_time_total", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let filter = match entity_type.as_str() {
+            "todo" | "task" => "entity_type = ?1 AND entity_id = ?2",
+            "plan" => "?1 = 'plan' AND entity_type = 'task' AND entity_id IN (SELECT id FROM tasks WHERE plan_id = ?2)",
+            _ => return Err("Invalid entity_type. Use 'todo', 'task' or 'plan'".to_string()),
+        };
+
+        let (total_seconds, entry_count, running): (i64, i32, bool) = conn
+            .query_row(
+                &format!(
+                    "SELECT COALESCE(SUM({}), 0), COUNT(*), COALESCE(MAX(status = 'running'), 0)
+                     FROM time_entries WHERE {}",
+                    ENTRY_SECONDS_SQL, filter
+                ),
+                rusqlite::params![entity_type, entity_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        Ok(TimeTotal {
+            entity_type,
+            entity_id,
+            total_seconds,
+            entry_count,
+            running,
+        })
+    })
+}
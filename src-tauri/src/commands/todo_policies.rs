@@ -0,0 +1,193 @@
+// Todo policy commands (auto-archive and rollover)
+
+use crate::log_command;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodoPolicySettings {
+    pub id: String,
+    pub auto_archive_enabled: bool,
+    pub archive_after_days: i32,
+    pub rollover_enabled: bool,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TodoPolicyRunResult {
+    pub archived: usize,
+    pub rolled_over: usize,
+    pub ran_at: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn load_settings(conn: &rusqlite::Connection) -> Result<TodoPolicySettings, String> {
+    conn.query_row(
+        "SELECT id, auto_archive_enabled, archive_after_days, rollover_enabled, last_run_at, created_at, updated_at
+         FROM todo_policy_settings LIMIT 1",
+        [],
+        |row| {
+            Ok(TodoPolicySettings {
+                id: row.get(0)?,
+                auto_archive_enabled: row.get::<_, i32>(1)? != 0,
+                archive_after_days: row.get(2)?,
+                rollover_enabled: row.get::<_, i32>(3)? != 0,
+                last_run_at: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Archives old completed todos and rolls overdue ones forward, according to
+/// the stored settings.
+pub fn apply_todo_policies(conn: &rusqlite::Connection) -> Result<TodoPolicyRunResult, String> {
+    let settings = load_settings(conn)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    // updated_at is left untouched so completion statistics keep their dates
+    let archived = if settings.auto_archive_enabled {
+        conn.execute(
+            "UPDATE todos SET status = 'archived'
+             WHERE status = 'done' AND datetime(updated_at) <= datetime('now', ?)",
+            [format!("-{} days", settings.archive_after_days.max(0))],
+        )
+        .map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+
+    // Keep the time part of due dates that have one
+    let rolled_over = if settings.rollover_enabled {
+        conn.execute(
+            "UPDATE todos SET
+                due_date = CASE WHEN length(due_date) > 10 THEN ?1 || substr(due_date, 11) ELSE ?1 END,
+                rollover_count = rollover_count + 1,
+                updated_at = ?2
+             WHERE status NOT IN ('done', 'archived')
+             AND due_date IS NOT NULL
+             AND date(due_date) < date(?1)",
+            rusqlite::params![today, now],
+        )
+        .map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+
+    conn.execute(
+        "UPDATE todo_policy_settings SET last_run_at = ? WHERE id = ?",
+        rusqlite::params![now, settings.id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if archived > 0 || rolled_over > 0 {
+        log::info!(
+            "Todo policies: archived {}, rolled over {}",
+            archived,
+            rolled_over
+        );
+    }
+
+    Ok(TodoPolicyRunResult {
+        archived,
+        rolled_over,
+        ran_at: now,
+    })
+}
+
+/// Applies the policies unless they already ran today (local time).
+pub fn apply_todo_policies_if_due(
+    conn: &rusqlite::Connection,
+) -> Result<Option<TodoPolicyRunResult>, String> {
+    let settings = load_settings(conn)?;
+    let today = chrono::Local::now().date_naive();
+
+    let ran_today = settings
+        .last_run_at
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .is_some_and(|dt| dt.with_timezone(&chrono::Local).date_naive() == today);
+
+    if ran_today {
+        return Ok(None);
+    }
+
+    apply_todo_policies(conn).map(Some)
+}
+
+/// Checks hourly in the background and runs the policies once per day.
+pub fn spawn_daily_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+
+        let state = app.state::<AppState>();
+        let result = match state.db.lock() {
+            Ok(conn) => apply_todo_policies_if_due(&conn),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            log::error!("Failed to run todo policies: {}", e);
+        }
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_todo_policy_settings(
+    state: tauri::State<AppState>,
+) -> Result<TodoPolicySettings, String> {
+    log_command!("get_todo_policy_settings", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    })
+}
+
+#[tauri::command]
+pub fn update_todo_policy_settings(
+    state: tauri::State<AppState>,
+    auto_archive_enabled: bool,
+    archive_after_days: i32,
+    rollover_enabled: bool,
+) -> Result<TodoPolicySettings, String> {
+    log_command!("update_todo_policy_settings", {
+        if archive_after_days < 0 {
+            return Err("archive_after_days must not be negative".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE todo_policy_settings SET auto_archive_enabled = ?, archive_after_days = ?, rollover_enabled = ?, updated_at = ? WHERE id = 'default'",
+            rusqlite::params![
+                auto_archive_enabled as i32,
+                archive_after_days,
+                rollover_enabled as i32,
+                now
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        load_settings(&conn)
+    })
+}
+
+#[tauri::command]
+pub fn run_todo_policies(state: tauri::State<AppState>) -> Result<TodoPolicyRunResult, String> {
+    log_command!("run_todo_policies", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        apply_todo_policies(&conn)
+    })
+}
@@ -1,5 +1,14 @@
 // Todo CRUD commands
 
+use super::comments::delete_entity_comments;
+use super::dependencies::{
+    check_can_start, delete_entity_dependencies, is_blocked, notify_unblocked,
+};
+use super::estimates::normalize_estimate;
+use super::ordering::next_position;
+use super::pomodoro::delete_entity_pomodoro_sessions;
+use super::step_links::{delete_entity_step_links, sync_linked_steps};
+use super::time_tracking::delete_entity_time_entries;
 use crate::log_command;
 use crate::models::Todo;
 use crate::AppState;
@@ -10,52 +19,95 @@ pub fn get_todo(state: tauri::State<AppState>, id: String) -> Result<Todo, Strin
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
-            .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at FROM todos WHERE id = ?")
+            .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at, defer_until, rollover_count, estimate, estimate_unit, position FROM todos WHERE id = ?")
             .map_err(|e| e.to_string())?;
 
-        stmt.query_row([&id], |row| {
+        let todo: Todo = stmt
+            .query_row([&id], |row| {
+                Ok(Todo {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    due_date: row.get(3)?,
+                    defer_until: row.get(8)?,
+                    status: row.get(4)?,
+                    priority: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    rollover_count: row.get(9)?,
+                    estimate: row.get(10)?,
+                    estimate_unit: row.get(11)?,
+                    position: row.get(12)?,
+                    blocked: false,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        let blocked = is_blocked(&conn, "todo", &id)?;
+        Ok(Todo { blocked, ..todo })
+    })
+}
+
+/// Todos in manual order. Deferred todos are hidden until their defer_until
+/// date, and archived todos are hidden entirely, unless requested.
+pub fn list_todos(
+    conn: &rusqlite::Connection,
+    include_deferred: bool,
+    include_archived: bool,
+) -> Result<Vec<Todo>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, content, due_date, status, priority, created_at, updated_at, defer_until, rollover_count, estimate, estimate_unit, position FROM todos
+             WHERE (?1 OR defer_until IS NULL OR datetime(defer_until) <= datetime('now'))
+             AND (?2 OR status != 'archived')
+             ORDER BY position, created_at",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let todo_iter = stmt
+        .query_map([include_deferred, include_archived], |row| {
             Ok(Todo {
                 id: row.get(0)?,
                 title: row.get(1)?,
                 content: row.get(2)?,
                 due_date: row.get(3)?,
+                defer_until: row.get(8)?,
                 status: row.get(4)?,
                 priority: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                rollover_count: row.get(9)?,
+                estimate: row.get(10)?,
+                estimate_unit: row.get(11)?,
+                position: row.get(12)?,
+                blocked: false,
             })
         })
-        .map_err(|e| e.to_string())
-    })
+        .map_err(|e| e.to_string())?;
+
+    let mut todos: Vec<Todo> = todo_iter.filter_map(|t| t.ok()).collect();
+
+    // Flag todos with unfinished blockers
+    for todo in &mut todos {
+        todo.blocked = is_blocked(conn, "todo", &todo.id)?;
+    }
+
+    Ok(todos)
 }
 
 #[tauri::command]
-pub fn get_todos(state: tauri::State<AppState>) -> Result<Vec<Todo>, String> {
+pub fn get_todos(
+    state: tauri::State<AppState>,
+    include_deferred: Option<bool>,
+    include_archived: Option<bool>,
+) -> Result<Vec<Todo>, String> {
     log_command!("get_todos", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
-
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, title, content, due_date, status, priority, created_at, updated_at FROM todos",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let todo_iter = stmt
-            .query_map([], |row| {
-                Ok(Todo {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    due_date: row.get(3)?,
-                    status: row.get(4)?,
-                    priority: row.get(5)?,
-                    created_at: row.get(6)?,
-                    updated_at: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-
-        Ok(todo_iter.filter_map(|t| t.ok()).collect())
+        list_todos(
+            &conn,
+            include_deferred.unwrap_or(false),
+            include_archived.unwrap_or(false),
+        )
     })
 }
 
@@ -66,17 +118,22 @@ pub fn create_todo(
     content: Option<String>,
     due_date: Option<String>,
     priority: Option<String>,
+    defer_until: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
 ) -> Result<Todo, String> {
     log_command!("create_todo", {
+        let estimate_unit = normalize_estimate(estimate, estimate_unit)?;
         let conn = state.db.lock().map_err(|e| e.to_string())?;
 
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
         let priority = priority.unwrap_or_else(|| "P2".to_string());
+        let position = next_position(&conn, "todo", None)?;
 
         conn.execute(
-            "INSERT INTO todos (id, title, content, due_date, status, priority, created_at, updated_at) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?)",
-            rusqlite::params![id, title, content, due_date, priority, now, now],
+            "INSERT INTO todos (id, title, content, due_date, defer_until, status, priority, estimate, estimate_unit, position, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'pending', ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, title, content, due_date, defer_until, priority, estimate, estimate_unit, position, now, now],
         ).map_err(|e| e.to_string())?;
 
         Ok(Todo {
@@ -84,16 +141,23 @@ pub fn create_todo(
             title,
             content,
             due_date,
+            defer_until,
             status: "pending".to_string(),
             priority,
             created_at: now.clone(),
             updated_at: now,
+            estimate,
+            estimate_unit,
+            position: Some(position),
+            rollover_count: 0,
+            blocked: false,
         })
     })
 }
 
 #[tauri::command]
 pub fn update_todo(
+    app: tauri::AppHandle,
     state: tauri::State<AppState>,
     id: String,
     title: Option<String>,
@@ -101,13 +165,17 @@ pub fn update_todo(
     due_date: Option<String>,
     status: Option<String>,
     priority: Option<String>,
+    defer_until: Option<String>,
+    estimate: Option<f64>,
+    estimate_unit: Option<String>,
+    force: Option<bool>,
 ) -> Result<Todo, String> {
     log_command!("update_todo", {
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
 
         let mut stmt = conn
-            .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at FROM todos WHERE id = ?")
+            .prepare("SELECT id, title, content, due_date, status, priority, created_at, updated_at, defer_until, rollover_count, estimate, estimate_unit, position FROM todos WHERE id = ?")
             .map_err(|e| e.to_string())?;
 
         let todo: Todo = stmt
@@ -117,10 +185,16 @@ pub fn update_todo(
                     title: row.get(1)?,
                     content: row.get(2)?,
                     due_date: row.get(3)?,
+                    defer_until: row.get(8)?,
                     status: row.get(4)?,
                     priority: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
+                    rollover_count: row.get(9)?,
+                    estimate: row.get(10)?,
+                    estimate_unit: row.get(11)?,
+                    position: row.get(12)?,
+                    blocked: false,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -128,23 +202,48 @@ pub fn update_todo(
         let new_title = title.unwrap_or(todo.title);
         let new_content = content.or(todo.content);
         let new_due_date = due_date.or(todo.due_date);
+        let new_defer_until = defer_until.or(todo.defer_until);
+        let was_done = todo.status == "done";
+        let status_changed = status.as_ref().is_some_and(|s| *s != todo.status);
         let new_status = status.unwrap_or(todo.status);
         let new_priority = priority.unwrap_or(todo.priority);
+        let new_estimate = estimate.or(todo.estimate);
+        let new_estimate_unit =
+            normalize_estimate(new_estimate, estimate_unit.or(todo.estimate_unit))?;
+
+        if status_changed {
+            check_can_start(&conn, "todo", &id, &new_status, force.unwrap_or(false))?;
+        }
 
         conn.execute(
-            "UPDATE todos SET title = ?, content = ?, due_date = ?, status = ?, priority = ?, updated_at = ? WHERE id = ?",
-            rusqlite::params![new_title, new_content, new_due_date, new_status, new_priority, now, id],
+            "UPDATE todos SET title = ?, content = ?, due_date = ?, defer_until = ?, status = ?, priority = ?, estimate = ?, estimate_unit = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![new_title, new_content, new_due_date, new_defer_until, new_status, new_priority, new_estimate, new_estimate_unit, now, id],
         ).map_err(|e| e.to_string())?;
 
+        if new_status == "done" && !was_done {
+            notify_unblocked(&app, &conn, "todo", &id)?;
+        }
+        if status_changed {
+            sync_linked_steps(&conn, "todo", &id)?;
+        }
+
+        let blocked = is_blocked(&conn, "todo", &id)?;
+
         Ok(Todo {
             id: todo.id,
             title: new_title,
             content: new_content,
             due_date: new_due_date,
+            defer_until: new_defer_until,
             status: new_status,
             priority: new_priority,
             created_at: todo.created_at,
             updated_at: now,
+            estimate: new_estimate,
+            estimate_unit: new_estimate_unit,
+            position: todo.position,
+            rollover_count: todo.rollover_count,
+            blocked,
         })
     })
 }
@@ -155,6 +254,11 @@ pub fn delete_todo(state: tauri::State<AppState>, id: String) -> Result<(), Stri
         let conn = state.db.lock().map_err(|e| e.to_string())?;
         conn.execute("DELETE FROM todos WHERE id = ?", [&id])
             .map_err(|e| e.to_string())?;
+        delete_entity_dependencies(&conn, "todo", &id)?;
+        delete_entity_comments(&conn, "todo", &id)?;
+        delete_entity_step_links(&conn, "todo", &id)?;
+        delete_entity_time_entries(&conn, "todo", &id)?;
+        delete_entity_pomodoro_sessions(&conn, "todo", &id)?;
         Ok(())
     })
 }
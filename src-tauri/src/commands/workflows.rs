@@ -0,0 +1,408 @@
+// Workflow commands (per-plan task statuses and transition rules)
+//
+// Every status belongs to a category (todo | doing | done). Progress,
+// statistics and blocking work on the category, so custom statuses behave
+// like pending / in-progress / done. Plans without a workflow use those three.
+
+use crate::log_command;
+use crate::models::{WorkflowStatus, WorkflowTransition};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub const CATEGORIES: [&str; 3] = ["todo", "doing", "done"];
+
+#[derive(Debug, Serialize)]
+pub struct Workflow {
+    pub plan_id: String,
+    pub statuses: Vec<WorkflowStatus>, // By position
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowStatusInput {
+    pub name: String,
+    pub category: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowTransitionInput {
+    pub from_status: String,
+    pub to_status: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// SQL expression for the category of a task row, where `task` is the table
+/// name or alias, e.g. `task_category_sql("t")`.
+pub fn task_category_sql(task: &str) -> String {
+    format!(
+        "COALESCE((SELECT ws.category FROM workflow_statuses ws WHERE ws.plan_id = {t}.plan_id AND ws.name = {t}.status),
+            CASE {t}.status WHEN 'done' THEN 'done' WHEN 'in-progress' THEN 'doing' ELSE 'todo' END)",
+        t = task
+    )
+}
+
+/// Built-in status of a category, used by plans without a workflow.
+pub fn default_status(category: &str) -> &'static str {
+    match category {
+        "done" => "done",
+        "doing" => "in-progress",
+        _ => "pending",
+    }
+}
+
+fn default_category(status: &str) -> &'static str {
+    match status {
+        "done" => "done",
+        "in-progress" => "doing",
+        _ => "todo",
+    }
+}
+
+pub fn load_workflow(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+) -> Result<Option<Workflow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, plan_id, name, category, position, created_at FROM workflow_statuses
+             WHERE plan_id = ? ORDER BY position",
+        )
+        .map_err(|e| e.to_string())?;
+    let statuses: Vec<WorkflowStatus> = stmt
+        .query_map([plan_id], |row| {
+            Ok(WorkflowStatus {
+                id: row.get(0)?,
+                plan_id: row.get(1)?,
+                name: row.get(2)?,
+                category: row.get(3)?,
+                position: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|s| s.ok())
+        .collect();
+
+    if statuses.is_empty() {
+        return Ok(None);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, plan_id, from_status, to_status, created_at FROM workflow_transitions
+             WHERE plan_id = ?",
+        )
+        .map_err(|e| e.to_string())?;
+    let transitions = stmt
+        .query_map([plan_id], |row| {
+            Ok(WorkflowTransition {
+                id: row.get(0)?,
+                plan_id: row.get(1)?,
+                from_status: row.get(2)?,
+                to_status: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|t| t.ok())
+        .collect();
+
+    Ok(Some(Workflow {
+        plan_id: plan_id.to_string(),
+        statuses,
+        transitions,
+    }))
+}
+
+/// Category of a status in a plan (todo | doing | done).
+pub fn status_category(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    status: &str,
+) -> Result<String, String> {
+    let category: Option<String> = conn
+        .query_row(
+            "SELECT category FROM workflow_statuses WHERE plan_id = ? AND name = ?",
+            rusqlite::params![plan_id, status],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(category.unwrap_or_else(|| default_category(status).to_string()))
+}
+
+/// First status of a category in the plan's workflow, or the built-in one.
+pub fn category_status(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    category: &str,
+) -> Result<String, String> {
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT name FROM workflow_statuses WHERE plan_id = ? AND category = ?
+             ORDER BY position LIMIT 1",
+            rusqlite::params![plan_id, category],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(status.unwrap_or_else(|| default_status(category).to_string()))
+}
+
+/// Status of a new task: the first status of the plan's workflow, or pending.
+pub fn initial_status(conn: &rusqlite::Connection, plan_id: &str) -> Result<String, String> {
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT name FROM workflow_statuses WHERE plan_id = ? ORDER BY position LIMIT 1",
+            [plan_id],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(status.unwrap_or_else(|| "pending".to_string()))
+}
+
+/// Rejects a status change the plan's workflow does not allow. Without
+/// transitions any change between workflow statuses is allowed, and tasks in
+/// a status outside the workflow may move to any workflow status.
+pub fn validate_transition(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), String> {
+    if from == to {
+        return Ok(());
+    }
+    let Some(workflow) = load_workflow(conn, plan_id)? else {
+        return Ok(());
+    };
+
+    if !workflow.statuses.iter().any(|s| s.name == to) {
+        return Err(format!(
+            "Status '{}' is not part of this plan's workflow",
+            to
+        ));
+    }
+    let from_known = workflow.statuses.iter().any(|s| s.name == from);
+    if from_known
+        && !workflow.transitions.is_empty()
+        && !workflow
+            .transitions
+            .iter()
+            .any(|t| t.from_status == from && t.to_status == to)
+    {
+        return Err(format!("Cannot move a task from '{}' to '{}'", from, to));
+    }
+    Ok(())
+}
+
+/// Moves tasks of the plan whose status is not in its workflow to the first
+/// status of their category. `categories` holds (task id, category) pairs
+/// taken before the workflow or plan changed.
+pub fn conform_statuses(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    categories: &[(String, String)],
+) -> Result<(), String> {
+    let names: Vec<String> = match load_workflow(conn, plan_id)? {
+        Some(workflow) => workflow.statuses.into_iter().map(|s| s.name).collect(),
+        None => CATEGORIES
+            .iter()
+            .map(|c| default_status(c).to_string())
+            .collect(),
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+
+    for (task_id, category) in categories {
+        let current: Option<String> = conn
+            .query_row("SELECT status FROM tasks WHERE id = ?", [task_id], |row| {
+                row.get(0)
+            })
+            .ok();
+        if current.is_none_or(|status| names.contains(&status)) {
+            continue;
+        }
+        conn.execute(
+            "UPDATE tasks SET status = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![category_status(conn, plan_id, category)?, now, task_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// (task id, category) of every task in the plan.
+pub fn task_categories(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, {} FROM tasks WHERE plan_id = ?",
+            task_category_sql("tasks")
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([plan_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+fn validate_workflow(
+    statuses: &[WorkflowStatusInput],
+    transitions: &[WorkflowTransitionInput],
+) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for status in statuses {
+        if status.name.is_empty() {
+            return Err("Status names cannot be empty".to_string());
+        }
+        if !CATEGORIES.contains(&status.category.as_str()) {
+            return Err(format!(
+                "Invalid category '{}'. Use 'todo', 'doing' or 'done'",
+                status.category
+            ));
+        }
+        if !names.insert(status.name.as_str()) {
+            return Err(format!("Duplicate status '{}'", status.name));
+        }
+    }
+    for category in CATEGORIES {
+        if !statuses.iter().any(|s| s.category == category) {
+            return Err(format!(
+                "A workflow needs at least one '{}' status",
+                category
+            ));
+        }
+    }
+    for transition in transitions {
+        for name in [&transition.from_status, &transition.to_status] {
+            if !names.contains(name.as_str()) {
+                return Err(format!("Transition uses unknown status '{}'", name));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn clear_workflow(conn: &rusqlite::Connection, plan_id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM workflow_statuses WHERE plan_id = ?", [plan_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM workflow_transitions WHERE plan_id = ?",
+        [plan_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Replaces the plan's workflow. Tasks in a status that no longer exists move
+/// to the first status of their category.
+pub fn save_workflow(
+    conn: &rusqlite::Connection,
+    plan_id: &str,
+    statuses: &[WorkflowStatusInput],
+    transitions: &[WorkflowTransitionInput],
+) -> Result<Workflow, String> {
+    let statuses: Vec<WorkflowStatusInput> = statuses
+        .iter()
+        .map(|s| WorkflowStatusInput {
+            name: s.name.trim().to_string(),
+            category: s.category.clone(),
+        })
+        .collect();
+    let transitions: Vec<WorkflowTransitionInput> = transitions
+        .iter()
+        .map(|t| WorkflowTransitionInput {
+            from_status: t.from_status.trim().to_string(),
+            to_status: t.to_status.trim().to_string(),
+        })
+        .collect();
+    validate_workflow(&statuses, &transitions)?;
+
+    let categories = task_categories(conn, plan_id)?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    clear_workflow(conn, plan_id)?;
+    for (position, status) in statuses.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO workflow_statuses (id, plan_id, name, category, position, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), plan_id, status.name, status.category, position as i32, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    for transition in &transitions {
+        conn.execute(
+            "INSERT OR IGNORE INTO workflow_transitions (id, plan_id, from_status, to_status, created_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), plan_id, transition.from_status, transition.to_status, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    conform_statuses(conn, plan_id, &categories)?;
+    load_workflow(conn, plan_id)?.ok_or_else(|| "Workflow not saved".to_string())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_plan_workflow(
+    state: tauri::State<AppState>,
+    plan_id: String,
+) -> Result<Option<Workflow>, String> {
+    log_command!("get_plan_workflow", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        load_workflow(&conn, &plan_id)
+    })
+}
+
+#[tauri::command]
+pub fn set_plan_workflow(
+    state: tauri::State<AppState>,
+    plan_id: String,
+    statuses: Vec<WorkflowStatusInput>,
+    transitions: Vec<WorkflowTransitionInput>,
+) -> Result<Workflow, String> {
+    log_command!("set_plan_workflow", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM plans WHERE id = ?)",
+                [&plan_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("Plan {} not found", plan_id));
+        }
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let workflow = save_workflow(&tx, &plan_id, &statuses, &transitions)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(workflow)
+    })
+}
+
+/// Removes the plan's workflow; tasks go back to pending / in-progress / done.
+#[tauri::command]
+pub fn delete_plan_workflow(state: tauri::State<AppState>, plan_id: String) -> Result<(), String> {
+    log_command!("delete_plan_workflow", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let categories = task_categories(&tx, &plan_id)?;
+        clear_workflow(&tx, &plan_id)?;
+        conform_statuses(&tx, &plan_id, &categories)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(())
+    })
+}
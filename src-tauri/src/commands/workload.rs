@@ -0,0 +1,283 @@
+// Workload commands (scheduled work per day across plans)
+//
+// Load is measured in priority weight (P0=4 .. P3=1). An open task spreads its
+// weight evenly over its start..end days (working days unless weekends are
+// included); an open todo puts its full weight on its due date.
+
+use super::plans::{is_weekend, priority_weight};
+use super::schedule::parse_day;
+use super::workflows::task_category_sql;
+use crate::log_command;
+use crate::AppState;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Longest range accepted by get_workload, in days.
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkloadSettings {
+    pub id: String,
+    pub daily_capacity: f64,    // Priority weight per day
+    pub include_weekends: bool, // Spread tasks over weekends too
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Workload {
+    pub from: String,
+    pub to: String,
+    pub daily_capacity: f64,
+    pub days: Vec<WorkloadDay>,
+    pub overloaded_days: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkloadDay {
+    pub date: String,
+    pub load: f64,
+    pub over_capacity: bool,
+    pub items: Vec<WorkloadItem>, // Heaviest first
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WorkloadItem {
+    pub entity_type: String, // 'task' | 'todo'
+    pub entity_id: String,
+    pub title: String,
+    pub priority: String,
+    pub load: f64, // Share of the item's weight on this day
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+pub fn load_workload_settings(conn: &rusqlite::Connection) -> Result<WorkloadSettings, String> {
+    conn.query_row(
+        "SELECT id, daily_capacity, include_weekends, created_at, updated_at FROM workload_settings LIMIT 1",
+        [],
+        |row| {
+            Ok(WorkloadSettings {
+                id: row.get(0)?,
+                daily_capacity: row.get(1)?,
+                include_weekends: row.get(2)?,
+                created_at: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Working days (Mon-Fri) from `first` to `last`, both included.
+fn working_days_in(first: NaiveDate, last: NaiveDate) -> i64 {
+    let total = (last - first).num_days() + 1;
+    let rest = (0..total % 7)
+        .filter(|offset| !is_weekend(last - Duration::days(*offset)))
+        .count() as i64;
+    total / 7 * 5 + rest
+}
+
+/// Days of a task's start..end range (or the single date it has) that fall
+/// between `from` and `to`, with the number of days in the whole range.
+/// Weekends are dropped unless included or the range has no working day.
+fn task_days(
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    include_weekends: bool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> (Vec<NaiveDate>, i64) {
+    let (first, last) = match (start, end) {
+        (Some(s), Some(e)) => (s.min(e), s.max(e)),
+        (Some(day), None) | (None, Some(day)) => (day, day),
+        (None, None) => return (Vec::new(), 0),
+    };
+    let working = working_days_in(first, last);
+    let weekends = include_weekends || working == 0;
+    let total = if weekends {
+        (last - first).num_days() + 1
+    } else {
+        working
+    };
+    let days = first
+        .max(from)
+        .iter_days()
+        .take_while(|d| *d <= last.min(to))
+        .filter(|d| weekends || !is_weekend(*d))
+        .collect();
+    (days, total)
+}
+
+/// Aggregates open tasks and todos per day between `from` and `to`.
+pub fn compute_workload(
+    conn: &rusqlite::Connection,
+    from: NaiveDate,
+    to: NaiveDate,
+    settings: &WorkloadSettings,
+) -> Result<Workload, String> {
+    if to < from {
+        return Err("'to' is before 'from'".to_string());
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(format!("Range is limited to {} days", MAX_RANGE_DAYS));
+    }
+
+    let mut days: BTreeMap<NaiveDate, Vec<WorkloadItem>> = from
+        .iter_days()
+        .take_while(|d| *d <= to)
+        .map(|d| (d, Vec::new()))
+        .collect();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, title, priority, start_date, end_date FROM tasks t
+             WHERE {} != 'done' AND (start_date IS NOT NULL OR end_date IS NOT NULL)",
+            task_category_sql("t")
+        ))
+        .map_err(|e| e.to_string())?;
+    let tasks: Vec<(String, String, String, Option<String>, Option<String>)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (id, title, priority, start_date, end_date) in tasks {
+        let (spread, total) = task_days(
+            start_date.as_deref().and_then(parse_day),
+            end_date.as_deref().and_then(parse_day),
+            settings.include_weekends,
+            from,
+            to,
+        );
+        if spread.is_empty() {
+            continue;
+        }
+        let share = priority_weight(&priority) as f64 / total as f64;
+        for day in spread {
+            if let Some(items) = days.get_mut(&day) {
+                items.push(WorkloadItem {
+                    entity_type: "task".to_string(),
+                    entity_id: id.clone(),
+                    title: title.clone(),
+                    priority: priority.clone(),
+                    load: share,
+                });
+            }
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, priority, due_date FROM todos
+             WHERE status NOT IN ('done', 'archived') AND due_date IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let todos: Vec<(String, String, String, String)> = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (id, title, priority, due_date) in todos {
+        let Some(items) = parse_day(&due_date).and_then(|day| days.get_mut(&day)) else {
+            continue;
+        };
+        items.push(WorkloadItem {
+            entity_type: "todo".to_string(),
+            entity_id: id,
+            title,
+            load: priority_weight(&priority) as f64,
+            priority,
+        });
+    }
+
+    let days: Vec<WorkloadDay> = days
+        .into_iter()
+        .map(|(day, mut items)| {
+            items.sort_by(|a, b| b.load.total_cmp(&a.load));
+            let load: f64 = items.iter().map(|item| item.load).sum();
+            let load = (load * 100.0).round() / 100.0;
+            WorkloadDay {
+                date: day.format("%Y-%m-%d").to_string(),
+                load,
+                over_capacity: load > settings.daily_capacity,
+                items,
+            }
+        })
+        .collect();
+
+    Ok(Workload {
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        daily_capacity: settings.daily_capacity,
+        overloaded_days: days
+            .iter()
+            .filter(|d| d.over_capacity)
+            .map(|d| d.date.clone())
+            .collect(),
+        days,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_workload(
+    state: tauri::State<AppState>,
+    from: String,
+    to: String,
+) -> Result<Workload, String> {
+    log_command!("get_workload", {
+        let from = parse_day(&from).ok_or("Invalid 'from' date")?;
+        let to = parse_day(&to).ok_or("Invalid 'to' date")?;
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let settings = load_workload_settings(&conn)?;
+        compute_workload(&conn, from, to, &settings)
+    })
+}
+
+#[tauri::command]
+pub fn get_workload_settings(state: tauri::State<AppState>) -> Result<WorkloadSettings, String> {
+    log_command!("get_workload_settings", {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        load_workload_settings(&conn)
+    })
+}
+
+#[tauri::command]
+pub fn update_workload_settings(
+    state: tauri::State<AppState>,
+    daily_capacity: f64,
+    include_weekends: bool,
+) -> Result<WorkloadSettings, String> {
+    log_command!("update_workload_settings", {
+        if daily_capacity.is_nan() || daily_capacity <= 0.0 {
+            return Err("daily_capacity must be positive".to_string());
+        }
+
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE workload_settings SET daily_capacity = ?, include_weekends = ?, updated_at = ? WHERE id = 'default'",
+            rusqlite::params![daily_capacity, include_weekends, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| e.to_string())?;
+
+        load_workload_settings(&conn)
+    })
+}
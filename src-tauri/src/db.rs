@@ -3,6 +3,13 @@
 use log::info;
 use rusqlite::Connection;
 
+/// Base directory for app data (database, attachments).
+pub fn data_dir() -> std::path::PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("plan-todos")
+}
+
 pub fn init_db(conn: &Connection) -> Result<(), rusqlite::Error> {
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON", [])?;
@@ -180,6 +187,82 @@ pub fn init_db(conn: &Connection) -> Result<(), rusqlite::Error> {
         )?;
     }
 
+    // Todo policy settings (auto-archive / rollover)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS todo_policy_settings (
+            id TEXT PRIMARY KEY,
+            auto_archive_enabled INTEGER NOT NULL DEFAULT 0,
+            archive_after_days INTEGER NOT NULL DEFAULT 30,
+            rollover_enabled INTEGER NOT NULL DEFAULT 0,
+            last_run_at TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Seed default todo policy settings
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM todo_policy_settings", [], |row| {
+        row.get(0)
+    })?;
+    if count == 0 {
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO todo_policy_settings (id, auto_archive_enabled, archive_after_days, rollover_enabled, last_run_at, created_at, updated_at) VALUES (?, ?, ?, ?, NULL, ?, ?)",
+            rusqlite::params!["default", 0, 30, 0, &now, &now],
+        )?;
+    }
+
+    // Pomodoro settings (phase lengths and long-break cadence)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pomodoro_settings (
+            id TEXT PRIMARY KEY,
+            work_minutes INTEGER NOT NULL DEFAULT 25,
+            short_break_minutes INTEGER NOT NULL DEFAULT 5,
+            long_break_minutes INTEGER NOT NULL DEFAULT 15,
+            long_break_interval INTEGER NOT NULL DEFAULT 4,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Seed default pomodoro settings
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM pomodoro_settings", [], |row| {
+        row.get(0)
+    })?;
+    if count == 0 {
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO pomodoro_settings (id, work_minutes, short_break_minutes, long_break_minutes, long_break_interval, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params!["default", 25, 5, 15, 4, &now, &now],
+        )?;
+    }
+
+    // Workload settings (daily capacity in priority weight: P0=4 .. P3=1)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workload_settings (
+            id TEXT PRIMARY KEY,
+            daily_capacity REAL NOT NULL DEFAULT 8,
+            include_weekends INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Seed default workload settings
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM workload_settings", [], |row| {
+        row.get(0)
+    })?;
+    if count == 0 {
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO workload_settings (id, daily_capacity, include_weekends, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params!["default", 8.0, 0, &now, &now],
+        )?;
+    }
+
     // Notification plugins table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS notification_plugins (
@@ -345,12 +428,275 @@ pub fn init_db(conn: &Connection) -> Result<(), rusqlite::Error> {
         .ok();
     }
 
+    // Dependencies table (blocker must be done before blocked can start)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS dependencies (
+            id TEXT PRIMARY KEY,
+            blocker_type TEXT NOT NULL,
+            blocker_id TEXT NOT NULL,
+            blocked_type TEXT NOT NULL,
+            blocked_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE(blocker_type, blocker_id, blocked_type, blocked_id)
+        )",
+        [],
+    )?;
+
+    // Comments table (notes timeline on entities)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS comments (
+            id TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            edited_at TEXT
+        )",
+        [],
+    )?;
+
+    // Time entries table (timers and manual entries on todos / tasks)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS time_entries (
+            id TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            running_since TEXT,
+            duration_seconds INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'stopped',
+            note TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Pomodoro sessions table (finished work phases, optionally on a todo / task)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pomodoro_sessions (
+            id TEXT PRIMARY KEY,
+            entity_type TEXT,
+            entity_id TEXT,
+            started_at TEXT NOT NULL,
+            ended_at TEXT NOT NULL,
+            planned_minutes INTEGER NOT NULL,
+            focus_seconds INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'completed',
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Plan templates table (plan shapes with dates as day offsets, content is JSON)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plan_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Workflow statuses table (per-plan task statuses, ordered, each in a category)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workflow_statuses (
+            id TEXT PRIMARY KEY,
+            plan_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            category TEXT NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            UNIQUE(plan_id, name)
+        )",
+        [],
+    )?;
+
+    // Workflow transitions table (allowed status changes, none = any change allowed)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workflow_transitions (
+            id TEXT PRIMARY KEY,
+            plan_id TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE(plan_id, from_status, to_status)
+        )",
+        [],
+    )?;
+
+    // Target values table (timestamped measurements of metric targets)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS target_values (
+            id TEXT PRIMARY KEY,
+            target_id TEXT NOT NULL,
+            value REAL NOT NULL,
+            note TEXT,
+            recorded_at TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Sprints table (time-boxed iterations inside a plan)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sprints (
+            id TEXT PRIMARY KEY,
+            plan_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            goal TEXT,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'planned',
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Status history table (task and step status changes, for burndown charts)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS status_history (
+            id TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            from_status TEXT,
+            to_status TEXT NOT NULL,
+            category TEXT NOT NULL,
+            changed_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Step links table (todos, tasks or plans a target step is made of)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS step_links (
+            id TEXT PRIMARY KEY,
+            step_id TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE(step_id, entity_type, entity_id)
+        )",
+        [],
+    )?;
+
+    // Objectives table (OKR objectives of a period, optionally aligned to a parent)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS objectives (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            description TEXT,
+            period TEXT NOT NULL,
+            parent_id TEXT,
+            status TEXT NOT NULL DEFAULT 'active',
+            final_score REAL,
+            grade_note TEXT,
+            graded_at TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Key results table (scored 0.0-1.0 from a metric or by hand)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS key_results (
+            id TEXT PRIMARY KEY,
+            objective_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            kind TEXT NOT NULL DEFAULT 'manual',
+            unit TEXT,
+            start_value REAL,
+            target_value REAL,
+            current_value REAL,
+            score REAL,
+            confidence REAL NOT NULL DEFAULT 0.5,
+            final_score REAL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Attachments table (files stored in the app data dir, deduplicated by content hash)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            stored_name TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
     // Migration: Add priority columns (SQLite doesn't support IF NOT EXISTS for ALTER TABLE)
     // Check if column exists first, then add if not
     add_column_if_not_exists(conn, "todos", "priority", "TEXT DEFAULT 'P2'")?;
     add_column_if_not_exists(conn, "tasks", "priority", "TEXT DEFAULT 'P2'")?;
     add_column_if_not_exists(conn, "steps", "priority", "TEXT DEFAULT 'P2'")?;
 
+    // Migration: Add defer_until columns (deferred start / snooze)
+    add_column_if_not_exists(conn, "todos", "defer_until", "TEXT")?;
+    add_column_if_not_exists(conn, "tasks", "defer_until", "TEXT")?;
+
+    // Migration: Add rollover counter for overdue todos rolled forward
+    add_column_if_not_exists(
+        conn,
+        "todos",
+        "rollover_count",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    // Migration: Add stored plan progress and its options
+    add_column_if_not_exists(conn, "plans", "progress", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_not_exists(
+        conn,
+        "plans",
+        "weighted_progress",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_not_exists(conn, "plans", "auto_complete", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Migration: Add parent task for subtasks
+    add_column_if_not_exists(conn, "tasks", "parent_task_id", "TEXT")?;
+
+    // Migration: Add effort estimates (minutes or story points)
+    for table in ["todos", "tasks", "steps"] {
+        add_column_if_not_exists(conn, table, "estimate", "REAL")?;
+        add_column_if_not_exists(conn, table, "estimate_unit", "TEXT")?;
+    }
+
+    // Migration: Add manual order keys (fractional indexing)
+    for table in ["todos", "tasks", "steps"] {
+        add_column_if_not_exists(conn, table, "position", "TEXT")?;
+    }
+
+    // Migration: Add metric mode to targets
+    add_column_if_not_exists(conn, "targets", "metric_unit", "TEXT")?;
+    add_column_if_not_exists(conn, "targets", "metric_start", "REAL")?;
+    add_column_if_not_exists(conn, "targets", "metric_goal", "REAL")?;
+    add_column_if_not_exists(conn, "targets", "metric_direction", "TEXT")?;
+
+    // Migration: Add sprint assignment of tasks
+    add_column_if_not_exists(conn, "tasks", "sprint_id", "TEXT")?;
+
+    // Migration: Add step weight modes to targets
+    add_column_if_not_exists(
+        conn,
+        "targets",
+        "weight_mode",
+        "TEXT NOT NULL DEFAULT 'manual'",
+    )?;
+
     // Create indexes for performance optimization
     create_indexes(conn)?;
 
@@ -398,6 +744,18 @@ fn create_indexes(conn: &Connection) -> Result<(), rusqlite::Error> {
         "CREATE INDEX IF NOT EXISTS idx_tasks_dates ON tasks(start_date, end_date)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tasks_parent_task_id ON tasks(parent_task_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tasks_plan_position ON tasks(plan_id, position)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tasks_sprint_id ON tasks(sprint_id)",
+        [],
+    )?;
 
     // Indexes for steps
     conn.execute(
@@ -408,6 +766,10 @@ fn create_indexes(conn: &Connection) -> Result<(), rusqlite::Error> {
         "CREATE INDEX IF NOT EXISTS idx_steps_status ON steps(status)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_steps_target_position ON steps(target_id, position)",
+        [],
+    )?;
 
     // Indexes for todos
     conn.execute(
@@ -422,6 +784,14 @@ fn create_indexes(conn: &Connection) -> Result<(), rusqlite::Error> {
         "CREATE INDEX IF NOT EXISTS idx_todos_status_due ON todos(status, due_date)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_todos_defer_until ON todos(defer_until)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_todos_position ON todos(position)",
+        [],
+    )?;
 
     // Indexes for plans
     conn.execute(
@@ -461,6 +831,105 @@ fn create_indexes(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     )?;
 
+    // Indexes for dependencies
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_dependencies_blocker ON dependencies(blocker_type, blocker_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_dependencies_blocked ON dependencies(blocked_type, blocked_id)",
+        [],
+    )?;
+
+    // Indexes for comments
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_comments_entity ON comments(entity_type, entity_id, created_at)",
+        [],
+    )?;
+
+    // Indexes for time entries
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_time_entries_entity ON time_entries(entity_type, entity_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_time_entries_status ON time_entries(status)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_time_entries_started_at ON time_entries(started_at)",
+        [],
+    )?;
+
+    // Indexes for pomodoro sessions
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_entity ON pomodoro_sessions(entity_type, entity_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_started_at ON pomodoro_sessions(started_at)",
+        [],
+    )?;
+
+    // Indexes for workflows
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_workflow_statuses_plan_id ON workflow_statuses(plan_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_workflow_transitions_plan_id ON workflow_transitions(plan_id)",
+        [],
+    )?;
+
+    // Indexes for target values
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_target_values_target ON target_values(target_id, recorded_at)",
+        [],
+    )?;
+
+    // Indexes for sprints
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_sprints_plan_id ON sprints(plan_id, start_date)",
+        [],
+    )?;
+
+    // Indexes for status history
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_status_history_entity ON status_history(entity_type, entity_id, changed_at)",
+        [],
+    )?;
+
+    // Indexes for step links
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_step_links_entity ON step_links(entity_type, entity_id)",
+        [],
+    )?;
+
+    // Indexes for OKRs
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_objectives_period ON objectives(period)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_objectives_parent_id ON objectives(parent_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_key_results_objective_id ON key_results(objective_id)",
+        [],
+    )?;
+
+    // Indexes for attachments
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_attachments_entity ON attachments(entity_type, entity_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_attachments_stored_name ON attachments(stored_name)",
+        [],
+    )?;
+
     // Indexes for notification settings
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_notification_entity ON notification_settings(entity_type, entity_id)",
@@ -32,10 +32,7 @@ pub fn run() {
     info!("Starting Plan Todos application...");
 
     // Initialize database
-    let db_path = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("plan-todos")
-        .join("data.db");
+    let db_path = db::data_dir().join("data.db");
 
     // Create directory if it doesn't exist
     if let Some(parent) = db_path.parent() {
@@ -48,6 +45,21 @@ pub fn run() {
     // Initialize database schema
     db::init_db(&conn).expect("Failed to initialize database");
 
+    // Apply todo auto-archive / rollover policies at startup
+    if let Err(e) = commands::todo_policies::apply_todo_policies(&conn) {
+        log::error!("Failed to run todo policies: {}", e);
+    }
+
+    // Backfill stored plan progress (older databases have none)
+    if let Err(e) = commands::plans::refresh_all_plan_progress(&conn) {
+        log::error!("Failed to refresh plan progress: {}", e);
+    }
+
+    // Backfill manual order keys, in creation order
+    if let Err(e) = commands::ordering::backfill_positions(&conn) {
+        log::error!("Failed to backfill positions: {}", e);
+    }
+
     // Create AppState
     let state = AppState {
         db: Mutex::new(conn),
@@ -59,6 +71,12 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(state)
+        .manage(commands::pomodoro::PomodoroState::default())
+        .setup(|app| {
+            commands::todo_policies::spawn_daily_scheduler(app.handle().clone());
+            commands::pomodoro::spawn_ticker(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::dashboard::get_dashboard,
             commands::todos::get_todo,
@@ -71,11 +89,36 @@ pub fn run() {
             commands::plans::create_plan,
             commands::plans::update_plan,
             commands::plans::delete_plan,
+            commands::plans::shift_plan,
+            commands::plans::reschedule_plan,
+            commands::sprints::get_sprints,
+            commands::sprints::create_sprint,
+            commands::sprints::update_sprint,
+            commands::sprints::start_sprint,
+            commands::sprints::close_sprint,
+            commands::sprints::delete_sprint,
+            commands::sprints::assign_tasks_to_sprint,
+            commands::duplicate::duplicate_plan,
+            commands::workflows::get_plan_workflow,
+            commands::workflows::set_plan_workflow,
+            commands::workflows::delete_plan_workflow,
+            commands::plan_templates::create_plan_template,
+            commands::plan_templates::get_plan_templates,
+            commands::plan_templates::delete_plan_template,
+            commands::plan_templates::instantiate_plan_template,
+            commands::plan_templates::export_plan_template,
+            commands::plan_templates::import_plan_template,
             commands::targets::get_target,
             commands::targets::get_targets,
             commands::targets::create_target,
             commands::targets::update_target,
             commands::targets::delete_target,
+            commands::duplicate::duplicate_target,
+            commands::target_metrics::set_target_metric,
+            commands::target_metrics::clear_target_metric,
+            commands::target_metrics::record_target_value,
+            commands::target_metrics::get_target_values,
+            commands::target_metrics::delete_target_value,
             commands::milestones::get_milestone,
             commands::milestones::get_milestones,
             commands::milestones::create_milestone,
@@ -103,14 +146,43 @@ pub fn run() {
             commands::tasks::create_task,
             commands::tasks::update_task,
             commands::tasks::delete_task,
+            commands::subtasks::get_task_tree,
+            commands::subtasks::move_task,
+            commands::ordering::reorder,
             commands::steps::get_steps,
             commands::steps::create_step,
             commands::steps::update_step,
             commands::steps::delete_step,
+            commands::step_weights::set_step_weight_mode,
+            commands::step_weights::normalize_step_weights,
+            commands::step_links::get_step_links,
+            commands::step_links::link_step,
+            commands::step_links::unlink_step,
             commands::search::search_all,
             commands::import::import_data,
             commands::export::export_data,
+            commands::import::import_bundle,
+            commands::export::export_bundle,
             commands::statistics::get_statistics,
+            commands::statistics::get_sprint_velocity,
+            commands::workload::get_workload,
+            commands::workload::get_workload_settings,
+            commands::workload::update_workload_settings,
+            commands::burndown::get_burndown,
+            commands::burndown::get_target_burnup,
+            commands::forecast::forecast_target,
+            commands::okrs::get_objectives,
+            commands::okrs::create_objective,
+            commands::okrs::update_objective,
+            commands::okrs::delete_objective,
+            commands::okrs::get_key_results,
+            commands::okrs::create_key_result,
+            commands::okrs::update_key_result,
+            commands::okrs::delete_key_result,
+            commands::okrs::grade_objective,
+            commands::okrs::grade_okr_period,
+            commands::okrs::reopen_objective,
+            commands::okrs::get_okr_report,
             commands::notification_plugins::get_notification_plugins,
             commands::notification_plugins::create_notification_plugin,
             commands::notification_plugins::update_notification_plugin,
@@ -129,6 +201,47 @@ pub fn run() {
             commands::batch::bulk_update_step_status,
             commands::batch::bulk_delete_todos,
             commands::batch::bulk_delete_tasks,
+            commands::dependencies::add_dependency,
+            commands::dependencies::remove_dependency,
+            commands::dependencies::get_blockers,
+            commands::dependencies::get_dependents,
+            commands::schedule::get_plan_schedule,
+            commands::snooze::snooze,
+            commands::snooze::unsnooze,
+            commands::quick_add::quick_add,
+            commands::todo_policies::get_todo_policy_settings,
+            commands::todo_policies::update_todo_policy_settings,
+            commands::todo_policies::run_todo_policies,
+            commands::attachments::add_attachment,
+            commands::attachments::get_attachments,
+            commands::attachments::open_attachment,
+            commands::attachments::remove_attachment,
+            commands::attachments::gc_attachments,
+            commands::comments::add_comment,
+            commands::comments::get_comments,
+            commands::comments::update_comment,
+            commands::comments::delete_comment,
+            commands::history::get_entity_history,
+            commands::time_tracking::start_timer,
+            commands::time_tracking::pause_timer,
+            commands::time_tracking::resume_timer,
+            commands::time_tracking::stop_timer,
+            commands::time_tracking::get_active_timer,
+            commands::time_tracking::get_time_entries,
+            commands::time_tracking::create_time_entry,
+            commands::time_tracking::update_time_entry,
+            commands::time_tracking::delete_time_entry,
+            commands::time_tracking::get_time_total,
+            commands::pomodoro::get_pomodoro_settings,
+            commands::pomodoro::update_pomodoro_settings,
+            commands::pomodoro::get_pomodoro_status,
+            commands::pomodoro::start_pomodoro,
+            commands::pomodoro::pause_pomodoro,
+            commands::pomodoro::resume_pomodoro,
+            commands::pomodoro::skip_pomodoro,
+            commands::pomodoro::abandon_pomodoro,
+            commands::pomodoro::get_pomodoro_sessions,
+            commands::estimates::get_estimate_report,
         ])
         .run(tauri::generate_context!())
         .expect("Error while running tauri application");
@@ -18,20 +18,39 @@ pub struct Plan {
     pub status: String, // active | completed | archived
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub progress: i32, // Stored (0-100), recomputed when tasks change
+    #[serde(default)]
+    pub weighted_progress: bool, // Weight tasks by priority (P0=4 .. P3=1)
+    #[serde(default)]
+    pub auto_complete: bool, // Mark completed when all tasks are done
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct Task {
     pub id: String,
     pub plan_id: String,
+    #[serde(default)]
+    pub parent_task_id: Option<String>, // Subtask of this task (same plan)
     pub title: String,
     pub description: Option<String>,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
-    pub status: String,   // pending | in-progress | done
-    pub priority: String, // P0 | P1 | P2 | P3 (default P2)
+    pub defer_until: Option<String>, // Snoozed until this date
+    pub status: String,              // pending | in-progress | done, or a plan workflow status
+    pub priority: String,            // P0 | P1 | P2 | P3 (default P2)
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub estimate: Option<f64>, // Effort estimate in estimate_unit
+    #[serde(default)]
+    pub estimate_unit: Option<String>, // minutes | points
+    #[serde(default)]
+    pub position: Option<String>, // Manual order key (fractional index)
+    #[serde(default)]
+    pub sprint_id: Option<String>, // Sprint of the same plan, None = backlog
+    #[serde(default)]
+    pub blocked: bool, // Calculated from unfinished blockers
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -41,9 +60,21 @@ pub struct Target {
     pub description: Option<String>,
     pub due_date: Option<String>,
     pub status: String, // active | completed | archived
-    pub progress: i32,  // Calculated from Steps (0-100)
+    pub progress: i32,  // Calculated from Steps, or from the metric value (0-100)
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub metric_unit: Option<String>, // Metric mode: unit of the measured value
+    #[serde(default)]
+    pub metric_start: Option<f64>,
+    #[serde(default)]
+    pub metric_goal: Option<f64>, // Set = metric mode
+    #[serde(default)]
+    pub metric_direction: Option<String>, // increase | decrease
+    #[serde(default)]
+    pub metric_value: Option<f64>, // Latest recorded value (calculated)
+    #[serde(default)]
+    pub weight_mode: String, // Step weights: manual | equal | proportional
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -56,6 +87,12 @@ pub struct Step {
     pub priority: String, // P0 | P1 | P2 | P3 (default P2)
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub estimate: Option<f64>, // Effort estimate in estimate_unit
+    #[serde(default)]
+    pub estimate_unit: Option<String>, // minutes | points
+    #[serde(default)]
+    pub position: Option<String>, // Manual order key (fractional index)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -64,10 +101,21 @@ pub struct Todo {
     pub title: String,
     pub content: Option<String>,
     pub due_date: Option<String>,
-    pub status: String,   // pending | in-progress | done
-    pub priority: String, // P0 | P1 | P2 | P3 (default P2)
+    pub defer_until: Option<String>, // Hidden from default views until this date
+    pub status: String,              // pending | in-progress | done | archived
+    pub priority: String,            // P0 | P1 | P2 | P3 (default P2)
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub estimate: Option<f64>, // Effort estimate in estimate_unit
+    #[serde(default)]
+    pub estimate_unit: Option<String>, // minutes | points
+    #[serde(default)]
+    pub rollover_count: i32, // Times rolled forward to today while overdue
+    #[serde(default)]
+    pub position: Option<String>, // Manual order key (fractional index)
+    #[serde(default)]
+    pub blocked: bool, // Calculated from unfinished blockers
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -84,6 +132,184 @@ pub struct Milestone {
     pub updated_at: String,
 }
 
+// Dependency - 依赖 (blocker must be done before blocked can start)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Dependency {
+    pub id: String,
+    pub blocker_type: String, // 'todo' | 'task'
+    pub blocker_id: String,
+    pub blocked_type: String, // 'todo' | 'task'
+    pub blocked_id: String,
+    pub created_at: String,
+}
+
+// Attachment - 附件 (file copied into the app data dir)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Attachment {
+    pub id: String,
+    pub entity_type: String, // 'todo' | 'task' | 'plan' | 'target'
+    pub entity_id: String,
+    pub file_name: String,    // Original file name
+    pub stored_name: String,  // File name inside the attachments dir
+    pub content_hash: String, // SHA-256 of the content
+    pub size: i64,
+    pub created_at: String,
+}
+
+// Comment - 评论 (notes timeline on an entity)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Comment {
+    pub id: String,
+    pub entity_type: String, // 'todo' | 'task' | 'plan' | 'target'
+    pub entity_id: String,
+    pub body: String, // Markdown
+    pub created_at: String,
+    pub edited_at: Option<String>,
+}
+
+// TimeEntry - 计时 (tracked time on a todo or task)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct TimeEntry {
+    pub id: String,
+    pub entity_type: String, // 'todo' | 'task'
+    pub entity_id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub running_since: Option<String>, // Start of the current segment while running
+    pub duration_seconds: i64,         // Accumulated time of finished segments
+    pub status: String,                // running | paused | stopped
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// PomodoroSession - 番茄钟 (finished work phase, optionally on a todo or task)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct PomodoroSession {
+    pub id: String,
+    pub entity_type: Option<String>, // 'todo' | 'task'
+    pub entity_id: Option<String>,
+    pub started_at: String,
+    pub ended_at: String,
+    pub planned_minutes: i64,
+    pub focus_seconds: i64,
+    pub status: String, // completed | skipped | abandoned
+    pub created_at: String,
+}
+
+// PlanTemplate - 计划模板 (plan, tasks and milestones with relative dates)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct PlanTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String, // JSON, see commands::plan_templates::PlanTemplateContent
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// WorkflowStatus - 工作流状态 (custom task status of a plan)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct WorkflowStatus {
+    pub id: String,
+    pub plan_id: String,
+    pub name: String,
+    pub category: String, // todo | doing | done
+    pub position: i32,
+    pub created_at: String,
+}
+
+// WorkflowTransition - 工作流流转 (allowed status change within a plan)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct WorkflowTransition {
+    pub id: String,
+    pub plan_id: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub created_at: String,
+}
+
+// TargetValue - 指标记录 (measurement of a metric target)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct TargetValue {
+    pub id: String,
+    pub target_id: String,
+    pub value: f64,
+    pub note: Option<String>,
+    pub recorded_at: String,
+    pub created_at: String,
+}
+
+// Sprint - 迭代 (time-boxed iteration inside a plan)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Sprint {
+    pub id: String,
+    pub plan_id: String,
+    pub name: String,
+    pub goal: Option<String>,
+    pub start_date: String,
+    pub end_date: String,
+    pub status: String, // planned | active | closed
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// StatusChange - 状态记录 (task / step status change, for burndown charts)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct StatusChange {
+    pub id: String,
+    pub entity_type: String, // 'task' | 'step'
+    pub entity_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub category: String, // todo | doing | done, at the time of the change
+    pub changed_at: String,
+}
+
+// StepLink - 步骤关联 (todo, task or plan whose completion drives a step)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct StepLink {
+    pub id: String,
+    pub step_id: String,
+    pub entity_type: String, // 'todo' | 'task' | 'plan'
+    pub entity_id: String,
+    pub created_at: String,
+}
+
+// Objective - 目标 (OKR objective of a period, e.g. 2026-Q3)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Objective {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub period: String,            // YYYY-Qn | YYYY-Hn | YYYY
+    pub parent_id: Option<String>, // Aligned to this objective
+    pub status: String,            // active | graded
+    pub final_score: Option<f64>,  // Locked when graded (0.0-1.0)
+    pub grade_note: Option<String>,
+    pub graded_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// KeyResult - 关键结果 (scored 0.0-1.0, from a metric or by hand)
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct KeyResult {
+    pub id: String,
+    pub objective_id: String,
+    pub title: String,
+    pub kind: String, // metric | manual
+    pub unit: Option<String>,
+    pub start_value: Option<f64>, // Metric: score 0.0 at start, 1.0 at target
+    pub target_value: Option<f64>,
+    pub current_value: Option<f64>,
+    pub score: Option<f64>,       // Manual score
+    pub confidence: f64,          // 0.0-1.0
+    pub final_score: Option<f64>, // Locked when the objective is graded
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 // Circulation - 打卡
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct Circulation {
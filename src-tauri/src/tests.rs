@@ -15,6 +15,9 @@ mod tests {
             status: "active".to_string(),
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            progress: 0,
+            weighted_progress: false,
+            auto_complete: false,
         };
         assert_eq!(plan.status, "active");
     }
@@ -26,10 +29,16 @@ mod tests {
             title: "Test".to_string(),
             content: None,
             due_date: None,
+            defer_until: None,
             status: "pending".to_string(),
             priority: "P2".to_string(),
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            estimate: None,
+            estimate_unit: None,
+            position: None,
+            rollover_count: 0,
+            blocked: false,
         };
         assert_eq!(todo.status, "pending");
     }
@@ -39,14 +48,21 @@ mod tests {
         let task = Task {
             id: "t1".to_string(),
             plan_id: "p1".to_string(),
+            parent_task_id: None,
             title: "Test".to_string(),
             description: None,
             start_date: None,
             end_date: None,
+            defer_until: None,
             status: "pending".to_string(),
             priority: "P2".to_string(),
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            estimate: None,
+            estimate_unit: None,
+            position: None,
+            sprint_id: None,
+            blocked: false,
         };
         assert_eq!(task.plan_id, "p1");
     }
@@ -62,6 +78,12 @@ mod tests {
             progress: 0,
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            metric_unit: None,
+            metric_start: None,
+            metric_goal: None,
+            metric_direction: None,
+            metric_value: None,
+            weight_mode: "manual".to_string(),
         };
         assert_eq!(target.progress, 0);
     }
@@ -77,6 +99,9 @@ mod tests {
             priority: "P2".to_string(),
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            estimate: None,
+            estimate_unit: None,
+            position: None,
         };
         assert_eq!(step.weight, 25);
     }
@@ -118,7 +143,24 @@ mod tests {
             "milestones",
             "notification_settings",
             "daily_summary_settings",
+            "todo_policy_settings",
             "notification_plugins",
+            "dependencies",
+            "attachments",
+            "comments",
+            "time_entries",
+            "pomodoro_sessions",
+            "pomodoro_settings",
+            "workload_settings",
+            "plan_templates",
+            "workflow_statuses",
+            "workflow_transitions",
+            "step_links",
+            "objectives",
+            "key_results",
+            "target_values",
+            "sprints",
+            "status_history",
         ];
 
         for table in tables {
@@ -650,4 +692,1763 @@ mod tests {
             .unwrap();
         assert_eq!(status, "done");
     }
+
+    // Dependency tests
+    fn insert_dependency(
+        conn: &Connection,
+        id: &str,
+        blocker: (&str, &str),
+        blocked: (&str, &str),
+    ) {
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![id, blocker.0, blocker.1, blocked.0, blocked.1, &now],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_dependency_blocks_until_done() {
+        use crate::commands::dependencies::{check_can_start, find_unblocked, is_blocked};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO todos (id, title, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params!["dep-a", "A", "pending", &now, &now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params!["dep-plan", "Plan", "active", &now, &now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tasks (id, plan_id, title, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params!["dep-b", "dep-plan", "B", "pending", &now, &now],
+        )
+        .unwrap();
+        insert_dependency(&conn, "dep-1", ("todo", "dep-a"), ("task", "dep-b"));
+
+        assert!(is_blocked(&conn, "task", "dep-b").unwrap());
+        assert!(!is_blocked(&conn, "todo", "dep-a").unwrap());
+        assert!(check_can_start(&conn, "task", "dep-b", "in-progress", false).is_err());
+        assert!(check_can_start(&conn, "task", "dep-b", "in-progress", true).is_ok());
+
+        conn.execute("UPDATE todos SET status = 'done' WHERE id = 'dep-a'", [])
+            .unwrap();
+
+        assert!(!is_blocked(&conn, "task", "dep-b").unwrap());
+        let unblocked = find_unblocked(&conn, "todo", "dep-a").unwrap();
+        assert_eq!(unblocked.len(), 1);
+        assert_eq!(unblocked[0].entity_id, "dep-b");
+    }
+
+    #[test]
+    fn test_dependency_cycle_detection() {
+        use crate::commands::dependencies::would_create_cycle;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+
+        insert_dependency(&conn, "cyc-1", ("todo", "a"), ("todo", "b"));
+        insert_dependency(&conn, "cyc-2", ("todo", "b"), ("task", "c"));
+
+        assert!(would_create_cycle(&conn, ("task", "c"), ("todo", "a")).unwrap());
+        assert!(would_create_cycle(&conn, ("todo", "a"), ("todo", "a")).unwrap());
+        assert!(!would_create_cycle(&conn, ("todo", "a"), ("task", "c")).unwrap());
+    }
+
+    // Snooze tests
+    #[test]
+    fn test_parse_snooze_duration() {
+        use crate::commands::snooze::{parse_snooze_duration, snooze_until};
+
+        assert_eq!(
+            parse_snooze_duration("30m").unwrap(),
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_snooze_duration("2h").unwrap(),
+            chrono::Duration::hours(2)
+        );
+        assert_eq!(
+            parse_snooze_duration("1d").unwrap(),
+            chrono::Duration::days(1)
+        );
+        assert_eq!(
+            parse_snooze_duration("1w").unwrap(),
+            chrono::Duration::weeks(1)
+        );
+        assert!(parse_snooze_duration("").is_err());
+        assert!(parse_snooze_duration("0d").is_err());
+        assert!(parse_snooze_duration("3y").is_err());
+        assert!(parse_snooze_duration("999999999999999w").is_err());
+        let far = parse_snooze_duration("99999999w").unwrap();
+        assert!(snooze_until(chrono::Utc::now(), far, "99999999w").is_err());
+    }
+
+    #[test]
+    fn test_deferred_todos_hidden() {
+        use crate::commands::todos::list_todos;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let now = chrono::Utc::now();
+        let future = (now + chrono::Duration::days(2)).to_rfc3339();
+        let past = (now - chrono::Duration::days(2)).to_rfc3339();
+        let now = now.to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO todos (id, title, status, defer_until, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params!["defer-1", "Later", "pending", &future, &now, &now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO todos (id, title, status, defer_until, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params!["defer-2", "Now", "pending", &past, &now, &now],
+        )
+        .unwrap();
+
+        let ids = |include_deferred: bool| -> Vec<String> {
+            list_todos(&conn, include_deferred, false)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.id)
+                .filter(|id| id.starts_with("defer-"))
+                .collect()
+        };
+        assert_eq!(ids(false), vec!["defer-2".to_string()]);
+        assert_eq!(ids(true).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_quick_add() {
+        use crate::commands::quick_add::parse_quick_add;
+
+        // Wednesday
+        let now = chrono::NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+
+        let parsed = parse_quick_add("明天下午3点 准备周报 #工作 !P1", now);
+        assert_eq!(parsed.title, "准备周报");
+        assert!(parsed
+            .due_date
+            .as_deref()
+            .unwrap()
+            .starts_with("2026-10-15T15:00:00"));
+        assert_eq!(parsed.priority.as_deref(), Some("P1"));
+        assert_eq!(parsed.tags, vec!["工作".to_string()]);
+        assert!(parsed.recurrence.is_none());
+
+        let parsed = parse_quick_add("Pay rent every friday @plan:Home #bills", now);
+        assert_eq!(parsed.title, "Pay rent");
+        assert_eq!(parsed.plan.as_deref(), Some("Home"));
+        let recurrence = parsed.recurrence.unwrap();
+        assert_eq!(recurrence.frequency, "weekly");
+        assert_eq!(recurrence.weekdays, vec![5]);
+
+        let parsed = parse_quick_add("Fix issue #123 next monday", now);
+        assert_eq!(parsed.title, "Fix issue #123");
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.due_date.as_deref(), Some("2026-10-19"));
+
+        let parsed = parse_quick_add("call mom tomorrow at 3:30pm", now);
+        assert_eq!(parsed.title, "call mom");
+        assert!(parsed
+            .due_date
+            .as_deref()
+            .unwrap()
+            .starts_with("2026-10-15T15:30:00"));
+
+        let parsed = parse_quick_add("每周五 写周报", now);
+        assert_eq!(parsed.title, "写周报");
+        assert_eq!(parsed.recurrence.unwrap().weekdays, vec![5]);
+
+        let parsed = parse_quick_add("周五晚上8点半 聚餐", now);
+        assert_eq!(parsed.title, "聚餐");
+        assert!(parsed
+            .due_date
+            .as_deref()
+            .unwrap()
+            .starts_with("2026-10-16T20:30:00"));
+
+        // Morning times keep their local day, whatever the UTC offset
+        let parsed = parse_quick_add("明天上午7点 跑步", now);
+        assert_eq!(parsed.due_date.as_deref(), Some("2026-10-15T07:00:00"));
+        let conn = Connection::open_in_memory().unwrap();
+        let day: String = conn
+            .query_row("SELECT date(?)", [parsed.due_date.unwrap()], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(day, "2026-10-15");
+
+        let parsed = parse_quick_add("renew passport in 2 weeks", now);
+        assert_eq!(parsed.due_date.as_deref(), Some("2026-10-28"));
+
+        // Amounts too large for a date leave the words in the title
+        let parsed = parse_quick_add("later in 9999999999999999 weeks", now);
+        assert!(parsed.due_date.is_none());
+        assert_eq!(parsed.title, "later in 9999999999999999 weeks");
+    }
+
+    #[test]
+    fn test_todo_policies_archive_and_rollover() {
+        use crate::commands::todo_policies::apply_todo_policies;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute("DELETE FROM todos", []).unwrap();
+        // Both policies are opt-in
+        let enabled: (bool, bool) = conn
+            .query_row(
+                "SELECT auto_archive_enabled, rollover_enabled FROM todo_policy_settings",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(enabled, (false, false));
+        conn.execute(
+            "UPDATE todo_policy_settings SET auto_archive_enabled = 1, archive_after_days = 7, rollover_enabled = 1",
+            [],
+        )
+        .unwrap();
+
+        let now = chrono::Utc::now();
+        let old = (now - chrono::Duration::days(10)).to_rfc3339();
+        let recent = (now - chrono::Duration::days(1)).to_rfc3339();
+        let overdue = (chrono::Local::now() - chrono::Duration::days(3))
+            .format("%Y-%m-%d")
+            .to_string();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+        for (id, status, due, updated) in [
+            ("old-done", "done", None, &old),
+            ("recent-done", "done", None, &recent),
+            ("overdue", "pending", Some(&overdue), &recent),
+            ("overdue-done", "done", Some(&overdue), &recent),
+        ] {
+            conn.execute(
+                "INSERT INTO todos (id, title, status, due_date, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![id, id, status, due, &old, updated],
+            )
+            .unwrap();
+        }
+
+        let result = apply_todo_policies(&conn).unwrap();
+        assert_eq!(result.archived, 1);
+        assert_eq!(result.rolled_over, 1);
+
+        let (status, updated_at): (String, String) = conn
+            .query_row(
+                "SELECT status, updated_at FROM todos WHERE id = 'old-done'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "archived");
+        assert_eq!(updated_at, old);
+
+        let (due_date, rollover_count): (String, i32) = conn
+            .query_row(
+                "SELECT due_date, rollover_count FROM todos WHERE id = 'overdue'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(due_date, today);
+        assert_eq!(rollover_count, 1);
+    }
+
+    #[test]
+    fn test_attachment_dedupe_and_gc() {
+        use crate::commands::attachments::{collect_garbage, store_file};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+
+        let root = std::env::temp_dir().join(format!("plan-todos-test-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("attachments");
+        std::fs::create_dir_all(&root).unwrap();
+        let a = root.join("a.PNG");
+        let b = root.join("b.png");
+        std::fs::write(&a, b"same content").unwrap();
+        std::fs::write(&b, b"same content").unwrap();
+
+        let first = store_file(&dir, &a).unwrap();
+        let second = store_file(&dir, &b).unwrap();
+        assert_eq!(first.stored_name, second.stored_name);
+        assert!(first.stored_name.ends_with(".png"));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Attachment of a todo that no longer exists
+        conn.execute(
+            "INSERT INTO attachments (id, entity_type, entity_id, file_name, stored_name, content_hash, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params!["att-1", "todo", "missing-todo", "a.PNG", first.stored_name, first.content_hash, first.size, "2026-01-01T00:00:00Z"],
+        )
+        .unwrap();
+
+        let result = collect_garbage(&conn, &dir).unwrap();
+        assert_eq!(result.removed_records, 1);
+        assert_eq!(result.removed_files, 1);
+        assert_eq!(result.freed_bytes, 12);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_imported_attachment_names_checked() {
+        use crate::commands::attachments::{is_valid_stored_name, stored_path};
+        use crate::commands::export::ExportDataContent;
+        use crate::commands::import::import_content;
+        use crate::models::Attachment;
+
+        let hash = "a".repeat(64);
+        assert!(is_valid_stored_name(&hash));
+        assert!(is_valid_stored_name(&format!("{}.png", hash)));
+        for name in [
+            "../../x",
+            "/etc/passwd",
+            "abc.png",
+            "",
+            &format!("{}.", hash),
+        ] {
+            assert!(!is_valid_stored_name(name), "{}", name);
+        }
+        assert!(!is_valid_stored_name(&format!("{}.a/../../x", hash)));
+        assert!(stored_path(std::path::Path::new("/tmp"), "../x").is_err());
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let attachment = |id: &str, stored_name: &str| Attachment {
+            id: id.to_string(),
+            entity_type: "todo".to_string(),
+            entity_id: "t1".to_string(),
+            file_name: "x".to_string(),
+            stored_name: stored_name.to_string(),
+            content_hash: hash.clone(),
+            size: 1,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let data = ExportDataContent {
+            attachments: vec![attachment("ok", &hash), attachment("bad", "../../x")],
+            ..Default::default()
+        };
+        for mode in ["merge", "replace", "update"] {
+            let result = import_content(&conn, &data, mode).unwrap();
+            assert_eq!(result.errors.len(), 1, "{}", mode);
+            let stored: Vec<String> = conn
+                .prepare("SELECT id FROM attachments")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(stored, vec!["ok".to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_comments_timeline() {
+        use crate::commands::comments::{delete_entity_comments, list_comments};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+
+        for (id, body, created_at) in [
+            ("c-2", "Second", "2026-01-02T00:00:00Z"),
+            ("c-1", "First **note**", "2026-01-01T00:00:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO comments (id, entity_type, entity_id, body, created_at) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![id, "plan", "plan-1", body, created_at],
+            )
+            .unwrap();
+        }
+
+        let comments = list_comments(&conn, "plan", "plan-1").unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, "c-1");
+        assert!(comments[0].edited_at.is_none());
+
+        delete_entity_comments(&conn, "plan", "plan-1").unwrap();
+        assert!(list_comments(&conn, "plan", "plan-1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_time_entry_totals() {
+        use crate::commands::time_tracking::{active_timer, stop_entry, ENTRY_SECONDS_SQL};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let now = chrono::Utc::now();
+        let minute_ago = (now - chrono::Duration::seconds(60)).to_rfc3339();
+        let now = now.to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, created_at, updated_at)
+             VALUES ('te-1', 'task', 'task-1', ?1, ?1, NULL, 600, 'stopped', ?1, ?1)",
+            [&now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, created_at, updated_at)
+             VALUES ('te-2', 'task', 'task-1', ?1, NULL, ?1, 30, 'running', ?1, ?1)",
+            [&minute_ago],
+        )
+        .unwrap();
+
+        let total: i64 = conn
+            .query_row(
+                &format!(
+                    "SELECT SUM({}) FROM time_entries WHERE entity_id = 'task-1'",
+                    ENTRY_SECONDS_SQL
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!((690..700).contains(&total));
+
+        let active = active_timer(&conn).unwrap().unwrap();
+        assert_eq!(active.id, "te-2");
+        stop_entry(&conn, &active).unwrap();
+        assert!(active_timer(&conn).unwrap().is_none());
+
+        let duration: i64 = conn
+            .query_row(
+                "SELECT duration_seconds FROM time_entries WHERE id = 'te-2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!((90..100).contains(&duration));
+    }
+
+    #[test]
+    fn test_pomodoro_cycle() {
+        use crate::commands::pomodoro::{load_settings, PomodoroEngine, MAX_PHASE_MINUTES};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let mut settings = load_settings(&conn).unwrap();
+        assert_eq!(settings.work_minutes, 25);
+        settings.long_break_interval = 2;
+
+        let mut engine = PomodoroEngine::default();
+        let t0 = chrono::Utc::now();
+        let at = |minutes: i64| t0 + chrono::Duration::minutes(minutes);
+
+        // Work, paused for 10 minutes in the middle
+        engine
+            .start(&settings, Some(("task".into(), "task-1".into())), t0)
+            .unwrap();
+        assert!(engine.start(&settings, None, t0).is_err());
+        engine.pause(at(10)).unwrap();
+        engine.resume(at(20)).unwrap();
+        assert!(engine.tick(&settings, at(30)).is_none());
+
+        let (event, finished) = engine.tick(&settings, at(35)).unwrap();
+        assert_eq!(event.previous_phase, "work");
+        assert_eq!(event.status.phase, "short_break");
+        assert_eq!(event.status.state, "running");
+        let finished = finished.unwrap();
+        assert_eq!(finished.status, "completed");
+        assert_eq!(finished.focus_seconds, 25 * 60);
+        assert_eq!(finished.entity_id.as_deref(), Some("task-1"));
+
+        // Break ends, next work waits for start
+        let (event, finished) = engine.tick(&settings, at(40)).unwrap();
+        assert_eq!(event.status.phase, "work");
+        assert_eq!(event.status.state, "idle");
+        assert!(finished.is_none());
+
+        // Second completed work session triggers the long break
+        engine.start(&settings, None, at(40)).unwrap();
+        let (event, _) = engine.tick(&settings, at(65)).unwrap();
+        assert_eq!(event.status.phase, "long_break");
+        assert_eq!(event.status.completed_work_sessions, 2);
+
+        // Skipping the long break resets the cadence
+        let (event, _) = engine.skip(&settings, at(66));
+        assert_eq!(event.reason, "skipped");
+        assert_eq!(event.status.completed_work_sessions, 0);
+
+        // Abandoned work keeps the partial focus time
+        engine.start(&settings, None, at(70)).unwrap();
+        let (event, finished) = engine.abandon(&settings, at(75));
+        assert_eq!(event.status.state, "idle");
+        let finished = finished.unwrap();
+        assert_eq!(finished.status, "abandoned");
+        assert_eq!(finished.focus_seconds, 5 * 60);
+
+        // Stored lengths are capped at a day; a phase past the end of time is refused
+        settings.work_minutes = i64::MAX;
+        let mut engine = PomodoroEngine::default();
+        engine.start(&settings, None, t0).unwrap();
+        assert_eq!(engine.status(t0).remaining_seconds, MAX_PHASE_MINUTES * 60);
+        let mut engine = PomodoroEngine::default();
+        assert!(engine
+            .start(&settings, None, chrono::DateTime::<chrono::Utc>::MAX_UTC)
+            .is_err());
+    }
+
+    #[test]
+    fn test_estimate_report() {
+        use crate::commands::estimates::{build_estimate_report, normalize_estimate};
+
+        assert_eq!(
+            normalize_estimate(Some(30.0), None).unwrap().as_deref(),
+            Some("minutes")
+        );
+        assert!(normalize_estimate(Some(-1.0), None).is_err());
+        assert!(normalize_estimate(Some(3.0), Some("hours".into())).is_err());
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p1', 'Launch', 'active', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        // 60 min estimate, done after 90 min; 3 points, done after 2 hours; one unfinished
+        conn.execute_batch(
+            "INSERT INTO tasks (id, plan_id, title, status, priority, estimate, estimate_unit, created_at, updated_at) VALUES
+                ('t1', 'p1', 'A', 'done', 'P1', 60, 'minutes', '2026-01-01T09:00:00Z', '2026-01-01T10:30:00Z'),
+                ('t2', 'p1', 'B', 'done', 'P2', 3, 'points', '2026-01-01T09:00:00Z', '2026-01-01T11:00:00Z'),
+                ('t3', 'p1', 'C', 'pending', 'P1', 30, 'minutes', '2026-01-01T09:00:00Z', '2026-01-01T09:00:00Z');
+             INSERT INTO todos (id, title, status, priority, estimate, estimate_unit, created_at, updated_at) VALUES
+                ('d1', 'D', 'done', 'P1', 30, 'minutes', '2026-01-01T09:00:00Z', '2026-01-01T09:30:00Z');",
+        )
+        .unwrap();
+
+        let report = build_estimate_report(&conn).unwrap();
+        assert_eq!(report.by_plan.len(), 1);
+        let plan = &report.by_plan[0];
+        assert_eq!(plan.label, "Launch");
+        assert_eq!(plan.items, 2);
+        assert!((plan.accuracy_ratio.unwrap() - 1.5).abs() < 0.01);
+        assert!((plan.minutes_per_point.unwrap() - 40.0).abs() < 0.01);
+
+        let p1 = report.by_priority.iter().find(|g| g.key == "P1").unwrap();
+        assert_eq!(p1.items, 2);
+        assert!((p1.estimated_minutes - 90.0).abs() < 0.01);
+        assert!((p1.actual_minutes - 120.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_plan_progress_refresh() {
+        use crate::commands::plans::{calculate_plan_progress, refresh_plan_progress};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, auto_complete, created_at, updated_at) VALUES
+                ('p1', 'Plan', 'active', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z');
+             INSERT INTO tasks (id, plan_id, title, status, priority, created_at, updated_at) VALUES
+                ('t1', 'p1', 'A', 'done', 'P0', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+                ('t2', 'p1', 'B', 'pending', 'P3', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z');",
+        )
+        .unwrap();
+
+        assert_eq!(
+            calculate_plan_progress(&conn, "p1", false).unwrap(),
+            (50, false)
+        );
+        assert_eq!(
+            calculate_plan_progress(&conn, "p1", true).unwrap(),
+            (80, false)
+        );
+
+        let plan_state = |conn: &Connection| -> (i32, String) {
+            conn.query_row(
+                "SELECT progress, status FROM plans WHERE id = 'p1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+
+        refresh_plan_progress(&conn, "p1").unwrap();
+        assert_eq!(plan_state(&conn), (50, "active".to_string()));
+
+        conn.execute("UPDATE tasks SET status = 'done' WHERE id = 't2'", [])
+            .unwrap();
+        refresh_plan_progress(&conn, "p1").unwrap();
+        assert_eq!(plan_state(&conn), (100, "completed".to_string()));
+
+        // Reopening a task reopens an auto-completed plan
+        conn.execute("UPDATE tasks SET status = 'pending' WHERE id = 't2'", [])
+            .unwrap();
+        refresh_plan_progress(&conn, "p1").unwrap();
+        assert_eq!(plan_state(&conn), (50, "active".to_string()));
+    }
+
+    #[test]
+    fn test_plan_schedule_critical_path() {
+        use crate::commands::schedule::{compute_schedule, ScheduleLink, ScheduleTaskInput};
+
+        let task = |id: &str, start: Option<&str>, end: Option<&str>| ScheduleTaskInput {
+            id: id.to_string(),
+            title: id.to_string(),
+            status: "pending".to_string(),
+            start_date: start.map(String::from),
+            end_date: end.map(String::from),
+        };
+        let link = |pred: &str, succ: &str| ScheduleLink {
+            dependency_id: format!("{}-{}", pred, succ),
+            predecessor_id: pred.to_string(),
+            successor_id: succ.to_string(),
+        };
+
+        let schedule = compute_schedule(
+            "p1",
+            None,
+            vec![
+                task("a", Some("2026-01-01"), Some("2026-01-03")),
+                task("b", Some("2026-01-04"), Some("2026-01-05T00:00:00Z")),
+                task("c", Some("2026-01-02"), Some("2026-01-02")),
+                task("d", None, None),
+            ],
+            vec![link("a", "b"), link("a", "c")],
+        )
+        .unwrap();
+
+        assert_eq!(schedule.project_start, "2026-01-01");
+        assert_eq!(schedule.project_finish, "2026-01-05");
+        assert_eq!(schedule.critical_path, vec!["a", "b"]);
+
+        let c = schedule.tasks.iter().find(|t| t.task_id == "c").unwrap();
+        assert_eq!(c.earliest_start, "2026-01-04");
+        assert_eq!(c.slack_days, 1);
+        let d = schedule.tasks.iter().find(|t| t.task_id == "d").unwrap();
+        assert_eq!(d.slack_days, 4);
+
+        assert_eq!(schedule.violations.len(), 1);
+        assert_eq!(schedule.violations[0].successor_id, "c");
+        assert_eq!(schedule.violations[0].overlap_days, 2);
+    }
+
+    #[test]
+    fn test_subtask_hierarchy() {
+        use crate::commands::plans::calculate_plan_progress;
+        use crate::commands::subtasks::{
+            delete_task_subtree, rollup_ancestors, validate_parent, MAX_TASK_DEPTH,
+        };
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        // epic -> story -> (sub1, sub2); other is a separate root
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p1', 'Plan', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, parent_task_id, title, status, priority, created_at, updated_at) VALUES
+                ('epic', 'p1', NULL, 'Epic', 'pending', 'P2', 'x', 'x'),
+                ('story', 'p1', 'epic', 'Story', 'pending', 'P2', 'x', 'x'),
+                ('sub1', 'p1', 'story', 'Sub 1', 'pending', 'P2', 'x', 'x'),
+                ('sub2', 'p1', 'story', 'Sub 2', 'pending', 'P2', 'x', 'x'),
+                ('other', 'p1', NULL, 'Other', 'pending', 'P2', 'x', 'x');",
+        )
+        .unwrap();
+        assert_eq!(MAX_TASK_DEPTH, 3);
+
+        // Cycles and depth
+        assert!(validate_parent(&conn, Some("epic"), "sub1").is_err());
+        assert!(validate_parent(&conn, None, "sub1").is_err());
+        assert!(validate_parent(&conn, Some("other"), "story").is_ok());
+        assert!(validate_parent(&conn, Some("epic"), "other").is_err());
+
+        let status = |id: &str| -> String {
+            conn.query_row("SELECT status FROM tasks WHERE id = ?", [id], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+
+        conn.execute("UPDATE tasks SET status = 'done' WHERE id = 'sub1'", [])
+            .unwrap();
+        rollup_ancestors(&conn, "sub1").unwrap();
+        assert_eq!(status("story"), "in-progress");
+        assert_eq!(status("epic"), "in-progress");
+
+        conn.execute("UPDATE tasks SET status = 'done' WHERE id = 'sub2'", [])
+            .unwrap();
+        rollup_ancestors(&conn, "sub2").unwrap();
+        assert_eq!(status("story"), "done");
+        assert_eq!(status("epic"), "done");
+
+        // Only leaves count towards plan progress: sub1, sub2 done, other pending
+        assert_eq!(
+            calculate_plan_progress(&conn, "p1", false).unwrap(),
+            (66, false)
+        );
+
+        conn.execute_batch(
+            "INSERT INTO time_entries (id, entity_type, entity_id, started_at, ended_at, running_since, duration_seconds, status, created_at, updated_at)
+                VALUES ('te-1', 'task', 'sub1', 'x', 'x', NULL, 60, 'stopped', 'x', 'x'),
+                       ('te-2', 'task', 'other', 'x', 'x', NULL, 60, 'stopped', 'x', 'x');
+             INSERT INTO pomodoro_sessions (id, entity_type, entity_id, started_at, ended_at, planned_minutes, focus_seconds, status, created_at)
+                VALUES ('ps-1', 'task', 'sub2', 'x', 'x', 25, 1500, 'completed', 'x');",
+        )
+        .unwrap();
+
+        assert!(delete_task_subtree(&conn, "story").unwrap());
+        let count = |sql: &str| -> i32 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM tasks WHERE plan_id = 'p1'"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM time_entries"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM pomodoro_sessions"), 0);
+    }
+
+    #[test]
+    fn test_imported_task_parents_checked() {
+        use crate::commands::export::ExportDataContent;
+        use crate::commands::import::import_content;
+        use crate::models::{Plan, Task};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let plan = |id: &str| Plan {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: None,
+            start_date: None,
+            end_date: None,
+            status: "active".to_string(),
+            created_at: "x".to_string(),
+            updated_at: "x".to_string(),
+            progress: 0,
+            weighted_progress: false,
+            auto_complete: false,
+        };
+        let task = |id: &str, plan_id: &str, parent: Option<&str>| Task {
+            id: id.to_string(),
+            plan_id: plan_id.to_string(),
+            parent_task_id: parent.map(|p| p.to_string()),
+            title: id.to_string(),
+            description: None,
+            start_date: None,
+            end_date: None,
+            defer_until: None,
+            status: "pending".to_string(),
+            priority: "P2".to_string(),
+            created_at: "x".to_string(),
+            updated_at: "x".to_string(),
+            estimate: None,
+            estimate_unit: None,
+            position: None,
+            sprint_id: None,
+            blocked: false,
+        };
+        // A cycle, a parent in another plan, a missing parent and a chain
+        // one level too deep (d1 -> d2 -> d3 -> d4)
+        let data = ExportDataContent {
+            plans: vec![plan("p1"), plan("p2")],
+            tasks: vec![
+                task("a", "p1", Some("b")),
+                task("b", "p1", Some("a")),
+                task("x", "p2", Some("a")),
+                task("m", "p1", Some("missing")),
+                task("d1", "p1", None),
+                task("d2", "p1", Some("d1")),
+                task("d3", "p1", Some("d2")),
+                task("d4", "p1", Some("d3")),
+            ],
+            ..Default::default()
+        };
+        let parent = |id: &str| -> Option<String> {
+            conn.query_row(
+                "SELECT parent_task_id FROM tasks WHERE id = ?",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        for mode in ["replace", "update", "merge"] {
+            if mode == "merge" {
+                conn.execute_batch("DELETE FROM tasks").unwrap();
+            }
+            let result = import_content(&conn, &data, mode).unwrap();
+            assert_eq!(result.errors.len(), 4, "{}: {:?}", mode, result.errors);
+            assert_eq!(parent("a"), None);
+            assert_eq!(parent("b"), Some("a".to_string()));
+            assert_eq!(parent("x"), None);
+            assert_eq!(parent("m"), None);
+            assert_eq!(parent("d3"), Some("d2".to_string()));
+            assert_eq!(parent("d4"), None);
+        }
+    }
+
+    #[test]
+    fn test_plan_template_roundtrip() {
+        use crate::commands::plan_templates::{capture_plan, instantiate, validate_content};
+        use chrono::NaiveDate;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, start_date, end_date, status, created_at, updated_at) VALUES ('p1', 'Release', '2026-03-01', '2026-03-10', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, parent_task_id, title, start_date, end_date, status, priority, created_at, updated_at) VALUES
+                ('a', 'p1', NULL, 'Freeze', '2026-03-01', '2026-03-02', 'done', 'P1', 'x', 'x'),
+                ('b', 'p1', 'a', 'Notes', '2026-03-03', '2026-03-05', 'pending', 'P2', 'x', 'x');
+             INSERT INTO dependencies (id, blocker_type, blocker_id, blocked_type, blocked_id, created_at) VALUES ('d1', 'task', 'a', 'task', 'b', 'x');
+             INSERT INTO milestones (id, title, target_date, biz_type, biz_id, status, progress, created_at, updated_at) VALUES ('m1', 'Ship', '2026-03-10', 'plan', 'p1', 'pending', 0, 'x', 'x');
+             INSERT INTO tags (id, name, created_at) VALUES ('t1', 'release', 'x');
+             INSERT INTO entity_tags (entity_type, entity_id, tag_id) VALUES ('task', 'b', 't1');",
+        )
+        .unwrap();
+
+        let (content, base) = capture_plan(&conn, "p1").unwrap();
+        assert_eq!(base, NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+        assert_eq!(content.end_offset, Some(9));
+        assert_eq!(content.tasks[1].start_offset, Some(2));
+        assert_eq!(content.tasks[1].tags, vec!["release".to_string()]);
+
+        let start = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let (plan_id, task_ids) = instantiate(&conn, &content, start, None).unwrap();
+        assert_eq!(task_ids.len(), 3); // Two tasks and the milestone
+        assert_ne!(plan_id, "p1");
+
+        let (title, end_date): (String, String) = conn
+            .query_row(
+                "SELECT title, end_date FROM plans WHERE id = ?",
+                [&plan_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(title, "Release");
+        assert_eq!(end_date, "2026-06-10");
+
+        // Fresh tasks keep the hierarchy, start pending and have shifted dates
+        let (child_start, pending, has_parent): (String, i32, i32) = conn
+            .query_row(
+                "SELECT MAX(start_date), SUM(status = 'pending'), COUNT(parent_task_id) FROM tasks WHERE plan_id = ?",
+                [&plan_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(child_start, "2026-06-03");
+        assert_eq!(pending, 2);
+        assert_eq!(has_parent, 1);
+
+        let counts: (i32, i32, i32) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM dependencies), (SELECT COUNT(*) FROM milestones WHERE target_date = '2026-06-10'), (SELECT COUNT(*) FROM entity_tags WHERE tag_id = 't1')",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(counts, (2, 1, 2));
+
+        // Out-of-range offsets and broken hierarchies are rejected
+        let mut bad = content.clone();
+        bad.tasks[1].end_offset = Some(i64::MAX);
+        assert!(validate_content(&bad).is_err());
+        assert!(instantiate(&conn, &bad, start, None).is_err());
+        let mut bad = content.clone();
+        bad.tasks[0].parent_key = Some(bad.tasks[1].key.clone());
+        assert!(validate_content(&bad).is_err());
+        let mut deep = content.clone();
+        for (i, parent) in [(2, 1), (3, 2)] {
+            let mut task = deep.tasks[1].clone();
+            task.key = format!("k{}", i);
+            task.parent_key = Some(deep.tasks[parent].key.clone());
+            deep.tasks.push(task);
+        }
+        assert!(validate_content(&deep).is_err());
+        deep.tasks.pop();
+        assert!(validate_content(&deep).is_ok());
+
+        // Self and circular dependencies are rejected
+        let mut bad = content.clone();
+        let mut reverse = bad.dependencies[0].clone();
+        std::mem::swap(&mut reverse.blocker_key, &mut reverse.blocked_key);
+        bad.dependencies.push(reverse);
+        assert!(validate_content(&bad).is_err());
+        let mut bad = content.clone();
+        bad.dependencies[0].blocked_key = bad.dependencies[0].blocker_key.clone();
+        assert!(validate_content(&bad).is_err());
+    }
+
+    #[test]
+    fn test_plan_workflow() {
+        use crate::commands::plans::calculate_plan_progress;
+        use crate::commands::workflows::{
+            conform_statuses, save_workflow, task_categories, validate_transition,
+            WorkflowStatusInput, WorkflowTransitionInput,
+        };
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p1', 'Board', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, created_at, updated_at) VALUES
+                ('a', 'p1', 'A', 'pending', 'P2', 'x', 'x'),
+                ('b', 'p1', 'B', 'in-progress', 'P2', 'x', 'x'),
+                ('c', 'p1', 'C', 'done', 'P2', 'x', 'x');",
+        )
+        .unwrap();
+
+        let status = |name: &str, category: &str| WorkflowStatusInput {
+            name: name.to_string(),
+            category: category.to_string(),
+        };
+        let transition = |from: &str, to: &str| WorkflowTransitionInput {
+            from_status: from.to_string(),
+            to_status: to.to_string(),
+        };
+        let statuses = vec![
+            status("Backlog", "todo"),
+            status("Ready", "todo"),
+            status("Doing", "doing"),
+            status("Review", "doing"),
+            status("Done", "done"),
+        ];
+        let transitions = vec![
+            transition("Backlog", "Ready"),
+            transition("Ready", "Doing"),
+            transition("Doing", "Review"),
+            transition("Review", "Done"),
+        ];
+
+        // Every category needs a status
+        assert!(save_workflow(&conn, "p1", &statuses[..2], &[]).is_err());
+        // Names are compared trimmed
+        let mut padded = vec![status("Done ", "done")];
+        padded.extend(statuses.iter().map(|s| status(&s.name, &s.category)));
+        assert!(save_workflow(&conn, "p1", &padded, &[]).is_err());
+        assert!(save_workflow(&conn, "p1", &statuses, &[transition(" Backlog", "Ready ")]).is_ok());
+        let workflow = save_workflow(&conn, "p1", &statuses, &transitions).unwrap();
+        assert_eq!(workflow.statuses.len(), 5);
+
+        // Existing tasks move to the first status of their category
+        let task_status = |id: &str| -> String {
+            conn.query_row("SELECT status FROM tasks WHERE id = ?", [id], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(task_status("a"), "Backlog");
+        assert_eq!(task_status("b"), "Doing");
+        assert_eq!(task_status("c"), "Done");
+
+        assert!(validate_transition(&conn, "p1", "Backlog", "Ready").is_ok());
+        assert!(validate_transition(&conn, "p1", "Backlog", "Done").is_err());
+        assert!(validate_transition(&conn, "p1", "Review", "pending").is_err());
+
+        // Review counts as doing, Done as done
+        conn.execute("UPDATE tasks SET status = 'Review' WHERE id = 'b'", [])
+            .unwrap();
+        assert_eq!(
+            calculate_plan_progress(&conn, "p1", false).unwrap(),
+            (33, false)
+        );
+
+        // Without the workflow, tasks go back to the built-in statuses
+        let categories = task_categories(&conn, "p1").unwrap();
+        conn.execute("DELETE FROM workflow_statuses WHERE plan_id = 'p1'", [])
+            .unwrap();
+        conform_statuses(&conn, "p1", &categories).unwrap();
+        assert_eq!(task_status("a"), "pending");
+        assert_eq!(task_status("b"), "in-progress");
+        assert_eq!(task_status("c"), "done");
+    }
+
+    #[test]
+    fn test_manual_ordering() {
+        use crate::commands::ordering::{
+            backfill_positions, clear_invalid_positions, key_between, next_position, reorder_item,
+        };
+
+        assert_eq!(key_between(None, None).unwrap(), "a0");
+        assert_eq!(key_between(Some("a0"), None).unwrap(), "a1");
+        assert_eq!(key_between(None, Some("a0")).unwrap(), "Zz");
+        assert_eq!(key_between(Some("a0"), Some("a1")).unwrap(), "a0V");
+        assert!(key_between(Some("a1"), Some("a0")).is_err());
+
+        // Repeated inserts at the same spot keep sorting correctly
+        let mut upper = "a1".to_string();
+        for _ in 0..50 {
+            let key = key_between(Some("a0"), Some(&upper)).unwrap();
+            assert!(key.as_str() > "a0" && key < upper);
+            upper = key;
+        }
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES
+                ('p1', 'One', 'active', 'x', 'x'), ('p2', 'Two', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, position, created_at, updated_at) VALUES
+                ('a', 'p1', 'A', 'pending', 'P2', 'a0', 'x', 'x'),
+                ('b', 'p1', 'B', 'pending', 'P2', 'a1', 'x', 'x'),
+                ('c', 'p1', 'C', 'pending', 'P2', 'a2', 'x', 'x'),
+                ('x', 'p2', 'X', 'pending', 'P2', 'a0', 'x', 'x');",
+        )
+        .unwrap();
+        assert_eq!(next_position(&conn, "task", Some("p1")).unwrap(), "a3");
+
+        let order = |plan_id: &str| -> Vec<String> {
+            let mut stmt = conn
+                .prepare("SELECT id FROM tasks WHERE plan_id = ? ORDER BY position")
+                .unwrap();
+            stmt.query_map([plan_id], |row| row.get(0))
+                .unwrap()
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+
+        // Move c to the top; only c changes, unkeyed items elsewhere stay unkeyed
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p3', 'Three', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, created_at, updated_at) VALUES ('n', 'p3', 'N', 'pending', 'P2', 'x', 'x');",
+        )
+        .unwrap();
+        reorder_item(&conn, "task", "c", None, Some("a"), None).unwrap();
+        assert_eq!(order("p1"), vec!["c", "a", "b"]);
+        let unkeyed: Option<String> = conn
+            .query_row("SELECT position FROM tasks WHERE id = 'n'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert!(unkeyed.is_none());
+
+        // Move a between x and the end of p2
+        reorder_item(&conn, "task", "a", Some("x"), None, None).unwrap();
+        assert_eq!(order("p1"), vec!["c", "b"]);
+        assert_eq!(order("p2"), vec!["x", "a"]);
+
+        assert!(reorder_item(&conn, "task", "b", Some("x"), Some("c"), None).is_err());
+
+        // Invalid and duplicate keys (e.g. from an import) are re-keyed at the end
+        conn.execute_batch(
+            "UPDATE tasks SET position = (SELECT position FROM tasks WHERE id = 'b') WHERE id = 'c';
+             INSERT INTO tasks (id, plan_id, title, status, priority, position, created_at, updated_at) VALUES
+                ('d', 'p1', 'D', 'pending', 'P2', '../x', 'x', 'x');",
+        )
+        .unwrap();
+        clear_invalid_positions(&conn).unwrap();
+        backfill_positions(&conn).unwrap();
+        assert_eq!(order("p1"), vec!["b", "c", "d"]);
+        assert!(next_position(&conn, "task", Some("p1")).is_ok());
+    }
+
+    #[test]
+    fn test_plan_date_range_and_shift() {
+        use crate::commands::plans::{
+            check_plan_range, check_task_dates, reschedule_delta, shift_plan_dates,
+        };
+        use crate::commands::subtasks::move_task_to;
+        use chrono::NaiveDate;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        // 2026-03-06 is a Friday
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, start_date, end_date, status, created_at, updated_at) VALUES ('p1', 'Plan', '2026-03-06', '2026-03-13', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, start_date, end_date, status, priority, created_at, updated_at) VALUES
+                ('t1', 'p1', 'Task', '2026-03-06', '2026-03-09', 'pending', 'P2', 'x', 'x');
+             INSERT INTO milestones (id, title, target_date, biz_type, biz_id, status, progress, created_at, updated_at) VALUES
+                ('m1', 'Done', '2026-03-13', 'task', 't1', 'pending', 0, 'x', 'x');",
+        )
+        .unwrap();
+
+        assert!(check_task_dates(&conn, "p1", Some("2026-03-06"), Some("2026-03-13")).is_ok());
+        assert!(check_task_dates(&conn, "p1", Some("2026-03-05"), None).is_err());
+        assert!(check_task_dates(&conn, "p1", None, Some("2026-03-14T10:00:00Z")).is_err());
+        assert!(check_task_dates(&conn, "p1", Some("2026-03-10"), Some("2026-03-09")).is_err());
+
+        let dates = || -> (String, String, String, String) {
+            conn.query_row(
+                "SELECT p.start_date, t.start_date, t.end_date, m.target_date
+                 FROM plans p, tasks t, milestones m WHERE p.id = 'p1' AND t.id = 't1' AND m.id = 'm1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap()
+        };
+
+        // One working day after Friday is Monday
+        shift_plan_dates(&conn, "p1", 1, true).unwrap();
+        assert_eq!(
+            dates(),
+            (
+                "2026-03-09".to_string(),
+                "2026-03-09".to_string(),
+                "2026-03-10".to_string(),
+                "2026-03-16".to_string()
+            )
+        );
+
+        let new_start = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+        let delta = reschedule_delta(&conn, "p1", new_start, false).unwrap();
+        assert_eq!(delta, 23);
+        shift_plan_dates(&conn, "p1", delta, false).unwrap();
+        assert_eq!(dates().0, "2026-04-01");
+        assert_eq!(dates().3, "2026-04-08");
+
+        // Long working-day shifts jump whole weeks, in both directions
+        shift_plan_dates(&conn, "p1", 11, true).unwrap();
+        assert_eq!(dates().0, "2026-04-16");
+        shift_plan_dates(&conn, "p1", -11, true).unwrap();
+        assert_eq!(dates().0, "2026-04-01");
+        conn.execute(
+            "UPDATE plans SET start_date = '2026-03-07' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+        shift_plan_dates(&conn, "p1", 6, true).unwrap();
+        assert_eq!(dates().0, "2026-03-16");
+        conn.execute(
+            "UPDATE plans SET start_date = '2026-03-07' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+        shift_plan_dates(&conn, "p1", -6, true).unwrap();
+        assert_eq!(dates().0, "2026-02-27");
+
+        assert!(shift_plan_dates(&conn, "p1", i64::MAX, false).is_err());
+        assert!(shift_plan_dates(&conn, "p1", i64::MIN, true).is_err());
+        assert_eq!(dates().0, "2026-02-27");
+
+        // Narrowing the plan or moving a task into another plan keeps task
+        // dates within range
+        assert!(check_plan_range(&conn, "p1", Some("2030-01-01"), None).is_err());
+        assert!(check_plan_range(&conn, "p1", None, None).is_ok());
+        conn.execute(
+            "INSERT INTO plans (id, title, start_date, end_date, status, created_at, updated_at) VALUES ('p2', 'Later', '2030-01-01', '2030-12-31', 'active', 'x', 'x')",
+            [],
+        )
+        .unwrap();
+        assert!(move_task_to(&conn, "t1", None, Some("p2".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_plan_and_target() {
+        use crate::commands::duplicate::{duplicate_plan_tree, duplicate_target_tree};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, start_date, status, created_at, updated_at) VALUES ('p1', 'Launch', '2026-03-01', 'active', 'x', 'x');
+             INSERT INTO workflow_statuses (id, plan_id, name, category, position, created_at) VALUES
+                ('w1', 'p1', 'backlog', 'todo', 0, 'x'), ('w2', 'p1', 'doing', 'doing', 1, 'x'), ('w3', 'p1', 'shipped', 'done', 2, 'x');
+             INSERT INTO tasks (id, plan_id, parent_task_id, title, start_date, status, priority, created_at, updated_at) VALUES
+                ('a', 'p1', NULL, 'Build', '2026-03-02', 'shipped', 'P1', 'x', 'x'),
+                ('b', 'p1', 'a', 'Test', '2026-03-04', 'doing', 'P2', 'x', 'x');
+             INSERT INTO milestones (id, title, target_date, biz_type, biz_id, status, progress, created_at, updated_at) VALUES ('m1', 'Beta', '2026-03-05', 'task', 'a', 'completed', 100, 'x', 'x');
+             INSERT INTO notification_settings (id, entity_type, entity_id, reminder_minutes, reminder_sent, created_at, updated_at) VALUES
+                ('n1', 'task', 'b', 30, 1, 'x', 'x'), ('n2', 'milestone', 'm1', 60, 1, 'x', 'x');
+             INSERT INTO targets (id, title, status, created_at, updated_at) VALUES ('g1', 'Fitness', 'completed', 'x', 'x');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES
+                ('s1', 'g1', 'Run', 60, 'completed', 'P1', 'x', 'x'), ('s2', 'g1', 'Swim', 40, 'completed', 'P2', 'x', 'x');
+             INSERT INTO tags (id, name, created_at) VALUES ('t1', 'health', 'x');
+             INSERT INTO entity_tags (entity_type, entity_id, tag_id) VALUES ('target', 'g1', 't1');",
+        )
+        .unwrap();
+
+        let plan_id = duplicate_plan_tree(&conn, "p1", None, None).unwrap();
+        let title: String = conn
+            .query_row("SELECT title FROM plans WHERE id = ?", [&plan_id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(title, "Launch (copy)");
+
+        // Statuses reset to the first todo status of the copied workflow
+        let (statuses, parent_ok): (String, i32) = conn
+            .query_row(
+                "SELECT GROUP_CONCAT(DISTINCT status), SUM(parent_task_id IN (SELECT id FROM tasks WHERE plan_id = ?1)) FROM tasks WHERE plan_id = ?1",
+                [&plan_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(statuses, "backlog");
+        assert_eq!(parent_ok, 1);
+
+        let (milestone_id, milestone_status): (String, String) = conn
+            .query_row(
+                "SELECT id, status FROM milestones WHERE biz_id IN (SELECT id FROM tasks WHERE plan_id = ?)",
+                [&plan_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(milestone_status, "pending");
+
+        let reminders: (i32, i32, i32) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(reminder_sent), SUM(entity_id = ?) FROM notification_settings",
+                [&milestone_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(reminders, (4, 2, 1));
+
+        let target_id =
+            duplicate_target_tree(&conn, "g1", Some("Fitness 2027".to_string())).unwrap();
+        let steps: (i32, i32, i32) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(status = 'pending'), SUM(weight) FROM steps WHERE target_id = ?",
+                [&target_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(steps, (2, 2, 100));
+        let (status, tagged): (String, i32) = conn
+            .query_row(
+                "SELECT status, (SELECT COUNT(*) FROM entity_tags WHERE entity_id = ?1) FROM targets WHERE id = ?1",
+                [&target_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), tagged), ("active", 1));
+    }
+
+    #[test]
+    fn test_burndown_from_status_history() {
+        use crate::commands::burndown::{plan_burndown, target_burnup};
+        use chrono::NaiveDate;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, start_date, end_date, status, created_at, updated_at) VALUES ('p1', 'Sprint', '2026-05-01', '2026-05-05', 'active', '2026-05-01T08:00:00Z', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, created_at, updated_at) VALUES
+                ('a', 'p1', 'A', 'done', 'P2', '2026-05-01T08:00:00Z', '2026-05-02T10:00:00Z'),
+                ('b', 'p1', 'B', 'pending', 'P2', '2026-05-01T08:00:00Z', '2026-05-04T10:00:00Z'),
+                ('c', 'p1', 'C', 'pending', 'P2', '2026-05-03T08:00:00Z', '2026-05-03T08:00:00Z');
+             INSERT INTO status_history (id, entity_type, entity_id, from_status, to_status, category, changed_at) VALUES
+                ('h1', 'task', 'b', 'pending', 'done', 'done', '2026-05-02T09:00:00Z'),
+                ('h2', 'task', 'b', 'done', 'pending', 'todo', '2026-05-04T10:00:00Z');
+             INSERT INTO targets (id, title, due_date, status, created_at, updated_at) VALUES ('g1', 'Read', '2026-05-04', 'active', '2026-05-01T08:00:00Z', 'x');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES
+                ('s1', 'g1', 'Part 1', 30, 'completed', 'P2', '2026-05-01T08:00:00Z', '2026-05-03T08:00:00Z'),
+                ('s2', 'g1', 'Part 2', 70, 'pending', 'P2', '2026-05-01T08:00:00Z', '2026-05-01T08:00:00Z');",
+        )
+        .unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2026, 5, 4).unwrap();
+        let burndown = plan_burndown(&conn, "p1", today).unwrap();
+        assert_eq!(burndown.points.len(), 5);
+        let remaining: Vec<Option<i64>> = burndown.points.iter().map(|p| p.remaining).collect();
+        // A done (no history, last update) on 05-02, B done 05-02 and reopened 05-04,
+        // C added on 05-03, 05-05 is in the future
+        assert_eq!(remaining, vec![Some(2), Some(0), Some(1), Some(2), None]);
+        assert_eq!(burndown.points[0].ideal, 3.0);
+        assert_eq!(burndown.points[4].ideal, 0.0);
+
+        let burnup = target_burnup(&conn, "g1", today).unwrap();
+        let completed: Vec<Option<i64>> = burnup.points.iter().map(|p| p.completed).collect();
+        assert_eq!(completed, vec![Some(0), Some(0), Some(30), Some(30)]);
+        assert_eq!(burnup.points[3].ideal, 100.0);
+        assert_eq!(burnup.unit, "weight");
+
+        // Far-off end dates are refused instead of building a huge series
+        conn.execute(
+            "UPDATE plans SET end_date = '9999-12-31' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+        assert!(plan_burndown(&conn, "p1", today).is_err());
+    }
+
+    #[test]
+    fn test_sprint_carry_over_and_velocity() {
+        use crate::commands::sprints::{assign_sprint, close_sprint_and_carry_over};
+        use crate::commands::statistics::get_velocity_stats;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, start_date, end_date, status, created_at, updated_at) VALUES ('p1', 'Release', '2026-07-01', '2026-07-31', 'active', 'x', 'x');
+             INSERT INTO sprints (id, plan_id, name, start_date, end_date, status, created_at, updated_at) VALUES
+                ('s1', 'p1', 'Sprint 1', '2026-07-01', '2026-07-14', 'active', 'x', 'x'),
+                ('s2', 'p1', 'Sprint 2', '2026-07-15', '2026-07-28', 'planned', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, estimate, estimate_unit, created_at, updated_at) VALUES
+                ('a', 'p1', 'A', 'done', 'P2', 3, 'points', 'x', 'x'),
+                ('b', 'p1', 'B', 'in-progress', 'P2', 5, 'points', 'x', 'x'),
+                ('c', 'p1', 'C', 'pending', 'P2', NULL, NULL, 'x', 'x');
+             INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p2', 'Other', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, priority, created_at, updated_at) VALUES ('z', 'p2', 'Z', 'pending', 'P2', 'x', 'x');",
+        )
+        .unwrap();
+
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            assign_sprint(&conn, &ids(&["a", "b"]), Some("s1")).unwrap(),
+            2
+        );
+        assert!(assign_sprint(&conn, &ids(&["z"]), Some("s1")).is_err());
+
+        let result = close_sprint_and_carry_over(&conn, "s1", None).unwrap();
+        assert_eq!(result.sprint.status, "closed");
+        assert_eq!(result.next_sprint_id.as_deref(), Some("s2"));
+        assert_eq!(result.carried_over, vec!["b".to_string()]);
+        assert!(assign_sprint(&conn, &ids(&["c"]), Some("s1")).is_err());
+
+        let velocity = get_velocity_stats(&conn, Some("p1")).unwrap();
+        assert_eq!(velocity.sprints.len(), 2);
+        assert_eq!(velocity.sprints[0].completed, 1);
+        assert_eq!(velocity.sprints[0].completed_points, 3.0);
+        assert_eq!(velocity.sprints[1].remaining, 1);
+        assert_eq!(velocity.average_completed, 1.0);
+
+        // The last sprint carries over to the backlog
+        let result = close_sprint_and_carry_over(&conn, "s2", None).unwrap();
+        assert_eq!(result.next_sprint_id, None);
+        let sprint_id: Option<String> = conn
+            .query_row("SELECT sprint_id FROM tasks WHERE id = 'b'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(sprint_id, None);
+    }
+
+    #[test]
+    fn test_workload_per_day() {
+        use crate::commands::workload::{compute_workload, load_workload_settings};
+        use chrono::NaiveDate;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        // 2026-08-07 is a Friday
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p1', 'Plan', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, start_date, end_date, status, priority, created_at, updated_at) VALUES
+                ('a', 'p1', 'Spread', '2026-08-06', '2026-08-10', 'pending', 'P0', 'x', 'x'),
+                ('b', 'p1', 'Single', NULL, '2026-08-07', 'in-progress', 'P1', 'x', 'x'),
+                ('c', 'p1', 'Finished', '2026-08-07', '2026-08-07', 'done', 'P0', 'x', 'x');
+             INSERT INTO todos (id, title, due_date, status, priority, created_at, updated_at) VALUES
+                ('t1', 'Call', '2026-08-07', 'pending', 'P2', 'x', 'x'),
+                ('t2', 'Old', '2026-08-07', 'done', 'P0', 'x', 'x');",
+        )
+        .unwrap();
+
+        let mut settings = load_workload_settings(&conn).unwrap();
+        assert_eq!(settings.daily_capacity, 8.0);
+        let from = NaiveDate::from_ymd_opt(2026, 8, 6).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 8, 10).unwrap();
+
+        // Task a: weight 4 over Thu, Fri, Mon
+        let workload = compute_workload(&conn, from, to, &settings).unwrap();
+        let loads: Vec<f64> = workload.days.iter().map(|d| d.load).collect();
+        assert_eq!(loads, vec![1.33, 6.33, 0.0, 0.0, 1.33]);
+        assert_eq!(workload.days[1].items.len(), 3);
+        assert!(workload.overloaded_days.is_empty());
+
+        settings.daily_capacity = 5.5;
+        settings.include_weekends = true;
+        let workload = compute_workload(&conn, from, to, &settings).unwrap();
+        assert_eq!(workload.days[2].load, 0.8);
+        assert_eq!(workload.overloaded_days, vec!["2026-08-07".to_string()]);
+
+        assert!(compute_workload(&conn, to, from, &settings).is_err());
+
+        // Ranges reaching outside the window keep their whole-range share,
+        // however long they are
+        conn.execute_batch(
+            "DELETE FROM tasks;
+             INSERT INTO tasks (id, plan_id, title, start_date, end_date, status, priority, created_at, updated_at) VALUES
+                ('week', 'p1', 'Week', '2026-08-03', '2026-08-07', 'pending', 'P0', 'x', 'x'),
+                ('ages', 'p1', 'Ages', '0001-01-01', '9999-12-31', 'pending', 'P0', 'x', 'x');",
+        )
+        .unwrap();
+        settings.include_weekends = false;
+        let workload = compute_workload(&conn, from, to, &settings).unwrap();
+        let loads: Vec<f64> = workload.days.iter().map(|d| d.load).collect();
+        assert_eq!(loads, vec![0.8, 2.8, 0.0, 0.0, 0.0]);
+        assert_eq!(workload.days[4].items.len(), 1);
+    }
+
+    #[test]
+    fn test_target_metric_progress() {
+        use crate::commands::step_links::refresh_target_progress;
+        use crate::commands::target_metrics::{get_value_series, value_progress};
+        use crate::commands::targets::{calculate_target_progress, get_target_by_id};
+
+        assert_eq!(value_progress(20_000.0, 100_000.0, 60_000.0), 50);
+        assert_eq!(value_progress(80.0, 70.0, 77.5), 25); // Decreasing goal
+        assert_eq!(value_progress(80.0, 70.0, 85.0), 0);
+        assert_eq!(value_progress(0.0, 10.0, 12.0), 100);
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO targets (id, title, status, created_at, updated_at, metric_unit, metric_start, metric_goal, metric_direction) VALUES ('g1', 'MAU 100k', 'active', 'x', 'x', 'users', 20000, 100000, 'increase');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES ('s1', 'g1', 'Launch', 100, 'completed', 'P2', 'x', 'x');",
+        )
+        .unwrap();
+
+        // No value yet: the start value counts, steps are ignored
+        assert_eq!(calculate_target_progress(&conn, "g1").unwrap(), 0);
+
+        conn.execute_batch(
+            "INSERT INTO target_values (id, target_id, value, recorded_at, created_at) VALUES
+                ('v2', 'g1', 60000, '2026-02-01T00:00:00Z', 'x'),
+                ('v1', 'g1', 40000, '2026-01-01T00:00:00Z', 'x');",
+        )
+        .unwrap();
+        let target = get_target_by_id(&conn, "g1").unwrap();
+        assert_eq!(target.progress, 50);
+        assert_eq!(target.metric_value, Some(60000.0));
+        refresh_target_progress(&conn, "g1").unwrap();
+        let stored: i32 = conn
+            .query_row("SELECT progress FROM targets WHERE id = 'g1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(stored, 50);
+
+        let series = get_value_series(&conn, "g1").unwrap();
+        let progress: Vec<i32> = series.points.iter().map(|p| p.progress).collect();
+        assert_eq!(progress, vec![25, 50]);
+
+        // Back to step mode
+        conn.execute("UPDATE targets SET metric_goal = NULL WHERE id = 'g1'", [])
+            .unwrap();
+        assert_eq!(calculate_target_progress(&conn, "g1").unwrap(), 100);
+        assert!(get_value_series(&conn, "g1").is_err());
+    }
+
+    #[test]
+    fn test_step_weight_modes() {
+        use crate::commands::step_weights::{
+            equal_weights, normalize_weights, rebalance_weights, scale_weights,
+        };
+        use crate::commands::targets::calculate_target_progress;
+
+        assert_eq!(equal_weights(3, 100), vec![34, 33, 33]);
+        assert_eq!(scale_weights(&[20, 20, 10], 100), vec![40, 40, 20]);
+        assert_eq!(scale_weights(&[1, 1, 1], 50), vec![17, 17, 16]);
+        assert_eq!(scale_weights(&[0, 0], 100), vec![50, 50]);
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO targets (id, title, status, created_at, updated_at) VALUES ('g1', 'Ship', 'active', 'x', 'x');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, position, created_at, updated_at) VALUES
+                ('s1', 'g1', 'Design', 20, 'completed', 'P2', 'a0', 'x', 'x'),
+                ('s2', 'g1', 'Build', 20, 'pending', 'P2', 'a1', 'x', 'x'),
+                ('s3', 'g1', 'Test', 10, 'pending', 'P2', 'a2', 'x', 'x');",
+        )
+        .unwrap();
+        let weights = |conn: &Connection| -> Vec<i32> {
+            let mut stmt = conn
+                .prepare("SELECT weight FROM steps WHERE target_id = 'g1' ORDER BY position")
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|w| w.unwrap())
+                .collect()
+        };
+
+        // Weights summing to 50 still give a share of the total
+        assert_eq!(calculate_target_progress(&conn, "g1").unwrap(), 40);
+
+        // Manual mode keeps weights as entered
+        rebalance_weights(&conn, "g1", None).unwrap();
+        assert_eq!(weights(&conn), vec![20, 20, 10]);
+        normalize_weights(&conn, "g1").unwrap();
+        assert_eq!(weights(&conn), vec![40, 40, 20]);
+
+        // Proportional: the reweighted step keeps its weight, the rest share the remainder
+        conn.execute_batch(
+            "UPDATE targets SET weight_mode = 'proportional' WHERE id = 'g1';
+             UPDATE steps SET weight = 70 WHERE id = 's3';",
+        )
+        .unwrap();
+        rebalance_weights(&conn, "g1", Some("s3")).unwrap();
+        assert_eq!(weights(&conn), vec![15, 15, 70]);
+
+        // Equal: a removed step's share goes to the others
+        conn.execute_batch(
+            "UPDATE targets SET weight_mode = 'equal' WHERE id = 'g1';
+             DELETE FROM steps WHERE id = 's2';",
+        )
+        .unwrap();
+        rebalance_weights(&conn, "g1", None).unwrap();
+        assert_eq!(weights(&conn), vec![50, 50]);
+        assert_eq!(calculate_target_progress(&conn, "g1").unwrap(), 50);
+
+        // Steps without weight count equally
+        conn.execute("UPDATE steps SET weight = 0 WHERE target_id = 'g1'", [])
+            .unwrap();
+        assert_eq!(calculate_target_progress(&conn, "g1").unwrap(), 50);
+    }
+
+    #[test]
+    fn test_step_links_drive_progress() {
+        use crate::commands::export::ExportDataContent;
+        use crate::commands::import::import_content;
+        use crate::commands::plans::refresh_plan_progress;
+        use crate::commands::step_links::{
+            delete_entity_step_links, list_step_links, sync_linked_steps, sync_step,
+        };
+        use crate::models::StepLink;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO plans (id, title, status, created_at, updated_at) VALUES ('p1', 'Launch', 'active', 'x', 'x');
+             INSERT INTO tasks (id, plan_id, title, status, created_at, updated_at) VALUES
+                ('t1', 'p1', 'Build', 'pending', 'x', 'x'),
+                ('t2', 'p1', 'Ship', 'pending', 'x', 'x');
+             INSERT INTO todos (id, title, status, created_at, updated_at) VALUES ('d1', 'Write post', 'pending', 'x', 'x');
+             INSERT INTO targets (id, title, status, created_at, updated_at) VALUES ('g1', 'Release', 'active', 'x', 'x');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES
+                ('s1', 'g1', 'Product', 50, 'pending', 'P2', 'x', 'x'),
+                ('s2', 'g1', 'Announce', 50, 'pending', 'P2', 'x', 'x');
+             INSERT INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES
+                ('l1', 's1', 'plan', 'p1', 'x'),
+                ('l2', 's2', 'todo', 'd1', 'x'),
+                ('l3', 's2', 'task', 't1', 'x');",
+        )
+        .unwrap();
+        let state = |conn: &Connection| -> (String, String, i32) {
+            conn.query_row(
+                "SELECT s1.status, s2.status, t.progress FROM steps s1, steps s2, targets t
+                 WHERE s1.id = 's1' AND s2.id = 's2' AND t.id = 'g1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+        };
+
+        // Half of the plan done: step 1 counts half its weight
+        conn.execute("UPDATE tasks SET status = 'done' WHERE id = 't1'", [])
+            .unwrap();
+        refresh_plan_progress(&conn, "p1").unwrap();
+        let links = list_step_links(&conn, "s2").unwrap();
+        let task_link = links.iter().find(|l| l.entity_type == "task").unwrap();
+        assert_eq!(task_link.progress, 100);
+        assert_eq!(state(&conn), ("pending".into(), "pending".into(), 50));
+
+        // All linked work of step 2 done: it completes
+        conn.execute("UPDATE todos SET status = 'done' WHERE id = 'd1'", [])
+            .unwrap();
+        sync_linked_steps(&conn, "todo", "d1").unwrap();
+        assert_eq!(state(&conn), ("pending".into(), "completed".into(), 75));
+
+        conn.execute("UPDATE tasks SET status = 'done' WHERE id = 't2'", [])
+            .unwrap();
+        refresh_plan_progress(&conn, "p1").unwrap();
+        assert_eq!(state(&conn), ("completed".into(), "completed".into(), 100));
+
+        // Reopened work reopens the step
+        conn.execute("UPDATE tasks SET status = 'pending' WHERE id = 't1'", [])
+            .unwrap();
+        refresh_plan_progress(&conn, "p1").unwrap();
+        assert_eq!(state(&conn), ("pending".into(), "pending".into(), 50));
+
+        // Deleting linked work leaves the remaining links in charge
+        conn.execute("DELETE FROM tasks WHERE id = 't1'", [])
+            .unwrap();
+        delete_entity_step_links(&conn, "task", "t1").unwrap();
+        sync_step(&conn, "s1").unwrap();
+        assert_eq!(list_step_links(&conn, "s2").unwrap().len(), 1);
+        assert_eq!(state(&conn).1, "completed");
+
+        // Unknown link types are skipped instead of failing the list
+        conn.execute(
+            "INSERT INTO step_links (id, step_id, entity_type, entity_id, created_at) VALUES ('l9', 's2', 'note', 'n1', 'y')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(list_step_links(&conn, "s2").unwrap().len(), 1);
+
+        // Imported links need a known type and an existing step
+        let link = |id: &str, step_id: &str, entity_type: &str| StepLink {
+            id: id.to_string(),
+            step_id: step_id.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id: "d1".to_string(),
+            created_at: "z".to_string(),
+        };
+        let data = ExportDataContent {
+            step_links: vec![
+                link("i1", "s1", "todo"),
+                link("i2", "s1", "note"),
+                link("i3", "gone", "todo"),
+            ],
+            ..Default::default()
+        };
+        for mode in ["merge", "update"] {
+            let result = import_content(&conn, &data, mode).unwrap();
+            assert_eq!(result.errors.len(), 2, "{}: {:?}", mode, result.errors);
+            let imported: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM step_links WHERE id LIKE 'i%'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(imported, 1);
+        }
+    }
+
+    #[test]
+    fn test_target_forecast() {
+        use crate::commands::dashboard::get_active_targets;
+        use crate::commands::forecast::{forecast, linear_fit};
+        use chrono::NaiveDate;
+
+        let (slope, intercept) = linear_fit(&[(0.0, 10.0), (1.0, 20.0), (2.0, 30.0)]).unwrap();
+        assert!((slope - 10.0).abs() < 1e-9 && (intercept - 10.0).abs() < 1e-9);
+        assert!(linear_fit(&[(1.0, 5.0)]).is_none());
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO targets (id, title, due_date, status, created_at, updated_at) VALUES ('g1', 'Course', '2026-05-10', 'active', '2026-05-01T08:00:00Z', 'x');
+             INSERT INTO steps (id, target_id, title, weight, status, priority, created_at, updated_at) VALUES
+                ('s1', 'g1', 'Week 1', 25, 'completed', 'P2', '2026-05-01T08:00:00Z', '2026-05-02T08:00:00Z'),
+                ('s2', 'g1', 'Week 2', 25, 'completed', 'P2', '2026-05-01T08:00:00Z', '2026-05-03T08:00:00Z'),
+                ('s3', 'g1', 'Week 3', 25, 'completed', 'P2', '2026-05-01T08:00:00Z', '2026-05-04T08:00:00Z'),
+                ('s4', 'g1', 'Week 4', 25, 'pending', 'P2', '2026-05-01T08:00:00Z', '2026-05-01T08:00:00Z');",
+        )
+        .unwrap();
+
+        // 25 points a day from 0% on 05-01: done on 05-05
+        let today = NaiveDate::from_ymd_opt(2026, 5, 4).unwrap();
+        let result = forecast(&conn, "g1", today).unwrap();
+        assert_eq!(result.progress, 75);
+        assert_eq!(result.history.len(), 4);
+        assert_eq!(result.daily_rate, 25.0);
+        assert_eq!(result.estimated_completion.as_deref(), Some("2026-05-05"));
+        assert_eq!(result.status, "on_track");
+
+        let status_with_due = |due: &str| {
+            conn.execute("UPDATE targets SET due_date = ? WHERE id = 'g1'", [due])
+                .unwrap();
+            forecast(&conn, "g1", today).unwrap().status
+        };
+        assert_eq!(status_with_due("2026-05-04"), "at_risk");
+        assert_eq!(status_with_due("2026-04-25"), "off_track");
+
+        // No progress at all: off track once there is history
+        conn.execute(
+            "UPDATE steps SET status = 'pending' WHERE target_id = 'g1'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(status_with_due("2026-05-10"), "off_track");
+        conn.execute("UPDATE targets SET due_date = NULL WHERE id = 'g1'", [])
+            .unwrap();
+        assert_eq!(forecast(&conn, "g1", today).unwrap().status, "unknown");
+
+        // The dashboard falls back to the stored progress when a target
+        // cannot be forecast
+        conn.execute(
+            "UPDATE targets SET progress = 40, description = X'00' WHERE id = 'g1'",
+            [],
+        )
+        .unwrap();
+        assert!(forecast(&conn, "g1", today).is_err());
+        let targets = get_active_targets(&conn).unwrap();
+        assert_eq!(targets[0].progress, 40);
+        assert_eq!(targets[0].forecast_status, "unknown");
+    }
+
+    #[test]
+    fn test_okr_report_and_grading() {
+        use crate::commands::okrs::{grade_scores, okr_report};
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO objectives (id, title, period, parent_id, status, created_at, updated_at) VALUES
+                ('o1', 'Grow the product', '2026-Q3', NULL, 'active', '2026-07-01T00:00:00Z', 'x'),
+                ('o2', 'Ship mobile app', '2026-Q3', 'o1', 'active', '2026-07-02T00:00:00Z', 'x'),
+                ('o3', 'Next year', '2027-Q1', NULL, 'active', '2026-07-03T00:00:00Z', 'x');
+             INSERT INTO key_results (id, objective_id, title, kind, start_value, target_value, current_value, score, confidence, created_at, updated_at) VALUES
+                ('k1', 'o1', 'MAU to 50k', 'metric', 10000, 50000, 40000, NULL, 0.8, '1', 'x'),
+                ('k2', 'o1', 'NPS survey', 'manual', NULL, NULL, NULL, 0.5, 0.3, '2', 'x'),
+                ('k3', 'o2', 'App store launch', 'manual', NULL, NULL, NULL, 0.2, 0.6, '3', 'x');",
+        )
+        .unwrap();
+
+        let report = okr_report(&conn, "2026-Q3").unwrap();
+        assert_eq!(report.objectives.len(), 1); // o2 is nested under o1
+        let o1 = &report.objectives[0];
+        let scores: Vec<f64> = o1.key_results.iter().map(|k| k.score).collect();
+        assert_eq!(scores, vec![0.75, 0.5]);
+        assert_eq!(o1.score, Some(0.63));
+        assert_eq!(o1.grade.as_deref(), Some("yellow"));
+        assert_eq!(o1.children[0].objective.id, "o2");
+        assert_eq!(o1.children[0].grade.as_deref(), Some("red"));
+        assert_eq!(report.average_score, Some(0.42)); // (0.63 + 0.2) / 2
+        assert_eq!(report.at_risk_key_results, vec!["k2".to_string()]);
+
+        // Grading locks the scores: later check-ins do not change them
+        grade_scores(&conn, "o1", Some("Good quarter")).unwrap();
+        assert!(grade_scores(&conn, "o1", None).is_err());
+        conn.execute(
+            "UPDATE key_results SET current_value = 50000 WHERE id = 'k1'",
+            [],
+        )
+        .unwrap();
+        let report = okr_report(&conn, "2026-Q3").unwrap();
+        assert_eq!(report.objectives[0].key_results[0].score, 0.75);
+        assert_eq!(report.graded_count, 1);
+        assert_eq!(report.objectives[0].score, Some(0.63));
+        assert!(report.at_risk_key_results.is_empty());
+    }
+
+    #[test]
+    fn test_objective_alignment_cycles() {
+        use crate::commands::export::ExportDataContent;
+        use crate::commands::import::import_content;
+        use crate::commands::okrs::okr_report;
+        use crate::models::Objective;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+
+        // A cycle already in the database does not hang the report
+        conn.execute_batch(
+            "INSERT INTO objectives (id, title, period, parent_id, status, created_at, updated_at) VALUES
+                ('c1', 'One', '2026-Q3', 'c2', 'active', '1', 'x'),
+                ('c2', 'Two', '2026-Q3', 'c1', 'active', '2', 'x');",
+        )
+        .unwrap();
+        okr_report(&conn, "2026-Q3").unwrap();
+
+        let objective = |id: &str, parent: Option<&str>| Objective {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: None,
+            period: "2026-Q3".to_string(),
+            parent_id: parent.map(|p| p.to_string()),
+            status: "active".to_string(),
+            final_score: None,
+            grade_note: None,
+            graded_at: None,
+            created_at: "x".to_string(),
+            updated_at: "x".to_string(),
+        };
+        let data = ExportDataContent {
+            objectives: vec![
+                objective("a", Some("b")),
+                objective("b", Some("a")),
+                objective("m", Some("missing")),
+            ],
+            ..Default::default()
+        };
+        for mode in ["replace", "update"] {
+            let result = import_content(&conn, &data, mode).unwrap();
+            assert_eq!(result.errors.len(), 2, "{}: {:?}", mode, result.errors);
+            let parents: Vec<Option<String>> = conn
+                .prepare("SELECT parent_id FROM objectives ORDER BY id")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(parents, vec![None, Some("a".to_string()), None]);
+        }
+    }
 }